@@ -23,7 +23,7 @@ async fn main() {
             println!("Found {} deals:", deals.len());
             for (i, deal) in deals.iter().enumerate() {
                 println!(
-                    "{}. {} - ${:.2} (-{}%) @ {} (ID: {})",
+                    "{}. {} - {} (-{}%) @ {} (ID: {})",
                     i + 1,
                     deal.title,
                     deal.price.amount,
@@ -1,7 +1,14 @@
-use reqwest::Client;
+use dealve_core::{DealveError, Result};
+use reqwest::{Client, RequestBuilder, Response};
+use std::time::Instant;
 
 const API_BASE_URL: &str = "https://api.isthereanydeal.com";
 
+/// Target of the event logged for every request, with the `endpoint`,
+/// `status` (absent when the request failed), `latency_ms`, a redacted `key`
+/// and the `error` if any
+pub const REQUEST_LOG_TARGET: &str = "dealve_api::request";
+
 pub struct ItadClient {
     client: Client,
     api_key: Option<String>,
@@ -27,3 +34,80 @@ impl ItadClient {
         self.api_key.as_deref()
     }
 }
+
+/// Remove the API key from text that may echo a request, like error bodies
+pub(crate) fn redact(text: &str, api_key: &str) -> String {
+    if api_key.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(api_key, "<redacted>")
+    }
+}
+
+/// Fingerprint of the API key, enough to tell keys apart in the logs without
+/// revealing any part of them: 32 bits of its FNV-1a hash
+pub fn redact_key(api_key: &str) -> String {
+    let hash = api_key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("key-{:08x}", hash >> 32)
+}
+
+/// Send a request and log it. The URL is never logged or put in errors since
+/// its query string holds the API key.
+pub(crate) trait SendLogged {
+    async fn send_logged(self, endpoint: &'static str, api_key: &str) -> Result<Response>;
+}
+
+impl SendLogged for RequestBuilder {
+    async fn send_logged(self, endpoint: &'static str, api_key: &str) -> Result<Response> {
+        let started = Instant::now();
+        let result = self.send().await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let key = redact_key(api_key);
+
+        match result {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                if response.status().is_success() {
+                    tracing::info!(target: REQUEST_LOG_TARGET, endpoint, status, latency_ms, key, "request");
+                } else {
+                    tracing::warn!(target: REQUEST_LOG_TARGET, endpoint, status, latency_ms, key, "request failed");
+                }
+                Ok(response)
+            }
+            Err(e) => {
+                let error = e.without_url().to_string();
+                tracing::warn!(target: REQUEST_LOG_TARGET, endpoint, latency_ms, key, error, "request failed");
+                Err(DealveError::Network(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_logged_as_fingerprints() {
+        let key = "3f2a9c1e-7b4d-4e8a-9f6b-0c5d2e1a8b7c";
+        let fingerprint = redact_key(key);
+        assert_eq!(fingerprint, redact_key(key));
+        assert_ne!(
+            fingerprint,
+            redact_key("3f2a9c1e-7b4d-4e8a-9f6b-0c5d2e1a8b7d")
+        );
+        assert!(fingerprint.starts_with("key-") && fingerprint.len() == 12);
+        assert!(!fingerprint.contains("8b7c"));
+    }
+
+    #[test]
+    fn the_key_is_removed_from_echoed_text() {
+        assert_eq!(
+            redact("GET /deals?key=secret failed", "secret"),
+            "GET /deals?key=<redacted> failed"
+        );
+        assert_eq!(redact("no key", ""), "no key");
+    }
+}
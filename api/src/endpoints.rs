@@ -1,11 +1,12 @@
 use crate::{
-    client::ItadClient,
+    client::{redact, ItadClient, SendLogged},
     types::{
-        DealInfo, DealsResponse, GameInfoResponse, GamePriceItem, GameSearchItem, PriceHistoryItem,
+        DealInfo, DealsResponse, GameInfoResponse, GameOverviewResponse, GamePriceItem,
+        GameSearchItem, PriceHistoryItem, PriceInfo,
     },
 };
 use dealve_core::{
-    models::{Deal, GameInfo, PriceHistoryPoint},
+    models::{Deal, GameInfo, PriceHistoryPoint, Region, RegionalOffer, RegionalPrice},
     DealveError, Result,
 };
 use std::{cmp::Ordering, collections::HashMap};
@@ -44,16 +45,16 @@ impl ItadClient {
             .client()
             .get(&url)
             .query(&query_params)
-            .send()
-            .await
-            .map_err(|e| DealveError::Network(e.to_string()))?;
+            .send_logged("deals/v2", api_key)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
             return Err(DealveError::Api(format!(
                 "API returned status {}: {}",
-                status, body
+                status,
+                redact(&body, api_key)
             )));
         }
 
@@ -62,7 +63,11 @@ impl ItadClient {
             .await
             .map_err(|e| DealveError::Parse(e.to_string()))?;
 
-        Ok(deals_response.list.into_iter().map(Deal::from).collect())
+        deals_response
+            .list
+            .into_iter()
+            .map(Deal::try_from)
+            .collect()
     }
 
     pub async fn get_game_info(&self, game_id: &str) -> Result<GameInfo> {
@@ -76,16 +81,16 @@ impl ItadClient {
             .client()
             .get(&url)
             .query(&[("key", api_key), ("id", game_id)])
-            .send()
-            .await
-            .map_err(|e| DealveError::Network(e.to_string()))?;
+            .send_logged("games/info/v2", api_key)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
             return Err(DealveError::Api(format!(
                 "API returned status {}: {}",
-                status, body
+                status,
+                redact(&body, api_key)
             )));
         }
 
@@ -117,16 +122,16 @@ impl ItadClient {
             .client()
             .get(&url)
             .query(&query_params)
-            .send()
-            .await
-            .map_err(|e| DealveError::Network(e.to_string()))?;
+            .send_logged("games/search/v1", api_key)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
             return Err(DealveError::Api(format!(
                 "API returned status {}: {}",
-                status, body
+                status,
+                redact(&body, api_key)
             )));
         }
 
@@ -169,16 +174,16 @@ impl ItadClient {
             .post(&url)
             .query(&query_params)
             .json(ids)
-            .send()
-            .await
-            .map_err(|e| DealveError::Network(e.to_string()))?;
+            .send_logged("games/prices/v3", api_key)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
             return Err(DealveError::Api(format!(
                 "API returned status {}: {}",
-                status, body
+                status,
+                redact(&body, api_key)
             )));
         }
 
@@ -213,16 +218,14 @@ impl ItadClient {
                 continue;
             }
             ids.push(result.id.clone());
-            titles_by_id.insert(result.id, result.title);
+            titles_by_id.insert(result.id, (result.title, result.slug));
         }
 
         let prices = self.get_prices_for_games(&ids, country, shop_id).await?;
-        let mut deals_by_id: HashMap<String, (DealInfo, Option<f64>)> = HashMap::new();
+        let mut deals_by_id: HashMap<String, (DealInfo, Option<PriceInfo>)> = HashMap::new();
 
         for price_item in prices {
-            let history_low = price_item
-                .history_low
-                .and_then(|h| h.all.map(|price| price.amount));
+            let history_low = price_item.history_low.and_then(|h| h.all);
 
             if let Some(best_deal) = select_best_deal(price_item.deals) {
                 deals_by_id.insert(price_item.id, (best_deal, history_low));
@@ -235,28 +238,113 @@ impl ItadClient {
                 continue;
             };
 
-            let title = titles_by_id.remove(&id).unwrap_or_else(|| id.clone());
+            let (title, slug) = titles_by_id
+                .remove(&id)
+                .unwrap_or_else(|| (id.clone(), None));
             deals.push(Deal {
                 id,
                 title,
+                slug,
                 shop: dealve_core::models::Shop {
                     id: deal_info.shop.id.to_string(),
                     name: deal_info.shop.name,
                 },
                 price: dealve_core::models::Price {
-                    amount: deal_info.price.amount,
-                    currency: deal_info.price.currency,
+                    amount: deal_info.price.money()?,
                     discount: deal_info.cut,
                 },
-                regular_price: deal_info.regular.amount,
+                regular_price: deal_info.regular.money()?,
                 url: deal_info.url,
-                history_low: history_low.or_else(|| deal_info.history_low.map(|h| h.amount)),
+                history_low: history_low
+                    .or(deal_info.history_low)
+                    .map(|low| low.money())
+                    .transpose()?,
+                store_low: deal_info.store_low.map(|low| low.money()).transpose()?,
+                expiry: deal_info.expiry,
             });
         }
 
         Ok(deals)
     }
 
+    /// Number of bundles each game has been in, by game id. Games the API
+    /// doesn't know are left out.
+    pub async fn get_bundle_counts(&self, ids: &[String]) -> Result<HashMap<String, u32>> {
+        let api_key = self
+            .api_key()
+            .ok_or_else(|| DealveError::Config("API key is required".to_string()))?;
+
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let url = format!("{}/games/overview/v2", self.base_url());
+
+        let response = self
+            .client()
+            .post(&url)
+            .query(&[("key", api_key)])
+            .json(ids)
+            .send_logged("games/overview/v2", api_key)
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(DealveError::Api(format!(
+                "API returned status {}: {}",
+                status,
+                redact(&body, api_key)
+            )));
+        }
+
+        let overview: GameOverviewResponse = response
+            .json()
+            .await
+            .map_err(|e| DealveError::Parse(e.to_string()))?;
+
+        Ok(overview
+            .prices
+            .into_iter()
+            .filter_map(|item| Some((item.id, item.bundled?)))
+            .collect())
+    }
+
+    /// Cheapest current offer for a game in each region, one request per
+    /// region. Stops at the first failed request.
+    pub async fn get_regional_prices(
+        &self,
+        game_id: &str,
+        regions: &[Region],
+    ) -> Result<Vec<RegionalPrice>> {
+        let ids = [game_id.to_string()];
+        let mut prices = Vec::with_capacity(regions.len());
+        for &region in regions {
+            let items = self.get_prices_for_games(&ids, region.code(), None).await?;
+            let offer = items
+                .into_iter()
+                .find(|item| item.id == game_id)
+                .and_then(|item| select_best_deal(item.deals))
+                .map(|deal| -> Result<RegionalOffer> {
+                    Ok(RegionalOffer {
+                        shop: dealve_core::models::Shop {
+                            id: deal.shop.id.to_string(),
+                            name: deal.shop.name,
+                        },
+                        price: dealve_core::models::Price {
+                            amount: deal.price.money()?,
+                            discount: deal.cut,
+                        },
+                        regular_price: deal.regular.money()?,
+                        url: deal.url,
+                    })
+                })
+                .transpose()?;
+            prices.push(RegionalPrice { region, offer });
+        }
+        Ok(prices)
+    }
+
     /// Get price history for a game (max 1 year of data)
     pub async fn get_price_history(
         &self,
@@ -282,16 +370,16 @@ impl ItadClient {
                 ("country", country),
                 ("since", since.as_str()),
             ])
-            .send()
-            .await
-            .map_err(|e| DealveError::Network(e.to_string()))?;
+            .send_logged("games/history/v2", api_key)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
             return Err(DealveError::Api(format!(
                 "API returned status {}: {}",
-                status, body
+                status,
+                redact(&body, api_key)
             )));
         }
 
@@ -311,7 +399,7 @@ impl ItadClient {
                     .timestamp();
                 Some(PriceHistoryPoint {
                     timestamp,
-                    price: deal.price.amount,
+                    price: deal.price.money().ok()?,
                     shop_name: item.shop.name,
                 })
             })
@@ -332,9 +420,8 @@ impl ItadClient {
         let response = client
             .get(url)
             .query(&[("key", api_key), ("limit", "1"), ("country", "US")])
-            .send()
-            .await
-            .map_err(|e| DealveError::Network(e.to_string()))?;
+            .send_logged("deals/v2", api_key)
+            .await?;
 
         match response.status().as_u16() {
             200..=299 => Ok(()),
@@ -344,7 +431,10 @@ impl ItadClient {
             )),
             _ => {
                 let body = response.text().await.unwrap_or_default();
-                Err(DealveError::Api(format!("API error: {}", body)))
+                Err(DealveError::Api(format!(
+                    "API error: {}",
+                    redact(&body, api_key)
+                )))
             }
         }
     }
@@ -2,4 +2,4 @@ pub mod client;
 pub mod endpoints;
 pub mod types;
 
-pub use client::ItadClient;
+pub use client::{ItadClient, REQUEST_LOG_TARGET};
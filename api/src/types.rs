@@ -1,3 +1,5 @@
+use dealve_core::money::{Currency, Money};
+use dealve_core::{DealveError, Result};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -9,12 +11,14 @@ pub struct DealsResponse {
 pub struct GameSearchItem {
     pub id: String,
     pub title: String,
+    pub slug: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct DealItem {
     pub id: String,
     pub title: String,
+    pub slug: Option<String>,
     pub deal: DealInfo,
 }
 
@@ -26,7 +30,10 @@ pub struct DealInfo {
     pub cut: u8,
     pub url: String,
     #[serde(rename = "historyLow")]
-    pub history_low: Option<HistoryPrice>,
+    pub history_low: Option<PriceInfo>,
+    #[serde(rename = "storeLow")]
+    pub store_low: Option<PriceInfo>,
+    pub expiry: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,12 +45,21 @@ pub struct ShopInfo {
 #[derive(Debug, Deserialize)]
 pub struct PriceInfo {
     pub amount: f64,
+    /// Amount in minor units, exact where `amount` may not be
+    #[serde(rename = "amountInt")]
+    pub amount_int: Option<i64>,
     pub currency: String,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct HistoryPrice {
-    pub amount: f64,
+impl PriceInfo {
+    pub fn money(&self) -> Result<Money> {
+        let currency = Currency::new(&self.currency)
+            .ok_or_else(|| DealveError::Parse(format!("unknown currency `{}`", self.currency)))?;
+        Ok(match self.amount_int {
+            Some(minor) => Money::new(minor, currency),
+            None => Money::from_major(self.amount, currency),
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -59,24 +75,28 @@ pub struct GamePriceItem {
     pub deals: Vec<DealInfo>,
 }
 
-impl From<DealItem> for dealve_core::models::Deal {
-    fn from(item: DealItem) -> Self {
-        Self {
+impl TryFrom<DealItem> for dealve_core::models::Deal {
+    type Error = DealveError;
+
+    fn try_from(item: DealItem) -> Result<Self> {
+        Ok(Self {
             id: item.id,
             title: item.title,
+            slug: item.slug,
             shop: dealve_core::models::Shop {
                 id: item.deal.shop.id.to_string(),
                 name: item.deal.shop.name,
             },
             price: dealve_core::models::Price {
-                amount: item.deal.price.amount,
-                currency: item.deal.price.currency,
+                amount: item.deal.price.money()?,
                 discount: item.deal.cut,
             },
-            regular_price: item.deal.regular.amount,
+            regular_price: item.deal.regular.money()?,
             url: item.deal.url,
-            history_low: item.deal.history_low.map(|h| h.amount),
-        }
+            history_low: item.deal.history_low.map(|low| low.money()).transpose()?,
+            store_low: item.deal.store_low.map(|low| low.money()).transpose()?,
+            expiry: item.deal.expiry,
+        })
     }
 }
 
@@ -90,6 +110,15 @@ pub struct GameInfoResponse {
     pub developers: Option<Vec<CompanyInfo>>,
     pub publishers: Option<Vec<CompanyInfo>>,
     pub tags: Option<Vec<String>>,
+    pub stats: Option<GameStatsInfo>,
+    pub reviews: Option<Vec<ReviewInfo>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewInfo {
+    pub score: Option<u8>,
+    pub source: String,
+    pub count: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -97,6 +126,13 @@ pub struct CompanyInfo {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GameStatsInfo {
+    pub rank: Option<u32>,
+    pub waitlisted: Option<u32>,
+    pub collected: Option<u32>,
+}
+
 impl From<GameInfoResponse> for dealve_core::models::GameInfo {
     fn from(resp: GameInfoResponse) -> Self {
         Self {
@@ -112,10 +148,43 @@ impl From<GameInfoResponse> for dealve_core::models::GameInfo {
                 .map(|p| p.into_iter().map(|c| c.name).collect())
                 .unwrap_or_default(),
             tags: resp.tags.unwrap_or_default(),
+            stats: resp.stats.map(|s| dealve_core::models::GameStats {
+                rank: s.rank,
+                waitlisted: s.waitlisted,
+                collected: s.collected,
+            }),
+            reviews: resp
+                .reviews
+                .map(|reviews| {
+                    reviews
+                        .into_iter()
+                        .map(|r| dealve_core::models::Review {
+                            score: r.score,
+                            source: r.source,
+                            count: r.count,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            // Comes from the overview endpoint, see `get_bundle_counts`
+            bundled: None,
         }
     }
 }
 
+// Game Overview API response types
+#[derive(Debug, Deserialize)]
+pub struct GameOverviewResponse {
+    pub prices: Vec<GameOverviewItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GameOverviewItem {
+    pub id: String,
+    /// Number of bundles the game has been in
+    pub bundled: Option<u32>,
+}
+
 // Price History API response types
 #[derive(Debug, Deserialize)]
 pub struct PriceHistoryResponse(pub Vec<PriceHistoryItem>);
@@ -145,7 +214,7 @@ impl PriceHistoryItem {
 
         Some(dealve_core::models::PriceHistoryPoint {
             timestamp,
-            price: deal.price.amount,
+            price: deal.price.money().ok()?,
             shop_name: self.shop.name.clone(),
         })
     }
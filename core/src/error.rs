@@ -1,7 +1,8 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Main error type for Dealve
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Serialize, Deserialize)]
 pub enum DealveError {
     #[error("API error: {0}")]
     Api(String),
@@ -14,6 +15,9 @@ pub enum DealveError {
 
     #[error("Configuration error: {0}")]
     Config(String),
+
+    #[error("Exchange rates error: {0}")]
+    Rates(String),
 }
 
 /// Result type alias using DealveError
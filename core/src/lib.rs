@@ -1,4 +1,8 @@
 pub mod error;
 pub mod models;
+pub mod money;
+pub mod query;
+pub mod rates;
+pub mod shell;
 
 pub use error::{DealveError, Result};
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use crate::money::{Currency, Money, MoneyFormat};
+
 /// Supported countries for deal filtering (ISO 3166-1 alpha-2 codes)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum Region {
     // Europe
     AT,
@@ -175,6 +178,53 @@ impl Region {
         }
     }
 
+    /// How prices are written in the region
+    pub fn money_format(&self) -> MoneyFormat {
+        let format = |decimal, group, after, spaced| MoneyFormat {
+            decimal_separator: decimal,
+            group_separator: group,
+            symbol_after: after,
+            symbol_spaced: spaced,
+        };
+        match self {
+            // 1 234,50 €
+            Region::BG
+            | Region::CZ
+            | Region::EE
+            | Region::FI
+            | Region::FR
+            | Region::HU
+            | Region::LT
+            | Region::LV
+            | Region::NO
+            | Region::PL
+            | Region::SE
+            | Region::SK => format(',', Some(' '), true, true),
+            // 1.234,50 €
+            Region::BE
+            | Region::DE
+            | Region::DK
+            | Region::ES
+            | Region::GR
+            | Region::HR
+            | Region::IT
+            | Region::PT
+            | Region::RO => format(',', Some('.'), true, true),
+            // € 1.234,50
+            Region::AT | Region::NL | Region::BR | Region::AR | Region::CO => {
+                format(',', Some('.'), false, true)
+            }
+            // ₺1.234,50
+            Region::TR | Region::ID | Region::CL => format(',', Some('.'), false, false),
+            // CHF 1'234.50
+            Region::CH => format('.', Some('\''), false, true),
+            // R 1 234,50
+            Region::ZA => format(',', Some(' '), false, true),
+            // €1,234.50
+            _ => MoneyFormat::default(),
+        }
+    }
+
     pub fn continent(&self) -> &str {
         match self {
             Region::AT
@@ -341,13 +391,41 @@ impl Region {
             "SA" => Some(Region::SA),
             "TR" => Some(Region::TR),
             "ZA" => Some(Region::ZA),
-            // Handle old config values
-            "EU1" => Some(Region::FR),
-            "EU2" => Some(Region::PL),
-            "UK" => Some(Region::GB),
             _ => None,
         }
     }
+
+    /// Codes from before regions were countries, with the region each became
+    pub const ALIASES: &'static [(&'static str, Region)] =
+        &[("EU1", Region::FR), ("EU2", Region::PL), ("UK", Region::GB)];
+
+    /// Region for a code typed by the user, in any case, old codes included
+    pub fn from_input(input: &str) -> Option<Region> {
+        let code = input.trim().to_uppercase();
+        Region::from_code(&code).or_else(|| Region::from_alias(&code))
+    }
+
+    /// Region an old code stands for, see `ALIASES`
+    pub fn from_alias(code: &str) -> Option<Region> {
+        Region::ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == code)
+            .map(|(_, region)| *region)
+    }
+}
+
+impl TryFrom<String> for Region {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Region::from_code(&code).ok_or_else(|| format!("unknown region `{}`", code))
+    }
+}
+
+impl From<Region> for String {
+    fn from(region: Region) -> Self {
+        region.code().to_string()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -502,11 +580,39 @@ impl Platform {
 pub struct Deal {
     pub id: String,
     pub title: String,
+    /// Name of the game's page on IsThereAnyDeal
+    #[serde(default)]
+    pub slug: Option<String>,
     pub shop: Shop,
     pub price: Price,
-    pub regular_price: f64,
+    pub regular_price: Money,
     pub url: String,
-    pub history_low: Option<f64>,
+    pub history_low: Option<Money>,
+    /// Lowest price ever recorded at this deal's shop
+    #[serde(default)]
+    pub store_low: Option<Money>,
+    /// When the deal ends (RFC 3339), if the shop announced it
+    #[serde(default)]
+    pub expiry: Option<String>,
+}
+
+impl Deal {
+    /// Identity of a deal across loads: the same game can be on sale in several shops
+    pub fn key(&self) -> String {
+        format!("{}@{}", self.id, self.shop.id)
+    }
+
+    /// The game's page on IsThereAnyDeal, when its slug is known
+    pub fn itad_url(&self) -> Option<String> {
+        self.slug
+            .as_ref()
+            .map(|slug| format!("https://isthereanydeal.com/game/{}/info/", slug))
+    }
+
+    /// Whether the current price is the lowest ever recorded
+    pub fn is_historical_low(&self) -> bool {
+        self.history_low.is_some_and(|low| self.price.amount <= low)
+    }
 }
 
 /// Detailed game information
@@ -518,16 +624,61 @@ pub struct GameInfo {
     pub developers: Vec<String>,
     pub publishers: Vec<String>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub stats: Option<GameStats>,
+    /// Review scores, one per site (Steam, Metacritic...)
+    #[serde(default)]
+    pub reviews: Vec<Review>,
+    /// Number of bundles the game has been in, when known
+    #[serde(default)]
+    pub bundled: Option<u32>,
+}
+
+/// A game's review score on one site
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    /// Score out of 100
+    pub score: Option<u8>,
+    pub source: String,
+    /// Number of reviews the score is based on
+    pub count: Option<u32>,
+}
+
+/// Popularity statistics of a game on IsThereAnyDeal
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct GameStats {
+    /// Popularity rank (1 = most popular)
+    pub rank: Option<u32>,
+    /// Number of users with the game on their waitlist
+    pub waitlisted: Option<u32>,
+    /// Number of users with the game in their collection
+    pub collected: Option<u32>,
 }
 
 /// Price history data point for charts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceHistoryPoint {
     pub timestamp: i64,
-    pub price: f64,
+    pub price: Money,
     pub shop_name: String,
 }
 
+/// Cheapest current offer for a game in one region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionalPrice {
+    pub region: Region,
+    /// `None` when no shop sells the game in the region
+    pub offer: Option<RegionalOffer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionalOffer {
+    pub shop: Shop,
+    pub price: Price,
+    pub regular_price: Money,
+    pub url: String,
+}
+
 /// Store/shop information
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Shop {
@@ -538,8 +689,7 @@ pub struct Shop {
 /// Price information with discount
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Price {
-    pub amount: f64,
-    pub currency: String,
+    pub amount: Money,
     pub discount: u8,
 }
 
@@ -554,8 +704,7 @@ pub struct DealFilter {
 impl Default for Price {
     fn default() -> Self {
         Self {
-            amount: 0.0,
-            currency: "USD".to_string(),
+            amount: Money::zero(Currency::USD),
             discount: 0,
         }
     }
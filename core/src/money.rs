@@ -0,0 +1,475 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// ISO 4217 currency code, e.g. `EUR`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+
+    /// Parse a three letter code, in any case
+    pub fn new(code: &str) -> Option<Currency> {
+        let bytes: [u8; 3] = code.as_bytes().try_into().ok()?;
+        if !bytes.iter().all(u8::is_ascii_alphabetic) {
+            return None;
+        }
+        Some(Currency(bytes.map(|b| b.to_ascii_uppercase())))
+    }
+
+    pub fn code(&self) -> &str {
+        // Only ASCII letters get in, see `new`
+        std::str::from_utf8(&self.0).unwrap_or("???")
+    }
+
+    /// Digits after the decimal point: 0 for JPY or KRW, 3 for KWD, 2 otherwise
+    pub fn minor_digits(&self) -> u32 {
+        match self.code() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+
+    /// Symbol shown next to amounts, the code itself when there is no
+    /// unambiguous one
+    pub fn symbol(&self) -> &str {
+        match self.code() {
+            "USD" => "$",
+            "EUR" => "€",
+            "GBP" => "£",
+            "JPY" => "¥",
+            "CNY" => "CN¥",
+            "KRW" => "₩",
+            "INR" => "₹",
+            "BRL" => "R$",
+            "CAD" => "CA$",
+            "AUD" => "A$",
+            "NZD" => "NZ$",
+            "HKD" => "HK$",
+            "SGD" => "S$",
+            "TWD" => "NT$",
+            "MXN" => "MX$",
+            "PLN" => "zł",
+            "CZK" => "Kč",
+            "HUF" => "Ft",
+            "SEK" | "NOK" | "DKK" => "kr",
+            "TRY" => "₺",
+            "ILS" => "₪",
+            "THB" => "฿",
+            "PHP" => "₱",
+            "IDR" => "Rp",
+            "ZAR" => "R",
+            "UAH" => "₴",
+            "RUB" => "₽",
+            _ => self.code(),
+        }
+    }
+
+    fn scale(&self) -> i64 {
+        10i64.pow(self.minor_digits())
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Currency::new(&code).ok_or_else(|| format!("invalid currency code `{}`", code))
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.code().to_string()
+    }
+}
+
+/// How amounts are written: `€1,234.50` in Ireland, `1 234,50 €` in France
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoneyFormat {
+    pub decimal_separator: char,
+    /// Between groups of three digits, `None` to write them together
+    pub group_separator: Option<char>,
+    pub symbol_after: bool,
+    /// Space between the symbol and the number
+    pub symbol_spaced: bool,
+}
+
+impl Default for MoneyFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator: Some(','),
+            symbol_after: false,
+            symbol_spaced: false,
+        }
+    }
+}
+
+/// An amount in the minor units of its currency (cents for EUR, yen for JPY)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(minor: i64, currency: Currency) -> Self {
+        Self { minor, currency }
+    }
+
+    /// Amount given in major units (`9.99`), rounded to the nearest minor unit
+    pub fn from_major(amount: f64, currency: Currency) -> Self {
+        Self {
+            minor: (amount * currency.scale() as f64).round() as i64,
+            currency,
+        }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Amount in major units, for charts and sorting only
+    pub fn to_major(&self) -> f64 {
+        self.minor as f64 / self.currency.scale() as f64
+    }
+
+    /// Difference of two amounts in the same currency
+    pub fn checked_sub(&self, other: Money) -> Option<Money> {
+        (self.currency == other.currency)
+            .then(|| Money::new(self.minor - other.minor, self.currency))
+    }
+
+    /// Number without the symbol, e.g. `1 234,50`
+    pub fn format_amount(&self, format: MoneyFormat) -> String {
+        let sign = if self.minor < 0 { "-" } else { "" };
+        format!("{}{}", sign, self.format_unsigned(format))
+    }
+
+    /// Amount with the currency symbol, e.g. `€9.99` or `9,99 €`
+    pub fn format(&self, format: MoneyFormat) -> String {
+        let amount = self.format_unsigned(format);
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let symbol = self.currency.symbol();
+        let space = if format.symbol_spaced { " " } else { "" };
+        if format.symbol_after {
+            format!("{}{}{}{}", sign, amount, space, symbol)
+        } else {
+            format!("{}{}{}{}", sign, symbol, space, amount)
+        }
+    }
+
+    /// Number without the sign or the symbol. `unsigned_abs` keeps `i64::MIN`
+    /// from overflowing.
+    fn format_unsigned(&self, format: MoneyFormat) -> String {
+        let digits = self.currency.minor_digits() as usize;
+        let scale = self.currency.scale().unsigned_abs();
+        let minor = self.minor.unsigned_abs();
+        let (major, fraction) = (minor / scale, minor % scale);
+
+        let major = major.to_string();
+        let mut text = String::with_capacity(major.len() + digits + 4);
+        for (i, digit) in major.chars().enumerate() {
+            if i > 0 && (major.len() - i) % 3 == 0 {
+                if let Some(separator) = format.group_separator {
+                    text.push(separator);
+                }
+            }
+            text.push(digit);
+        }
+        if digits > 0 {
+            text.push(format.decimal_separator);
+            text.push_str(&format!("{:0width$}", fraction, width = digits));
+        }
+        text
+    }
+}
+
+/// Amount typed without a currency, like a price filter bound, read in the
+/// currency of whatever it's compared with. Kept in thousandths so it's exact
+/// in the minor units of every currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(i64);
+
+impl Amount {
+    const DIGITS: u32 = 3;
+    const SCALE: i64 = 1000;
+
+    /// Parse a positive decimal number like `20` or `12.5`, with at most three
+    /// digits after the point
+    pub fn parse(text: &str) -> Option<Amount> {
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty())
+            || !digits(whole)
+            || !digits(fraction)
+            || fraction.len() > Self::DIGITS as usize
+        {
+            return None;
+        }
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().ok()?
+        };
+        let fraction: i64 = format!("{:0<3}", fraction).parse().ok()?;
+        whole
+            .checked_mul(Self::SCALE)?
+            .checked_add(fraction)
+            .map(Amount)
+    }
+
+    /// Nearest amount to a number in major units, for files that store them
+    pub fn from_major(amount: f64) -> Amount {
+        Amount((amount * Self::SCALE as f64).round() as i64)
+    }
+
+    pub fn to_major(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    /// The amount in a currency, rounded to its minor units
+    pub fn in_currency(self, currency: Currency) -> Money {
+        let divisor = 10i64.pow(Self::DIGITS - currency.minor_digits().min(Self::DIGITS));
+        let half = divisor / 2;
+        let rounded = if self.0 < 0 {
+            self.0 - half
+        } else {
+            self.0 + half
+        };
+        Money::new(rounded / divisor, currency)
+    }
+}
+
+/// `12.5`, without trailing zeros
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let value = self.0.unsigned_abs();
+        let scale = Self::SCALE.unsigned_abs();
+        let (whole, fraction) = (value / scale, value % scale);
+        if fraction == 0 {
+            write!(f, "{}{}", sign, whole)
+        } else {
+            let fraction = format!("{:03}", fraction);
+            write!(f, "{}{}.{}", sign, whole, fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+/// Written as a plain number, which is what presets stored before amounts
+/// were exact
+impl Serialize for Amount {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_major())
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Amount::from_major)
+    }
+}
+
+/// Amounts in different currencies can't be compared
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.currency == other.currency).then(|| self.minor.cmp(&other.minor))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(MoneyFormat::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IRELAND: MoneyFormat = MoneyFormat {
+        decimal_separator: '.',
+        group_separator: Some(','),
+        symbol_after: false,
+        symbol_spaced: false,
+    };
+    const FRANCE: MoneyFormat = MoneyFormat {
+        decimal_separator: ',',
+        group_separator: Some('\u{202f}'),
+        symbol_after: true,
+        symbol_spaced: true,
+    };
+    const SWITZERLAND: MoneyFormat = MoneyFormat {
+        decimal_separator: '.',
+        group_separator: Some('\''),
+        symbol_after: false,
+        symbol_spaced: true,
+    };
+    const NO_GROUPS: MoneyFormat = MoneyFormat {
+        decimal_separator: ',',
+        group_separator: None,
+        symbol_after: true,
+        symbol_spaced: false,
+    };
+
+    fn currency(code: &str) -> Currency {
+        Currency::new(code).unwrap()
+    }
+
+    #[test]
+    fn amounts_are_formatted() {
+        let cases = [
+            (999, "EUR", IRELAND, "€9.99"),
+            (123450, "EUR", IRELAND, "€1,234.50"),
+            (123450, "EUR", FRANCE, "1\u{202f}234,50 €"),
+            (123450, "CHF", SWITZERLAND, "CHF 1'234.50"),
+            (123450, "PLN", NO_GROUPS, "1234,50zł"),
+            (5, "USD", IRELAND, "$0.05"),
+            (0, "USD", IRELAND, "$0.00"),
+            (100000000, "USD", IRELAND, "$1,000,000.00"),
+            // No minor units
+            (1980, "JPY", IRELAND, "¥1,980"),
+            (25000, "KRW", FRANCE, "25\u{202f}000 ₩"),
+            // Three minor digits
+            (1500, "KWD", IRELAND, "KWD1.500"),
+            (12345, "KWD", SWITZERLAND, "KWD 12.345"),
+        ];
+        for (minor, code, format, expected) in cases {
+            assert_eq!(
+                Money::new(minor, currency(code)).format(format),
+                expected,
+                "{} {}",
+                minor,
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn negative_amounts_keep_the_sign_in_front() {
+        let cases = [
+            (-999, "EUR", IRELAND, "-€9.99", "-9.99"),
+            (
+                -123450,
+                "EUR",
+                FRANCE,
+                "-1\u{202f}234,50 €",
+                "-1\u{202f}234,50",
+            ),
+            (-1980, "JPY", IRELAND, "-¥1,980", "-1,980"),
+            (-5, "KWD", IRELAND, "-KWD0.005", "-0.005"),
+        ];
+        for (minor, code, format, expected, amount) in cases {
+            let money = Money::new(minor, currency(code));
+            assert_eq!(money.format(format), expected);
+            assert_eq!(money.format_amount(format), amount);
+        }
+    }
+
+    #[test]
+    fn the_smallest_amount_does_not_overflow() {
+        let money = Money::new(i64::MIN, Currency::USD);
+        assert_eq!(money.format(NO_GROUPS), "-92233720368547758,08$");
+        assert_eq!(money.format_amount(IRELAND), "-92,233,720,368,547,758.08");
+    }
+
+    #[test]
+    fn major_amounts_are_rounded_to_minor_units() {
+        assert_eq!(Money::from_major(9.99, Currency::EUR).minor(), 999);
+        assert_eq!(Money::from_major(0.125, Currency::EUR).minor(), 13);
+        assert_eq!(Money::from_major(1980.4, currency("JPY")).minor(), 1980);
+        assert_eq!(Money::from_major(1.5, currency("KWD")).minor(), 1500);
+        assert_eq!(Money::from_major(-2.5, Currency::USD).minor(), -250);
+    }
+
+    #[test]
+    fn only_amounts_in_the_same_currency_compare() {
+        let eur = Money::new(500, Currency::EUR);
+        assert!(eur < Money::new(600, Currency::EUR));
+        assert_eq!(eur.partial_cmp(&Money::new(500, Currency::USD)), None);
+        assert_eq!(
+            Money::new(600, Currency::EUR).checked_sub(eur),
+            Some(Money::new(100, Currency::EUR))
+        );
+        assert_eq!(eur.checked_sub(Money::new(100, Currency::USD)), None);
+    }
+
+    #[test]
+    fn typed_amounts_are_exact() {
+        let amount = |text| Amount::parse(text).map(|amount| amount.to_string());
+        assert_eq!(amount("20").as_deref(), Some("20"));
+        assert_eq!(amount("12.50").as_deref(), Some("12.5"));
+        assert_eq!(amount(".5").as_deref(), Some("0.5"));
+        assert_eq!(amount("7.").as_deref(), Some("7"));
+        assert_eq!(amount("0.005").as_deref(), Some("0.005"));
+        for invalid in [
+            "",
+            ".",
+            "1.2345",
+            "1.2.3",
+            "-5",
+            "1e3",
+            "9999999999999999999",
+        ] {
+            assert_eq!(Amount::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn typed_amounts_are_rounded_to_minor_units() {
+        let amount = Amount::parse("1980.5").unwrap();
+        assert_eq!(
+            amount.in_currency(Currency::EUR),
+            Money::new(198050, Currency::EUR)
+        );
+        assert_eq!(
+            amount.in_currency(currency("JPY")),
+            Money::new(1981, currency("JPY"))
+        );
+        assert_eq!(
+            amount.in_currency(currency("KWD")),
+            Money::new(1980500, currency("KWD"))
+        );
+        let amount = Amount::parse("0.105").unwrap();
+        assert_eq!(
+            amount.in_currency(Currency::USD),
+            Money::new(11, Currency::USD)
+        );
+        assert_eq!(Amount::from_major(0.1 + 0.2), Amount::parse("0.3").unwrap());
+    }
+
+    #[test]
+    fn currency_codes_are_three_letters() {
+        assert_eq!(Currency::new("usd"), Some(Currency::USD));
+        assert_eq!(Currency::new("US"), None);
+        assert_eq!(Currency::new("U5D"), None);
+        assert_eq!(currency("XYZ").symbol(), "XYZ");
+    }
+}
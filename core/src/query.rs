@@ -0,0 +1,574 @@
+use std::fmt;
+
+use crate::models::{Deal, GameInfo};
+use crate::money::Money;
+
+/// Comparison operator used by numeric filter terms (`cut>=75`, `price<10`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn symbol(&self) -> &str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Equal => "=",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Greater => ">",
+        }
+    }
+
+    fn compare<T: PartialOrd>(&self, value: T, target: T) -> bool {
+        match self {
+            Comparison::Less => value < target,
+            Comparison::LessOrEqual => value <= target,
+            Comparison::Equal => value == target,
+            Comparison::GreaterOrEqual => value >= target,
+            Comparison::Greater => value > target,
+        }
+    }
+}
+
+/// A single condition of a deal query
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    /// Discount percentage (`cut>=75`)
+    Cut(Comparison, f64),
+    /// Current price (`price<10`)
+    Price(Comparison, f64),
+    /// Shop name, any of the listed values (`shop:gog,steam`)
+    Shop(Vec<String>),
+    /// Game tag, any of the listed values (`tag:roguelike`)
+    Tag(Vec<String>),
+    /// Current price is the all-time low (`atl`)
+    AllTimeLow,
+    /// Word contained in the title or tags (only produced negated, `-dlc`)
+    Word(String),
+    /// Negation of another term (`-shop:steam`)
+    Not(Box<Term>),
+}
+
+impl Term {
+    /// Evaluate the term against a deal and its cached game info, `price`
+    /// being the deal's price as shown, in the display currency when there is
+    /// one. Returns `None` when the answer depends on game info that isn't
+    /// loaded yet.
+    pub fn matches(&self, deal: &Deal, info: Option<&GameInfo>, price: Money) -> Option<bool> {
+        match self {
+            Term::Cut(cmp, value) => Some(cmp.compare(deal.price.discount as f64, *value)),
+            Term::Price(cmp, value) => {
+                // The typed price is in the currency prices are shown in
+                let target = Money::from_major(*value, price.currency());
+                Some(cmp.compare(price, target))
+            }
+            Term::Shop(shops) => {
+                let name = normalize(&deal.shop.name);
+                Some(shops.iter().any(|shop| name.contains(shop.as_str())))
+            }
+            Term::Tag(tags) => {
+                let info = info?;
+                Some(
+                    info.tags
+                        .iter()
+                        .map(|tag| normalize(tag))
+                        .any(|tag| tags.contains(&tag)),
+                )
+            }
+            Term::AllTimeLow => Some(deal.is_historical_low()),
+            Term::Word(word) => {
+                if normalize(&deal.title).contains(word.as_str()) {
+                    return Some(true);
+                }
+                let info = info?;
+                Some(info.tags.iter().any(|tag| normalize(tag).contains(word)))
+            }
+            Term::Not(term) => term.matches(deal, info, price).map(|matched| !matched),
+        }
+    }
+
+    /// Whether the term can depend on game info
+    pub fn needs_game_info(&self) -> bool {
+        match self {
+            Term::Tag(_) | Term::Word(_) => true,
+            Term::Not(term) => term.needs_game_info(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Cut(cmp, value) => write!(f, "cut{}{}", cmp.symbol(), value),
+            Term::Price(cmp, value) => write!(f, "price{}{}", cmp.symbol(), value),
+            Term::Shop(shops) => write!(f, "shop:{}", shops.join(",")),
+            Term::Tag(tags) => write!(f, "tag:{}", tags.join(",")),
+            Term::AllTimeLow => write!(f, "atl"),
+            Term::Word(word) => write!(f, "{}", word),
+            Term::Not(term) => write!(f, "-{}", term),
+        }
+    }
+}
+
+/// Syntax error in a deal query, with the byte offset of the offending token
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} (at column {})", .position + 1)]
+pub struct QueryError {
+    pub position: usize,
+    pub message: String,
+}
+
+/// Parsed deal query, e.g. `cut>=75 price<10 shop:gog,steam tag:roguelike atl -dlc`.
+///
+/// Structured terms are evaluated locally against loaded deals, while plain
+/// words are joined into `text` and used as the remote title search.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DealQuery {
+    pub terms: Vec<Term>,
+    pub text: Option<String>,
+}
+
+impl DealQuery {
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let mut terms = Vec::new();
+        let mut words = Vec::new();
+
+        for Token {
+            position,
+            text,
+            quoted,
+        } in tokenize(input)?
+        {
+            let (negated, body) = match text.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, text.as_str()),
+            };
+
+            // Quoted text is never interpreted as a filter (`"Half-Life: Alyx"`)
+            let parsed = if quoted {
+                None
+            } else {
+                parse_term(body, position + negated as usize)?
+            };
+
+            let term = match parsed {
+                Some(term) => term,
+                None if negated => Term::Word(normalize(body)),
+                None => {
+                    words.push(body.to_string());
+                    continue;
+                }
+            };
+
+            terms.push(if negated {
+                Term::Not(Box::new(term))
+            } else {
+                term
+            });
+        }
+
+        let text = if words.is_empty() {
+            None
+        } else {
+            Some(words.join(" "))
+        };
+
+        Ok(Self { terms, text })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.text.is_none()
+    }
+
+    /// Whether the deal satisfies every local term, see `Term::matches`.
+    /// Until its game info is loaded, a deal is kept by negated terms, which
+    /// only hide what is known to match, and left out by the others.
+    pub fn matches(&self, deal: &Deal, info: Option<&GameInfo>, price: Money) -> bool {
+        self.terms.iter().all(|term| {
+            term.matches(deal, info, price)
+                .unwrap_or(matches!(term, Term::Not(_)))
+        })
+    }
+
+    /// Whether any term needs the game info of the deals, see `Term::matches`
+    pub fn needs_game_info(&self) -> bool {
+        self.terms.iter().any(Term::needs_game_info)
+    }
+}
+
+struct Token {
+    /// Byte offset of the token in the input
+    position: usize,
+    text: String,
+    quoted: bool,
+}
+
+/// Split the input on whitespace, keeping double-quoted sections together
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut quote_start: Option<usize> = None;
+
+    for (i, c) in input.char_indices() {
+        if c == '"' {
+            if current.is_empty() && quote_start.is_none() {
+                start = i;
+            }
+            quoted = true;
+            quote_start = match quote_start {
+                Some(_) => None,
+                None => Some(i),
+            };
+        } else if c.is_whitespace() && quote_start.is_none() {
+            if !current.is_empty() {
+                tokens.push(Token {
+                    position: start,
+                    text: std::mem::take(&mut current),
+                    quoted,
+                });
+            }
+            quoted = false;
+        } else {
+            if current.is_empty() && quote_start.is_none() {
+                start = i;
+            }
+            current.push(c);
+        }
+    }
+
+    if let Some(position) = quote_start {
+        return Err(QueryError {
+            position,
+            message: "unclosed quote".to_string(),
+        });
+    }
+    if !current.is_empty() {
+        tokens.push(Token {
+            position: start,
+            text: current,
+            quoted,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a structured term, or return `None` for a plain word
+fn parse_term(token: &str, position: usize) -> Result<Option<Term>, QueryError> {
+    let error = |message: String| QueryError { position, message };
+    let lower = token.to_lowercase();
+
+    if lower == "atl" {
+        return Ok(Some(Term::AllTimeLow));
+    }
+
+    if let Some((key, value)) = lower.split_once(':') {
+        let values: Vec<String> = value
+            .split(',')
+            .map(normalize)
+            .filter(|v| !v.is_empty())
+            .collect();
+
+        return match key {
+            "shop" | "tag" if values.is_empty() => Err(error(format!("`{}:` needs a value", key))),
+            "shop" => Ok(Some(Term::Shop(values))),
+            "tag" => Ok(Some(Term::Tag(values))),
+            "cut" | "price" => parse_numeric(key, Comparison::Equal, value, position).map(Some),
+            _ => Err(error(format!("unknown filter `{}:`", key))),
+        };
+    }
+
+    for key in ["cut", "price"] {
+        let Some(rest) = lower.strip_prefix(key) else {
+            continue;
+        };
+        let (cmp, value) = if let Some(v) = rest.strip_prefix(">=") {
+            (Comparison::GreaterOrEqual, v)
+        } else if let Some(v) = rest.strip_prefix("<=") {
+            (Comparison::LessOrEqual, v)
+        } else if let Some(v) = rest.strip_prefix('>') {
+            (Comparison::Greater, v)
+        } else if let Some(v) = rest.strip_prefix('<') {
+            (Comparison::Less, v)
+        } else if let Some(v) = rest.strip_prefix('=') {
+            (Comparison::Equal, v)
+        } else {
+            continue;
+        };
+        return parse_numeric(key, cmp, value, position).map(Some);
+    }
+
+    if lower.contains(['<', '>', '=']) {
+        return Err(error(format!("unknown comparison `{}`", token)));
+    }
+
+    Ok(None)
+}
+
+fn parse_numeric(
+    key: &str,
+    cmp: Comparison,
+    value: &str,
+    position: usize,
+) -> Result<Term, QueryError> {
+    let number: f64 = value
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| QueryError {
+            position,
+            message: format!("`{}` expects a number, got `{}`", key, value),
+        })?;
+
+    Ok(match key {
+        "cut" => Term::Cut(cmp, number),
+        _ => Term::Price(cmp, number),
+    })
+}
+
+/// Lowercase and strip everything but letters and digits, so that
+/// `Epic Games Store` matches `epic` and `Rogue-like` matches `roguelike`
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Price, Shop};
+    use crate::money::Currency;
+
+    fn deal(title: &str, shop: &str, price: f64, discount: u8) -> Deal {
+        Deal {
+            id: title.to_lowercase(),
+            title: title.to_string(),
+            slug: None,
+            shop: Shop {
+                id: "1".to_string(),
+                name: shop.to_string(),
+            },
+            price: Price {
+                amount: Money::from_major(price, Currency::EUR),
+                discount,
+            },
+            regular_price: Money::from_major(20.0, Currency::EUR),
+            url: String::new(),
+            history_low: None,
+            store_low: None,
+            expiry: None,
+        }
+    }
+
+    /// Without a display currency
+    fn matches(query: &DealQuery, deal: &Deal, info: Option<&GameInfo>) -> bool {
+        query.matches(deal, info, deal.price.amount)
+    }
+
+    fn info(tags: &[&str]) -> GameInfo {
+        GameInfo {
+            id: String::new(),
+            title: String::new(),
+            release_date: None,
+            developers: Vec::new(),
+            publishers: Vec::new(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            stats: None,
+            reviews: Vec::new(),
+            bundled: None,
+        }
+    }
+
+    fn texts(input: &str) -> Vec<(usize, String, bool)> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| (token.position, token.text, token.quoted))
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace() {
+        assert_eq!(
+            texts("  cut>=75   hades "),
+            [
+                (2, "cut>=75".to_string(), false),
+                (12, "hades".to_string(), false)
+            ]
+        );
+        assert!(texts("   ").is_empty());
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_sections_together() {
+        assert_eq!(
+            texts(r#""Half-Life: Alyx" atl"#),
+            [
+                (0, "Half-Life: Alyx".to_string(), true),
+                (18, "atl".to_string(), false)
+            ]
+        );
+        // A quote inside a word joins what follows
+        assert_eq!(
+            texts(r#"-"shop:gog" x"#),
+            [
+                (0, "-shop:gog".to_string(), true),
+                (12, "x".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_reports_unclosed_quotes() {
+        assert_eq!(
+            tokenize(r#"atl "dead cells"#).err(),
+            Some(QueryError {
+                position: 4,
+                message: "unclosed quote".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_structured_terms() {
+        let query = DealQuery::parse("cut>=75 price<10 shop:GOG,Epic tag:Rogue-like atl").unwrap();
+        assert_eq!(
+            query.terms,
+            [
+                Term::Cut(Comparison::GreaterOrEqual, 75.0),
+                Term::Price(Comparison::Less, 10.0),
+                Term::Shop(vec!["gog".to_string(), "epic".to_string()]),
+                Term::Tag(vec!["roguelike".to_string()]),
+                Term::AllTimeLow,
+            ]
+        );
+        assert_eq!(query.text, None);
+    }
+
+    #[test]
+    fn parse_key_value_comparisons() {
+        let query = DealQuery::parse("cut:50% price=4.99 cut<=10 price>1").unwrap();
+        assert_eq!(
+            query.terms,
+            [
+                Term::Cut(Comparison::Equal, 50.0),
+                Term::Price(Comparison::Equal, 4.99),
+                Term::Cut(Comparison::LessOrEqual, 10.0),
+                Term::Price(Comparison::Greater, 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_negation() {
+        let query = DealQuery::parse("-shop:steam -DLC -").unwrap();
+        assert_eq!(
+            query.terms,
+            [
+                Term::Not(Box::new(Term::Shop(vec!["steam".to_string()]))),
+                Term::Not(Box::new(Term::Word("dlc".to_string()))),
+            ]
+        );
+        // A lone dash is a word
+        assert_eq!(query.text.as_deref(), Some("-"));
+    }
+
+    #[test]
+    fn parse_joins_words_into_the_search_text() {
+        let query = DealQuery::parse(r#"dead cut>50 "cut>50" cells"#).unwrap();
+        assert_eq!(query.terms, [Term::Cut(Comparison::Greater, 50.0)]);
+        assert_eq!(query.text.as_deref(), Some("dead cut>50 cells"));
+        assert!(DealQuery::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_term_errors() {
+        let error = |input: &str| DealQuery::parse(input).unwrap_err();
+        assert_eq!(
+            error("atl shop:"),
+            QueryError {
+                position: 4,
+                message: "`shop:` needs a value".to_string()
+            }
+        );
+        assert_eq!(error("tag:,").message, "`tag:` needs a value");
+        assert_eq!(error("year:2020").message, "unknown filter `year:`");
+        assert_eq!(
+            error("cut>=lots").message,
+            "`cut` expects a number, got `lots`"
+        );
+        assert_eq!(error("price:").message, "`price` expects a number, got ``");
+        assert_eq!(error("score>80").message, "unknown comparison `score>80`");
+        // Negated terms point past the dash
+        assert_eq!(error("x -rank<3").position, 3);
+        assert_eq!(
+            error("cut>=lots").to_string(),
+            "`cut` expects a number, got `lots` (at column 1)"
+        );
+    }
+
+    #[test]
+    fn terms_match_deals() {
+        let query = DealQuery::parse("cut>=50 price<10 shop:gog").unwrap();
+        assert!(matches(&query, &deal("Hades", "GOG", 9.99, 50), None));
+        assert!(!matches(&query, &deal("Hades", "GOG", 10.0, 50), None));
+        assert!(!matches(&query, &deal("Hades", "Steam", 5.0, 75), None));
+        assert!(!matches(&query, &deal("Hades", "GOG", 5.0, 25), None));
+        assert!(!query.needs_game_info());
+    }
+
+    #[test]
+    fn prices_are_compared_in_the_currency_shown() {
+        let query = DealQuery::parse("price<10").unwrap();
+        let hades = deal("Hades", "GOG", 9.0, 50);
+        assert!(query.matches(&hades, None, hades.price.amount));
+        // €9 shown as $11.25
+        assert!(!query.matches(&hades, None, Money::from_major(11.25, Currency::USD)));
+    }
+
+    #[test]
+    fn deals_without_game_info_only_pass_negated_terms() {
+        let query = DealQuery::parse("tag:roguelike").unwrap();
+        let hades = deal("Hades", "GOG", 9.99, 50);
+        assert!(query.needs_game_info());
+        assert!(!matches(&query, &hades, None));
+        assert!(matches(
+            &query,
+            &hades,
+            Some(&info(&["Rogue-like", "Action"]))
+        ));
+        assert!(!matches(&query, &hades, Some(&info(&["Puzzle"]))));
+
+        let query = DealQuery::parse("-tag:roguelike").unwrap();
+        assert!(matches(&query, &hades, None));
+        assert!(matches(&query, &hades, Some(&info(&["Puzzle"]))));
+        assert!(!matches(&query, &hades, Some(&info(&["Roguelike"]))));
+    }
+
+    #[test]
+    fn negated_words_check_the_title_then_tags() {
+        let query = DealQuery::parse("-dlc").unwrap();
+        assert!(!matches(&query, &deal("Hades DLC", "GOG", 1.0, 0), None));
+        assert!(matches(&query, &deal("Hades", "GOG", 1.0, 0), None));
+        assert!(matches(
+            &query,
+            &deal("Hades", "GOG", 1.0, 0),
+            Some(&info(&["Action"]))
+        ));
+        assert!(!matches(
+            &query,
+            &deal("Hades", "GOG", 1.0, 0),
+            Some(&info(&["DLC"]))
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+//! Exchange rates for showing prices in another currency. They are read from
+//! a local JSON file in the format of the ECB reference rates services, e.g.
+//! `{"base": "EUR", "date": "2025-06-02", "rates": {"USD": 1.14}}`, which a
+//! command such as `curl -s https://api.frankfurter.dev/v1/latest` prints.
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::{DealveError, Result};
+use crate::money::{Currency, Money};
+use crate::shell::{self, Streams};
+
+/// Rates used until a rates file is saved, approximate reference rates
+const BUILTIN_RATES: &[(&str, f64)] = &[
+    ("AUD", 1.65),
+    ("BRL", 6.10),
+    ("CAD", 1.48),
+    ("CHF", 0.94),
+    ("CZK", 25.2),
+    ("DKK", 7.46),
+    ("GBP", 0.85),
+    ("HUF", 395.0),
+    ("INR", 90.0),
+    ("JPY", 162.0),
+    ("NOK", 11.6),
+    ("PLN", 4.30),
+    ("SEK", 11.4),
+    ("TRY", 37.0),
+    ("USD", 1.08),
+];
+
+/// Days after their publication before rates are worth updating. Reference
+/// rates aren't published on weekends and holidays.
+pub const STALE_AFTER_DAYS: i64 = 7;
+
+/// Conversion rates between currencies, given as units of each currency for
+/// one unit of a base currency (`EUR 1 = USD 1.08`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRates {
+    base: Currency,
+    /// Day the rates were published, `None` for the built-in ones
+    date: Option<String>,
+    rates: HashMap<Currency, f64>,
+}
+
+/// Layout of a rates file
+#[derive(Deserialize)]
+struct RatesFile {
+    base: Currency,
+    #[serde(default)]
+    date: Option<String>,
+    rates: HashMap<String, f64>,
+}
+
+impl ExchangeRates {
+    pub fn new(base: Currency) -> Self {
+        Self {
+            base,
+            date: None,
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Approximate euro rates shipped with dealve, for when no rates file
+    /// was saved yet
+    pub fn builtin() -> Self {
+        let mut rates = Self::new(Currency::EUR);
+        for &(code, rate) in BUILTIN_RATES {
+            if let Some(currency) = Currency::new(code) {
+                rates.insert(currency, rate);
+            }
+        }
+        rates
+    }
+
+    /// Parse the content of a rates file. Currencies with an invalid code or
+    /// rate are left out.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: RatesFile = serde_json::from_str(json)
+            .map_err(|e| DealveError::Parse(format!("invalid exchange rates: {}", e)))?;
+        let mut rates = Self::new(file.base);
+        rates.date = file.date;
+        for (code, rate) in file.rates {
+            if let Some(currency) = Currency::new(&code) {
+                rates.insert(currency, rate);
+            }
+        }
+        Ok(rates)
+    }
+
+    /// Read a rates file
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| DealveError::Rates(format!("can't read {}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Run `command` with the shell and save the rates it prints to `path`,
+    /// replacing the file only when they are valid
+    pub fn refresh(command: &str, path: &Path) -> Result<Self> {
+        let json =
+            shell::run(command, Streams::Capture).map_err(|e| DealveError::Rates(e.to_string()))?;
+        let rates = Self::from_json(&json)?;
+        save(path, &json)
+            .map_err(|e| DealveError::Rates(format!("can't save {}: {}", path.display(), e)))?;
+        Ok(rates)
+    }
+
+    pub fn base(&self) -> Currency {
+        self.base
+    }
+
+    pub fn date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+
+    /// Whether the rates were published more than `STALE_AFTER_DAYS` before
+    /// `today`. Rates without a readable date can't tell and aren't.
+    pub fn is_stale(&self, today: NaiveDate) -> bool {
+        self.date
+            .as_deref()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .is_some_and(|date| (today - date).num_days() > STALE_AFTER_DAYS)
+    }
+
+    /// Set the rate of a currency. Rates that aren't positive are ignored.
+    pub fn insert(&mut self, currency: Currency, rate: f64) {
+        if rate.is_finite() && rate > 0.0 && currency != self.base {
+            self.rates.insert(currency, rate);
+        }
+    }
+
+    /// Units of `currency` for one unit of the base currency
+    pub fn rate(&self, currency: Currency) -> Option<f64> {
+        if currency == self.base {
+            return Some(1.0);
+        }
+        self.rates.get(&currency).copied()
+    }
+
+    /// Every currency with a rate, the base included, by code
+    pub fn currencies(&self) -> Vec<Currency> {
+        let mut currencies: Vec<Currency> = self.rates.keys().copied().collect();
+        currencies.push(self.base);
+        currencies.sort_by(|a, b| a.code().cmp(b.code()));
+        currencies
+    }
+
+    /// Amount in another currency, `None` when a rate is missing
+    pub fn convert(&self, money: Money, to: Currency) -> Option<Money> {
+        if money.currency() == to {
+            return Some(money);
+        }
+        let from_rate = self.rate(money.currency())?;
+        let to_rate = self.rate(to)?;
+        Some(Money::from_major(
+            money.to_major() / from_rate * to_rate,
+            to,
+        ))
+    }
+}
+
+/// Write the file whole, so a failed write keeps the previous rates
+fn save(path: &Path, json: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    let mut temp = File::create(&temp_path)?;
+    temp.write_all(json.as_bytes())?;
+    temp.sync_all()?;
+    fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: &str =
+        r#"{"base": "EUR", "date": "2025-06-02", "rates": {"USD": 1.25, "JPY": 160, "GBP": 0.8}}"#;
+
+    fn currency(code: &str) -> Currency {
+        Currency::new(code).unwrap()
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dealve-rates-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn amounts_are_converted_through_the_base() {
+        let rates = ExchangeRates::from_json(RATES).unwrap();
+        let eur = Money::new(1000, Currency::EUR);
+        assert_eq!(
+            rates.convert(eur, Currency::USD),
+            Some(Money::new(1250, Currency::USD))
+        );
+        // USD 12.50 is EUR 10, which is JPY 1600
+        let usd = Money::new(1250, Currency::USD);
+        assert_eq!(
+            rates.convert(usd, currency("JPY")),
+            Some(Money::new(1600, currency("JPY")))
+        );
+        assert_eq!(
+            rates.convert(usd, currency("GBP")),
+            Some(Money::new(800, currency("GBP")))
+        );
+        // Rounded to the nearest minor unit
+        let yen = Money::new(1, currency("JPY"));
+        assert_eq!(
+            rates.convert(yen, Currency::EUR),
+            Some(Money::new(1, Currency::EUR))
+        );
+        assert_eq!(rates.convert(usd, Currency::USD), Some(usd));
+        assert_eq!(rates.convert(usd, currency("CHF")), None);
+        assert_eq!(
+            rates.convert(Money::new(100, currency("CHF")), Currency::EUR),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_rates_are_left_out() {
+        let rates = ExchangeRates::from_json(
+            r#"{"base": "EUR", "rates": {"USD": 1.1, "EURO": 1, "GBP": 0, "JPY": -3, "CHF": 0.9}}"#,
+        )
+        .unwrap();
+        assert_eq!(rates.date(), None);
+        let codes: Vec<String> = rates
+            .currencies()
+            .iter()
+            .map(|currency| currency.code().to_string())
+            .collect();
+        assert_eq!(codes, ["CHF", "EUR", "USD"]);
+        assert_eq!(rates.rate(Currency::EUR), Some(1.0));
+        assert_eq!(rates.rate(currency("GBP")), None);
+        assert!(ExchangeRates::from_json("{}").is_err());
+        assert!(ExchangeRates::from_json(r#"{"base": "EURO", "rates": {}}"#).is_err());
+    }
+
+    #[test]
+    fn rates_go_stale_after_a_week() {
+        let rates = ExchangeRates::from_json(RATES).unwrap();
+        let day = |date| NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        assert!(!rates.is_stale(day("2025-06-02")));
+        assert!(!rates.is_stale(day("2025-06-09")));
+        assert!(rates.is_stale(day("2025-06-10")));
+        assert!(!ExchangeRates::builtin().is_stale(day("2030-01-01")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refreshing_saves_only_valid_rates() {
+        let dir = temp_dir("refresh");
+        let path = dir.join("rates.json");
+        let rates = ExchangeRates::refresh(&format!("echo '{}'", RATES), &path).unwrap();
+        assert_eq!(rates.date(), Some("2025-06-02"));
+        assert_eq!(ExchangeRates::load(&path).unwrap(), rates);
+
+        let error = ExchangeRates::refresh("echo 'not json'", &path).unwrap_err();
+        assert!(
+            error.to_string().contains("invalid exchange rates"),
+            "{}",
+            error
+        );
+        let error = ExchangeRates::refresh("echo offline >&2; exit 6", &path).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Exchange rates error: `echo offline >&2; exit 6` failed (exit status: 6): offline"
+        );
+        // The saved rates are kept
+        assert_eq!(ExchangeRates::load(&path).unwrap(), rates);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
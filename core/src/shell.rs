@@ -0,0 +1,196 @@
+//! Running the user's own commands, like `key_command`, with the platform
+//! shell.
+
+use std::io::Write;
+use std::process::{Command, ExitStatus, Stdio};
+use thiserror::Error;
+
+/// How a command's standard streams are connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Streams<'a> {
+    /// Read its output; the first line of stderr explains a failure
+    Capture,
+    /// Write the text to its standard input, ignore its output
+    Input(&'a str),
+    /// Connect nothing, for commands that may keep running in the background
+    /// and would hold on to a pipe
+    Discard,
+}
+
+/// Failure of a shell command, with the command line in every message
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ShellError {
+    #[error("can't run `{command}`: {reason}")]
+    Spawn { command: String, reason: String },
+
+    #[error("can't write to `{command}`: {reason}")]
+    Input { command: String, reason: String },
+
+    #[error("`{command}` failed ({status}){}", stderr_suffix(.stderr))]
+    Failed {
+        command: String,
+        status: String,
+        /// First line the command wrote to stderr, empty when not captured
+        stderr: String,
+    },
+}
+
+fn stderr_suffix(stderr: &str) -> String {
+    if stderr.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", stderr)
+    }
+}
+
+/// `sh -c <command> dealve <args>…`, or `cmd /C <command>` on Windows with
+/// the arguments in `DEALVE_ARG1`… and delayed expansion to read them
+fn command(command: &str, args: &[&str]) -> Command {
+    #[cfg(windows)]
+    let mut process = Command::new("cmd");
+    #[cfg(windows)]
+    {
+        if !args.is_empty() {
+            process.arg("/V:ON");
+        }
+        process.arg("/C").arg(command);
+        for (i, arg) in args.iter().enumerate() {
+            process.env(format!("DEALVE_ARG{}", i + 1), arg);
+        }
+    }
+    #[cfg(not(windows))]
+    let mut process = Command::new("sh");
+    #[cfg(not(windows))]
+    process.arg("-c").arg(command).arg("dealve").args(args);
+    process
+}
+
+/// How a command line refers to argument `index` (from 1) of `run_with_args`,
+/// quoted so the shell never parses its value
+pub fn argument(index: usize) -> String {
+    #[cfg(windows)]
+    let reference = format!("\"!DEALVE_ARG{}!\"", index);
+    #[cfg(not(windows))]
+    let reference = format!("\"${}\"", index);
+    reference
+}
+
+/// Run `command` with the shell and wait for it. Returns what it printed
+/// with `Streams::Capture`, an empty string otherwise.
+pub fn run(command_line: &str, streams: Streams<'_>) -> Result<String, ShellError> {
+    run_with_args(command_line, &[], streams)
+}
+
+/// `run` with arguments the command line refers to with `argument`, for text
+/// that doesn't come from the user, like a link from the API
+pub fn run_with_args(
+    command_line: &str,
+    args: &[&str],
+    streams: Streams<'_>,
+) -> Result<String, ShellError> {
+    let spawn_error = |e: std::io::Error| ShellError::Spawn {
+        command: command_line.to_string(),
+        reason: e.to_string(),
+    };
+    let mut process = command(command_line, args);
+    match streams {
+        Streams::Capture => {
+            let output = process
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .output()
+                .map_err(spawn_error)?;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().next().unwrap_or("").trim();
+            check(command_line, output.status, reason)?;
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Streams::Input(text) => {
+            let mut child = process
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(spawn_error)?;
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin
+                    .write_all(text.as_bytes())
+                    .map_err(|e| ShellError::Input {
+                        command: command_line.to_string(),
+                        reason: e.to_string(),
+                    })?;
+                // Dropping stdin closes it, which tells the command the text is complete
+            }
+            let status = child.wait().map_err(spawn_error)?;
+            check(command_line, status, "")?;
+            Ok(String::new())
+        }
+        Streams::Discard => {
+            let status = process
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map_err(spawn_error)?;
+            check(command_line, status, "")?;
+            Ok(String::new())
+        }
+    }
+}
+
+fn check(command: &str, status: ExitStatus, stderr: &str) -> Result<(), ShellError> {
+    if status.success() {
+        return Ok(());
+    }
+    Err(ShellError::Failed {
+        command: command.to_string(),
+        status: status.to_string(),
+        stderr: stderr.to_string(),
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_output_is_returned() {
+        assert_eq!(run("echo hello", Streams::Capture).unwrap(), "hello\n");
+        assert_eq!(run("echo hello >&2", Streams::Capture).unwrap(), "");
+    }
+
+    #[test]
+    fn failures_include_the_first_line_of_stderr() {
+        let error = run("echo nope >&2; echo more >&2; exit 3", Streams::Capture).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`echo nope >&2; echo more >&2; exit 3` failed (exit status: 3): nope"
+        );
+        let error = run("exit 1", Streams::Discard).unwrap_err();
+        assert_eq!(error.to_string(), "`exit 1` failed (exit status: 1)");
+    }
+
+    #[test]
+    fn input_is_written_to_stdin() {
+        run(
+            r#"read line; test "$line" = "some text""#,
+            Streams::Input("some text\n"),
+        )
+        .unwrap();
+        let error = run(
+            r#"read line; test "$line" = other"#,
+            Streams::Input("some text\n"),
+        )
+        .unwrap_err();
+        assert!(matches!(error, ShellError::Failed { .. }));
+    }
+
+    #[test]
+    fn arguments_are_not_parsed_by_the_shell() {
+        let text = "it's; $(echo no) `echo no` \"quoted\" $HOME";
+        let command_line = format!("printf %s {}", argument(1));
+        let output = run_with_args(&command_line, &[text], Streams::Capture).unwrap();
+        assert_eq!(output, text);
+    }
+}
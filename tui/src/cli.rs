@@ -0,0 +1,438 @@
+//! Command line arguments: `dealve [options]` runs the TUI, `dealve config
+//! show` prints the effective config and where each setting came from,
+//! `dealve export` writes the deals to a file and `dealve rates update`
+//! saves new exchange rates.
+
+use anyhow::{anyhow, bail, Context, Result};
+use dealve_core::query::DealQuery;
+use dealve_core::rates::ExchangeRates;
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::columns::DealsColumn;
+use crate::config::{Config, ConfigStore, FileConfigStore, Overrides, Source};
+use crate::export::{expand_path, Export, ExportFormat};
+use crate::model::Model;
+use crate::secrets;
+use crate::tasks::{DataSource, Loader};
+
+pub const USAGE: &str = "\
+Usage: dealve [options]
+       dealve config show
+       dealve export <path> [--format <format>] [--columns <list>] [--filter <query>]
+       dealve rates update
+       dealve setup
+
+Commands:
+  config show            Print the effective settings and where each comes from
+  export <path>          Write the first page of deals to a CSV, JSON, Markdown
+                         or HTML file
+  rates update           Run rates_command and save the exchange rates it prints
+  setup                  Enter the API key again and choose where it's kept
+
+Options:
+      --region <code>    Region for this session, e.g. US, FR, GB
+      --shop <name>      Shop to browse, e.g. steam, gog, \"epic game store\"
+      --sort <sort>      Sort order: price, cut, hottest, release, expiring or
+                         popular, optionally followed by :asc or :desc
+      --theme <name>     Color theme
+      --page-size <n>    Deals loaded per page
+      --config <path>    Config file to use instead of ~/.config/dealve/config.toml
+      --demo             Browse bundled sample deals, without an API key
+  -h, --help             Print this help
+  -V, --version          Print the version
+
+Export options (only with `dealve export`):
+      --format <format>  csv, json, markdown or html, from the file extension
+                         by default
+      --columns <list>   Columns to write, e.g. title,price,cut,url, instead of
+                         export_columns from the config
+      --filter <query>   Filter like in the TUI, e.g. \"cut>=75 shop:gog\"
+
+Options override the config file for this session only, and so do the
+DEALVE_REGION, DEALVE_SHOP, DEALVE_SORT, DEALVE_THEME, DEALVE_PAGE_SIZE,
+DEALVE_GAME_INFO_DELAY and DEALVE_ACCESSIBILITY environment variables.
+Options take precedence over environment variables.
+";
+
+/// Flags that override a setting, with the setting each one overrides
+const SETTING_FLAGS: &[(&str, &str)] = &[
+    ("--region", "region"),
+    ("--shop", "shop"),
+    ("--sort", "sort"),
+    ("--theme", "theme"),
+    ("--page-size", "page_size"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Run,
+    /// Run the onboarding again to replace the API key or where it's kept
+    Setup,
+    ShowConfig,
+    Export,
+    UpdateRates,
+    Help,
+    Version,
+}
+
+#[derive(Debug)]
+pub struct Args {
+    pub command: Command,
+    /// `--config`, falling back to `DEALVE_CONFIG`
+    pub config_path: Option<PathBuf>,
+    /// Use the bundled sample data instead of the API
+    pub demo: bool,
+    pub export: ExportArgs,
+    /// Flag, setting and value of each setting flag, in order
+    settings: Vec<(&'static str, &'static str, String)>,
+}
+
+/// Arguments of `dealve export`
+#[derive(Debug, Default)]
+pub struct ExportArgs {
+    pub path: Option<String>,
+    pub format: Option<String>,
+    pub columns: Option<String>,
+    pub filter: Option<String>,
+}
+
+impl Args {
+    /// Parse the arguments, without the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self {
+            command: Command::Run,
+            config_path: std::env::var_os("DEALVE_CONFIG").map(PathBuf::from),
+            demo: false,
+            export: ExportArgs::default(),
+            settings: Vec::new(),
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            // `--flag=value` or `--flag value`
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .with_context(|| format!("{} needs a value", flag))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => parsed.command = Command::Help,
+                "-V" | "--version" => parsed.command = Command::Version,
+                "--config" => parsed.config_path = Some(PathBuf::from(value()?)),
+                "--demo" => parsed.demo = true,
+                "--format" => parsed.export.format = Some(value()?),
+                "--columns" => parsed.export.columns = Some(value()?),
+                "--filter" => parsed.export.filter = Some(value()?),
+                "setup" => parsed.command = Command::Setup,
+                "export" => {
+                    parsed.command = Command::Export;
+                    parsed.export.path = Some(
+                        args.next()
+                            .context("missing file to export to, e.g. `export deals.csv`")?,
+                    );
+                }
+                "config" => match args.next().as_deref() {
+                    Some("show") => parsed.command = Command::ShowConfig,
+                    Some(other) => bail!("unknown config command `{}`, expected `show`", other),
+                    None => bail!("missing config command, expected `config show`"),
+                },
+                "rates" => match args.next().as_deref() {
+                    Some("update") => parsed.command = Command::UpdateRates,
+                    Some(other) => bail!("unknown rates command `{}`, expected `update`", other),
+                    None => bail!("missing rates command, expected `rates update`"),
+                },
+                _ => match SETTING_FLAGS.iter().find(|(name, _)| *name == flag) {
+                    Some(&(name, setting)) => parsed.settings.push((name, setting, value()?)),
+                    None => bail!("unknown argument `{}`, see `dealve --help`", arg),
+                },
+            }
+        }
+
+        if parsed.command != Command::Export {
+            let export_flags = [
+                ("--format", &parsed.export.format),
+                ("--columns", &parsed.export.columns),
+                ("--filter", &parsed.export.filter),
+            ];
+            if let Some((flag, _)) = export_flags.iter().find(|(_, value)| value.is_some()) {
+                bail!(
+                    "{} only applies to `dealve export`, see `dealve --help`",
+                    flag
+                );
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Overrides from the environment, then from the flags
+    pub fn overrides(&self) -> Result<Overrides> {
+        let mut overrides = Overrides::from_env();
+        for (flag, setting, value) in &self.settings {
+            overrides
+                .set(setting, value, Source::Flag(flag))
+                .map_err(|e| anyhow::anyhow!("{}: {}", flag, e))?;
+        }
+        Ok(overrides)
+    }
+
+    /// Store for the config file and overrides given on the command line
+    pub fn config_store(&self) -> Result<FileConfigStore> {
+        Ok(FileConfigStore::new(
+            self.config_path.clone(),
+            self.overrides()?,
+        ))
+    }
+}
+
+/// Print the effective config, one setting per line with its source
+pub fn show_config(store: &FileConfigStore) {
+    let config = store.load();
+
+    match store.path() {
+        Some(path) if path.exists() => println!("# {}", path.display()),
+        Some(path) => println!("# {} (not created yet)", path.display()),
+        None => println!("# no config directory, using defaults"),
+    }
+
+    let Ok(Value::Object(mut settings)) = serde_json::to_value(&config) else {
+        return;
+    };
+    settings.remove("version");
+    // The key itself is never printed
+    let api_key = if config.api_key.is_some() {
+        "<redacted>"
+    } else {
+        "<not set>"
+    };
+    settings.insert("api_key".to_string(), Value::from(api_key));
+    let presets: Vec<&str> = config.presets.iter().map(|p| p.name.as_str()).collect();
+
+    let rows: Vec<(String, String, Source)> = settings
+        .into_iter()
+        .map(|(key, value)| {
+            let shown = match (key.as_str(), &value) {
+                ("api_key", Value::String(text)) => text.clone(),
+                ("presets", _) => format!("{:?}", presets),
+                _ => value.to_string(),
+            };
+            let source = config.source(&key);
+            (key, shown, source)
+        })
+        .collect();
+
+    let key_width = rows.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+    for (key, value, source) in rows {
+        println!(
+            "{:width$} = {}  # {}",
+            key,
+            value,
+            source,
+            width = key_width
+        );
+    }
+
+    for warning in &config.warnings {
+        eprintln!("warning: {}", warning);
+    }
+}
+
+/// Run `rates_command` and save the exchange rates it prints
+pub fn update_rates(store: &FileConfigStore) -> Result<()> {
+    let config = store.load();
+    let command = config.rates_command.context(
+        "no rates_command in the config file, e.g. \
+         rates_command = \"curl -s https://api.frankfurter.dev/v1/latest\"",
+    )?;
+    let path = Config::rates_path().context("no config directory to save the rates to")?;
+    let rates = ExchangeRates::refresh(&command, &path)?;
+    println!(
+        "Saved {} exchange rates{} to {}",
+        rates.currencies().len(),
+        rates
+            .date()
+            .map(|date| format!(" of {}", date))
+            .unwrap_or_default(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Load the first page of deals like the TUI does and write them to a file
+pub async fn export(args: &Args) -> Result<()> {
+    let mut store = args.config_store()?;
+    let mut config = store.load();
+    for warning in &config.warnings {
+        eprintln!("warning: {}", warning);
+    }
+
+    let input = args.export.path.as_deref().unwrap_or_default();
+    let path = expand_path(input);
+    let format = match &args.export.format {
+        Some(name) => ExportFormat::parse(name).with_context(|| {
+            format!(
+                "unknown format `{}`, expected csv, json, markdown or html",
+                name
+            )
+        })?,
+        None => ExportFormat::from_path(&path).unwrap_or_default(),
+    };
+    let columns = match &args.export.columns {
+        Some(list) => list
+            .split(',')
+            .map(|id| {
+                DealsColumn::from_export_id(id.trim())
+                    .with_context(|| format!("--columns: unknown column `{}`", id.trim()))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => config.get_export_columns(),
+    };
+    if columns.is_empty() {
+        bail!("no columns to export");
+    }
+
+    let source = if args.demo {
+        DataSource::Demo
+    } else {
+        secrets::migrate_plain_text_key(&mut store, &mut config).await?;
+        match secrets::load_api_key(&config).await? {
+            Some(api_key) => DataSource::Api { api_key },
+            None => bail!("no API key, run `dealve setup` first or try `--demo`"),
+        }
+    };
+    let loader = source.loader();
+
+    let mut model = Model::with_store(Box::new(store));
+    if let Some(filter) = &args.export.filter {
+        let query = DealQuery::parse(filter).map_err(|e| anyhow!("--filter: {}", e))?;
+        model.active_search_query = query.text.clone();
+        model.query = query;
+    }
+
+    let region = model.region.code().to_string();
+    let task = match model.active_search_query.clone() {
+        Some(text) => loader.search(text, model.platform_filter, region, model.deals_page_size),
+        None => loader.deals(
+            model.platform_filter,
+            region,
+            0,
+            model.deals_page_size,
+            model.sort_state.api_param(),
+        ),
+    };
+    model.deals = task
+        .wait()
+        .await
+        .map_err(|_| anyhow!("loading deals failed"))??;
+
+    let export = Export {
+        path,
+        format,
+        columns,
+    };
+    // Tag filters need the game info of every loaded deal, the columns only
+    // that of the deals left after filtering
+    if model.query.needs_game_info() {
+        let all = model.deals.iter().map(|deal| deal.id.clone()).collect();
+        load_game_info(&mut model, loader.as_ref(), all).await;
+    }
+    let missing = export.missing_game_info(&model.filtered_deals(), &model.game_info_cache);
+    load_game_info(&mut model, loader.as_ref(), missing).await;
+
+    let deals = model.filtered_deals();
+    export
+        .write(&deals, &model.game_info_cache, model.region.money_format())
+        .with_context(|| format!("can't write {}", export.path.display()))?;
+    println!(
+        "Exported {} deals to {}",
+        deals.len(),
+        export.path.display()
+    );
+    Ok(())
+}
+
+async fn load_game_info(model: &mut Model, loader: &dyn Loader, ids: Vec<String>) {
+    if ids.is_empty() {
+        return;
+    }
+    let infos = loader.game_info_batch(ids).wait().await.unwrap_or_default();
+    for info in infos {
+        model.game_info_cache.insert(info.id.clone(), info);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Args> {
+        Args::parse(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn export_takes_its_options_before_or_after_the_path() {
+        let args = parse("--format md export deals.txt --columns=title,url --filter atl").unwrap();
+        assert_eq!(args.command, Command::Export);
+        assert_eq!(args.export.path.as_deref(), Some("deals.txt"));
+        assert_eq!(args.export.format.as_deref(), Some("md"));
+        assert_eq!(args.export.columns.as_deref(), Some("title,url"));
+        assert_eq!(args.export.filter.as_deref(), Some("atl"));
+    }
+
+    #[test]
+    fn export_options_are_rejected_elsewhere() {
+        for args in [
+            "--format csv",
+            "config show --columns title",
+            "--demo --filter atl",
+        ] {
+            let error = parse(args).unwrap_err().to_string();
+            assert!(
+                error.ends_with("only applies to `dealve export`, see `dealve --help`"),
+                "{}: {}",
+                args,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn setting_flags_are_kept_in_order() {
+        let args = parse("--region fr --sort=cut:desc --region uk").unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(
+            args.settings,
+            [
+                ("--region", "region", "fr".to_string()),
+                ("--sort", "sort", "cut:desc".to_string()),
+                ("--region", "region", "uk".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_arguments_are_reported() {
+        let error = |args: &str| parse(args).unwrap_err().to_string();
+        assert_eq!(
+            error("--nope"),
+            "unknown argument `--nope`, see `dealve --help`"
+        );
+        assert_eq!(error("--region"), "--region needs a value");
+        assert_eq!(
+            error("export"),
+            "missing file to export to, e.g. `export deals.csv`"
+        );
+        assert_eq!(
+            error("config edit"),
+            "unknown config command `edit`, expected `show`"
+        );
+    }
+}
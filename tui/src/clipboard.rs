@@ -0,0 +1,165 @@
+//! Copying to the clipboard. The text is sent to the terminal with the OSC 52
+//! escape sequence, which works over SSH and in tmux; terminals that ignore it
+//! get it from `clipboard_command` too.
+
+use std::io::{self, Write};
+
+use base64::Engine;
+use dealve_core::models::Deal;
+use dealve_core::money::MoneyFormat;
+use dealve_core::shell::{self, Streams};
+
+use crate::tasks::Task;
+
+pub type CopyTask = Task<Result<(), String>>;
+
+/// What the yank menu copies from the selected deals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YankItem {
+    Url,
+    ItadPage,
+    Title,
+    /// `Title — 4,99 € (-80%) at GOG <url>`
+    Summary,
+}
+
+impl YankItem {
+    pub const ALL: &'static [YankItem] = &[
+        YankItem::Url,
+        YankItem::ItadPage,
+        YankItem::Title,
+        YankItem::Summary,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            YankItem::Url => "Deal URL",
+            YankItem::ItadPage => "ITAD page",
+            YankItem::Title => "Title",
+            YankItem::Summary => "Summary",
+        }
+    }
+
+    /// What several of them are called, for the status line
+    pub fn plural(&self) -> &str {
+        match self {
+            YankItem::Url => "deal URLs",
+            YankItem::ItadPage => "ITAD pages",
+            YankItem::Title => "titles",
+            YankItem::Summary => "summaries",
+        }
+    }
+
+    /// Text copied for a deal, `None` when the deal doesn't have it
+    pub fn text(&self, deal: &Deal, money_format: MoneyFormat) -> Option<String> {
+        match self {
+            YankItem::Url => Some(deal.url.clone()),
+            YankItem::ItadPage => deal.itad_url(),
+            YankItem::Title => Some(deal.title.clone()),
+            YankItem::Summary => Some(format!(
+                "{} — {} (-{}%) at {} <{}>",
+                deal.title,
+                deal.price.amount.format(money_format),
+                deal.price.discount,
+                deal.shop.name,
+                deal.url
+            )),
+        }
+    }
+}
+
+/// Where copied text goes
+pub trait Clipboard {
+    /// Copy `text`, returning the task running `command` if there is one
+    fn copy(&self, text: &str, command: Option<&str>) -> Option<CopyTask>;
+}
+
+/// The terminal's clipboard, and the clipboard command's
+pub struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+    fn copy(&self, text: &str, command: Option<&str>) -> Option<CopyTask> {
+        // Written between two frames so it can't land inside a redraw
+        let sequence = osc52(text, std::env::var_os("TMUX").is_some());
+        let mut stdout = io::stdout();
+        if let Err(e) = stdout
+            .write_all(sequence.as_bytes())
+            .and_then(|_| stdout.flush())
+        {
+            tracing::warn!(error = %e, "writing OSC 52 failed");
+        }
+
+        let command = command?.to_string();
+        let text = text.to_string();
+        Some(Task::spawn(async move {
+            run_clipboard_command(command, text).await
+        }))
+    }
+}
+
+/// `ESC ] 52 ; c ; <base64> BEL`. tmux only forwards it with `set-clipboard
+/// on`, so inside tmux it's sent a second time wrapped in a passthrough,
+/// which needs `allow-passthrough on` instead.
+fn osc52(text: &str, tmux: bool) -> String {
+    let payload = base64::engine::general_purpose::STANDARD.encode(text);
+    let sequence = format!("\x1b]52;c;{}\x07", payload);
+    if tmux {
+        format!(
+            "{}\x1bPtmux;{}\x1b\\",
+            sequence,
+            sequence.replace('\x1b', "\x1b\x1b")
+        )
+    } else {
+        sequence
+    }
+}
+
+/// Run `clipboard_command` with the shell, the text on its standard input.
+/// xclip and wl-copy stay in the background owning the clipboard, so their
+/// output isn't read: it would only end when they exit.
+async fn run_clipboard_command(command: String, text: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || shell::run(&command, Streams::Input(&text)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn each_item_copies_its_text() {
+        let mut deal = fixtures::deals()
+            .into_iter()
+            .find(|deal| deal.title == "Celeste")
+            .unwrap();
+        let text = |item: YankItem, deal: &Deal| item.text(deal, MoneyFormat::default());
+        let url = "https://itad.link/018d937f-2a3f-7084-a6b8-4bd9a1f1c3d2/";
+        assert_eq!(text(YankItem::Url, &deal).as_deref(), Some(url));
+        assert_eq!(
+            text(YankItem::ItadPage, &deal).as_deref(),
+            Some("https://isthereanydeal.com/game/celeste/info/")
+        );
+        assert_eq!(text(YankItem::Title, &deal).as_deref(), Some("Celeste"));
+        assert_eq!(
+            text(YankItem::Summary, &deal),
+            Some(format!("Celeste — €3.99 (-80%) at GOG <{}>", url))
+        );
+
+        // Without a slug there's no ITAD page
+        deal.slug = None;
+        assert_eq!(text(YankItem::ItadPage, &deal), None);
+    }
+
+    #[test]
+    fn osc52_is_wrapped_for_tmux() {
+        assert_eq!(osc52("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(
+            osc52("hi", true),
+            "\x1b]52;c;aGk=\x07\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use ratatui::layout::Constraint;
+use std::collections::BTreeMap;
+
+/// A column of deals, in the table or in an export. In the table the status
+/// marker always comes first, and prices in the display currency follow the
+/// price column when set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DealsColumn {
+    Title,
+    Shop,
+    Price,
+    Regular,
+    /// Regular price minus the current one
+    Savings,
+    Cut,
+    /// Whether the price is the all-time low
+    Atl,
+    HistoryLow,
+    StoreLow,
+    /// Time left until the deal ends
+    Expiry,
+    ReleaseDate,
+    Tags,
+    Reviews,
+    /// Number of bundles the game has been in
+    Bundled,
+    /// Link to the deal, only in exports
+    Url,
+}
+
+impl DealsColumn {
+    /// Columns the table can show
+    pub const ALL: &'static [DealsColumn] = &[
+        DealsColumn::Title,
+        DealsColumn::Price,
+        DealsColumn::Cut,
+        DealsColumn::Atl,
+        DealsColumn::Shop,
+        DealsColumn::Regular,
+        DealsColumn::Savings,
+        DealsColumn::HistoryLow,
+        DealsColumn::StoreLow,
+        DealsColumn::Expiry,
+        DealsColumn::ReleaseDate,
+        DealsColumn::Tags,
+        DealsColumn::Reviews,
+        DealsColumn::Bundled,
+    ];
+
+    /// Columns an export can include
+    pub const EXPORT: &'static [DealsColumn] = &[
+        DealsColumn::Title,
+        DealsColumn::Shop,
+        DealsColumn::Price,
+        DealsColumn::Cut,
+        DealsColumn::Regular,
+        DealsColumn::Atl,
+        DealsColumn::Url,
+        DealsColumn::ReleaseDate,
+        DealsColumn::Tags,
+        DealsColumn::Savings,
+        DealsColumn::HistoryLow,
+        DealsColumn::StoreLow,
+        DealsColumn::Expiry,
+        DealsColumn::Reviews,
+        DealsColumn::Bundled,
+    ];
+
+    /// Name in the config file and on the command line, also the JSON key
+    pub fn id(&self) -> &str {
+        match self {
+            DealsColumn::Title => "title",
+            DealsColumn::Shop => "shop",
+            DealsColumn::Price => "price",
+            DealsColumn::Regular => "regular",
+            DealsColumn::Savings => "savings",
+            DealsColumn::Cut => "cut",
+            DealsColumn::Atl => "atl",
+            DealsColumn::HistoryLow => "history_low",
+            DealsColumn::StoreLow => "store_low",
+            DealsColumn::Expiry => "expiry",
+            DealsColumn::ReleaseDate => "release_date",
+            DealsColumn::Tags => "tags",
+            DealsColumn::Reviews => "reviews",
+            DealsColumn::Bundled => "bundled",
+            DealsColumn::Url => "url",
+        }
+    }
+
+    pub fn header(&self) -> &str {
+        match self {
+            DealsColumn::Title => "Title",
+            DealsColumn::Shop => "Shop",
+            DealsColumn::Price => "Price",
+            DealsColumn::Regular => "Regular",
+            DealsColumn::Savings => "Savings",
+            DealsColumn::Cut => "Deal",
+            DealsColumn::Atl => "",
+            DealsColumn::HistoryLow => "Hist. low",
+            DealsColumn::StoreLow => "Store low",
+            DealsColumn::Expiry => "Ends",
+            DealsColumn::ReleaseDate => "Release",
+            DealsColumn::Tags => "Tags",
+            DealsColumn::Reviews => "Reviews",
+            DealsColumn::Bundled => "Bundles",
+            DealsColumn::Url => "URL",
+        }
+    }
+
+    /// Name in the options popup, where the header may be empty
+    pub fn name(&self) -> &str {
+        match self {
+            DealsColumn::Atl => "ATL marker",
+            DealsColumn::Cut => "Discount",
+            DealsColumn::HistoryLow => "Historical low",
+            DealsColumn::Expiry => "Expiry countdown",
+            DealsColumn::ReleaseDate => "Release date",
+            DealsColumn::Reviews => "Review score",
+            DealsColumn::Bundled => "Bundled count",
+            _ => self.header(),
+        }
+    }
+
+    /// Header in exported files and the export popup, where there's room
+    /// for more than in the table
+    pub fn export_header(&self) -> &str {
+        match self {
+            DealsColumn::Cut => "Cut",
+            DealsColumn::Atl => "ATL",
+            DealsColumn::Expiry => "Expires",
+            _ => self.name(),
+        }
+    }
+
+    /// A column of the table
+    pub fn from_id(id: &str) -> Option<Self> {
+        DealsColumn::ALL.iter().find(|c| c.id() == id).copied()
+    }
+
+    /// A column of an export
+    pub fn from_export_id(id: &str) -> Option<Self> {
+        DealsColumn::EXPORT.iter().find(|c| c.id() == id).copied()
+    }
+
+    /// Width in cells when the config doesn't set one; the title's is a
+    /// minimum, and it takes the rest of the row
+    pub fn default_width(&self) -> u16 {
+        match self {
+            DealsColumn::Title => 20,
+            DealsColumn::Shop => 12,
+            DealsColumn::Cut => 7,
+            DealsColumn::Atl => 4,
+            DealsColumn::Expiry => 7,
+            DealsColumn::Tags => 24,
+            DealsColumn::Reviews | DealsColumn::Bundled => 8,
+            _ => 10,
+        }
+    }
+
+    /// Whether the column comes from game info rather than the deal
+    pub fn needs_game_info(&self) -> bool {
+        matches!(
+            self,
+            DealsColumn::ReleaseDate
+                | DealsColumn::Tags
+                | DealsColumn::Reviews
+                | DealsColumn::Bundled
+        )
+    }
+
+    /// Shown before anything is configured
+    fn visible_by_default(&self) -> bool {
+        matches!(
+            self,
+            DealsColumn::Title | DealsColumn::Price | DealsColumn::Cut | DealsColumn::Atl
+        )
+    }
+}
+
+/// Where a column goes in the table, if anywhere, and how wide it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnSetting {
+    pub column: DealsColumn,
+    pub visible: bool,
+    /// Width in cells, `None` for the column's default
+    pub width: Option<u16>,
+}
+
+impl ColumnSetting {
+    /// Narrowest width the options popup goes down to
+    pub const MIN_WIDTH: u16 = 3;
+
+    pub fn width(&self) -> u16 {
+        self.width.unwrap_or(self.column.default_width())
+    }
+
+    pub fn constraint(&self) -> Constraint {
+        if self.column == DealsColumn::Title {
+            Constraint::Min(self.width())
+        } else {
+            Constraint::Length(self.width())
+        }
+    }
+}
+
+/// Every column in its default place, the default ones shown
+pub fn default_columns() -> Vec<ColumnSetting> {
+    DealsColumn::ALL
+        .iter()
+        .map(|&column| ColumnSetting {
+            column,
+            visible: column.visible_by_default(),
+            width: None,
+        })
+        .collect()
+}
+
+/// Every column from the `columns` and `column_widths` config settings: the
+/// listed ones shown in order, then the others in their default order.
+/// Unknown or repeated ids and widths under the minimum are skipped, and the
+/// default columns are shown when none is listed.
+pub fn columns_from_config(ids: &[String], widths: &BTreeMap<String, u16>) -> Vec<ColumnSetting> {
+    let mut shown: Vec<DealsColumn> = Vec::new();
+    for column in ids.iter().filter_map(|id| DealsColumn::from_id(id)) {
+        if !shown.contains(&column) {
+            shown.push(column);
+        }
+    }
+    if shown.is_empty() {
+        shown = DealsColumn::ALL
+            .iter()
+            .copied()
+            .filter(DealsColumn::visible_by_default)
+            .collect();
+    }
+    let hidden = DealsColumn::ALL
+        .iter()
+        .copied()
+        .filter(|column| !shown.contains(column));
+    shown
+        .iter()
+        .map(|&column| (column, true))
+        .chain(hidden.map(|column| (column, false)))
+        .map(|(column, visible)| ColumnSetting {
+            column,
+            visible,
+            width: widths
+                .get(column.id())
+                .copied()
+                .filter(|&width| width >= ColumnSetting::MIN_WIDTH),
+        })
+        .collect()
+}
+
+/// The `columns` and `column_widths` config settings for the columns
+pub fn columns_to_config(columns: &[ColumnSetting]) -> (Vec<String>, BTreeMap<String, u16>) {
+    let ids = columns
+        .iter()
+        .filter(|setting| setting.visible)
+        .map(|setting| setting.column.id().to_string())
+        .collect();
+    let widths = columns
+        .iter()
+        .filter_map(|setting| Some((setting.column.id().to_string(), setting.width?)))
+        .collect();
+    (ids, widths)
+}
+
+/// Show or hide a column. The last one shown stays, so the table is never
+/// empty; returns whether the column changed.
+pub fn toggle_column(columns: &mut [ColumnSetting], index: usize) -> bool {
+    let shown = columns.iter().filter(|setting| setting.visible).count();
+    match columns.get_mut(index) {
+        Some(setting) if !setting.visible || shown > 1 => {
+            setting.visible = !setting.visible;
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn shown(columns: &[ColumnSetting]) -> Vec<&str> {
+        columns
+            .iter()
+            .filter(|setting| setting.visible)
+            .map(|setting| setting.column.id())
+            .collect()
+    }
+
+    #[test]
+    fn config_round_trip() {
+        let widths = BTreeMap::from([("title".to_string(), 30), ("tags".to_string(), 12)]);
+        let columns = columns_from_config(&ids(&["price", "title", "tags"]), &widths);
+        assert_eq!(columns.len(), DealsColumn::ALL.len());
+        assert_eq!(shown(&columns), ["price", "title", "tags"]);
+        // Hidden ones follow in their default order
+        assert_eq!(columns[3].column, DealsColumn::Cut);
+        assert!(!columns[3].visible);
+
+        let (saved_ids, saved_widths) = columns_to_config(&columns);
+        assert_eq!(saved_ids, ["price", "title", "tags"]);
+        assert_eq!(saved_widths, widths);
+        assert_eq!(columns_from_config(&saved_ids, &saved_widths), columns);
+    }
+
+    #[test]
+    fn unknown_and_repeated_columns_are_skipped() {
+        let widths = BTreeMap::from([("nope".to_string(), 8), ("shop".to_string(), 8)]);
+        let columns = columns_from_config(&ids(&["shop", "nope", "url", "shop", "cut"]), &widths);
+        assert_eq!(columns.len(), DealsColumn::ALL.len());
+        assert_eq!(shown(&columns), ["shop", "cut"]);
+        assert_eq!(columns_to_config(&columns).1.len(), 1);
+
+        // Nothing left to show, the defaults instead
+        let columns = columns_from_config(&ids(&["nope"]), &BTreeMap::new());
+        assert_eq!(columns, default_columns());
+    }
+
+    #[test]
+    fn widths_stay_above_the_minimum() {
+        let widths = BTreeMap::from([("title".to_string(), 2), ("shop".to_string(), 3)]);
+        let columns = columns_from_config(&ids(&["title", "shop"]), &widths);
+        assert_eq!(columns[0].width, None);
+        assert_eq!(columns[0].constraint(), Constraint::Min(20));
+        assert_eq!(columns[1].width(), 3);
+        assert_eq!(columns[1].constraint(), Constraint::Length(3));
+    }
+
+    #[test]
+    fn the_last_shown_column_stays() {
+        let mut columns = columns_from_config(&ids(&["title", "price"]), &BTreeMap::new());
+        assert!(toggle_column(&mut columns, 0));
+        assert!(!toggle_column(&mut columns, 1));
+        assert_eq!(shown(&columns), ["price"]);
+        assert!(toggle_column(&mut columns, 0));
+        assert_eq!(shown(&columns), ["title", "price"]);
+        assert!(!toggle_column(&mut columns, DealsColumn::ALL.len()));
+    }
+}
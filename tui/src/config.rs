@@ -1,14 +1,115 @@
-use crate::model::{SortCriteria, SortDirection, SortState};
-use crate::view::styles::Theme;
+use crate::columns::{self, ColumnSetting, DealsColumn};
+use crate::model::{PriceFilterState, SortCriteria, SortDirection, SortState};
+use crate::secrets::SecretBackend;
 use dealve_core::models::{Platform, Region};
+use dealve_core::money::{Amount, Currency};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::fs;
-use std::path::PathBuf;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Format of the config file. Bump it with a new step in `migrate`.
+const CONFIG_VERSION: u32 = 1;
+
+/// Largest page the deals API accepts
+const MAX_PAGE_SIZE: usize = 200;
+
+/// Where the config is loaded from and saved to
+pub trait ConfigStore {
+    fn load(&self) -> Config;
+    fn save(&mut self, config: &Config) -> Result<(), std::io::Error>;
+}
+
+/// The config file, `~/.config/dealve/config.toml` unless `--config` names
+/// another one, with the session's overrides applied on top
+pub struct FileConfigStore {
+    path: Option<PathBuf>,
+    overrides: Overrides,
+}
+
+impl FileConfigStore {
+    pub fn new(path: Option<PathBuf>, overrides: Overrides) -> Self {
+        Self {
+            path: path.or_else(Config::config_path),
+            overrides,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    fn load_file(&self) -> Config {
+        match &self.path {
+            Some(path) => Config::load_from(path),
+            None => Config::default(),
+        }
+    }
+}
+
+impl ConfigStore for FileConfigStore {
+    fn load(&self) -> Config {
+        let mut config = self.load_file();
+        config.apply(&self.overrides);
+        config
+    }
+
+    /// Overridden settings the user didn't change keep their value from the file
+    fn save(&mut self, config: &Config) -> Result<(), std::io::Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        config
+            .without(&self.overrides, &self.load_file())
+            .save_to(path)
+    }
+}
+
+/// Config kept in memory
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryConfigStore {
+    pub config: Config,
+    /// Make every save fail, to test error reporting
+    pub read_only: bool,
+}
+
+#[cfg(test)]
+impl MemoryConfigStore {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            read_only: false,
+        }
+    }
+}
+
+#[cfg(test)]
+impl ConfigStore for MemoryConfigStore {
+    fn load(&self) -> Config {
+        self.config.clone()
+    }
+
+    fn save(&mut self, config: &Config) -> Result<(), std::io::Error> {
+        if self.read_only {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "read-only config",
+            ));
+        }
+        self.config = config.clone();
+        Ok(())
+    }
+}
 
 /// Persistent configuration saved to disk
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Format of the file, files from before versioning are 0
+    #[serde(default)]
+    pub version: u32,
     pub default_platform: String,
     pub enabled_platforms: Vec<String>,
     #[serde(default = "default_region")]
@@ -19,9 +120,16 @@ pub struct Config {
     /// Debounce delay (ms) before loading game info after selection change
     #[serde(default = "default_game_info_delay")]
     pub game_info_delay_ms: u64,
-    /// IsThereAnyDeal API key (optional, can also be set via ITAD_API_KEY env var)
+    /// IsThereAnyDeal API key in plain text, from before secret backends.
+    /// Can also be set via ITAD_API_KEY env var.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    /// Where the API key was stored during onboarding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_backend: Option<SecretBackend>,
+    /// Command printing the API key, e.g. `pass show itad`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_command: Option<String>,
     /// Default sort criteria (Price, Cut, Hottest, Release, Expiring, Popular)
     #[serde(default = "default_sort_criteria")]
     pub default_sort_criteria: String,
@@ -31,6 +139,243 @@ pub struct Config {
     /// Color theme
     #[serde(default = "default_theme")]
     pub theme: String,
+    /// Accessibility mode: no colour, ASCII-only glyphs and no animations
+    #[serde(default)]
+    pub accessibility: bool,
+    /// Play transitions when the app starts and the deals reload
+    #[serde(default = "default_animations")]
+    pub animations: bool,
+    /// Regions shown side by side in the regional prices popup
+    #[serde(default = "default_compare_regions")]
+    pub compare_regions: Vec<String>,
+    /// Currency prices are converted to and shown beside, e.g. `USD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_currency: Option<String>,
+    /// Command printing today's exchange rates as JSON, e.g.
+    /// `curl -s https://api.frankfurter.dev/v1/latest`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rates_command: Option<String>,
+    /// Columns written by the export, in order: title, shop, price, cut,
+    /// regular, atl, url, release_date, tags
+    #[serde(default = "default_export_columns")]
+    pub export_columns: Vec<String>,
+    /// Columns of the deals table, in order: title, shop, price, regular,
+    /// savings, cut, atl, history_low, store_low, expiry, release_date,
+    /// tags, reviews, bundled
+    #[serde(default = "default_columns")]
+    pub columns: Vec<String>,
+    /// Width in cells of deals table columns, by column; the title's is a
+    /// minimum
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub column_widths: BTreeMap<String, u16>,
+    /// Command the yank menu also pipes copied text to, for terminals without
+    /// OSC 52, e.g. `wl-copy`, `xclip -selection clipboard` or `pbcopy`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clipboard_command: Option<String>,
+    /// Command opening links instead of the default browser, `{url}` standing
+    /// for the link, e.g. `firefox --new-tab {url}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_command: Option<String>,
+    /// Saved filter presets, selectable with number keys
+    #[serde(default)]
+    pub presets: Vec<Preset>,
+    /// Settings that were invalid when loading and fell back to their default
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+    /// Where each setting not left at its default came from
+    #[serde(skip)]
+    pub sources: BTreeMap<String, Source>,
+}
+
+/// Where the value of a setting came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File,
+    /// Environment variable
+    Env(&'static str),
+    /// Command line flag
+    Flag(&'static str),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File => write!(f, "config file"),
+            Source::Env(name) | Source::Flag(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Environment variables read by `Overrides::from_env`, with the setting each
+/// one overrides
+pub const ENV_VARS: &[(&str, &str)] = &[
+    ("DEALVE_REGION", "region"),
+    ("DEALVE_SHOP", "shop"),
+    ("DEALVE_SORT", "sort"),
+    ("DEALVE_THEME", "theme"),
+    ("DEALVE_PAGE_SIZE", "page_size"),
+    ("DEALVE_GAME_INFO_DELAY", "game_info_delay"),
+    ("DEALVE_ACCESSIBILITY", "accessibility"),
+    ("ITAD_API_KEY", "api_key"),
+];
+
+/// Settings given for one session by environment variables and command line
+/// flags. They apply on top of the config file and are never saved to it.
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    values: Vec<(&'static str, Value, Source)>,
+    /// Environment variables that were ignored because their value is invalid
+    warnings: Vec<String>,
+}
+
+impl Overrides {
+    /// Read the `DEALVE_*` variables and `ITAD_API_KEY`
+    pub fn from_env() -> Self {
+        let mut overrides = Self::default();
+        for &(var, setting) in ENV_VARS {
+            let Ok(raw) = std::env::var(var) else {
+                continue;
+            };
+            if raw.is_empty() {
+                continue;
+            }
+            if let Err(e) = overrides.set(setting, &raw, Source::Env(var)) {
+                overrides.warnings.push(format!("{}: {}", var, e));
+            }
+        }
+        overrides
+    }
+
+    /// Override a setting, replacing an earlier override of it. Settings are
+    /// `region`, `shop`, `sort` (`cut` or `cut:desc`), `theme`, `page_size`,
+    /// `game_info_delay`, `accessibility` and `api_key`.
+    pub fn set(&mut self, setting: &str, raw: &str, source: Source) -> Result<(), String> {
+        for (key, value) in setting_values(setting, raw)? {
+            self.values.retain(|(k, _, _)| *k != key);
+            self.values.push((key, value, source.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Config keys and values for a setting given as text
+fn setting_values(setting: &str, raw: &str) -> Result<Vec<(&'static str, Value)>, String> {
+    let raw = raw.trim();
+    let values = match setting {
+        "region" => {
+            let region =
+                Region::from_input(raw).ok_or_else(|| format!("unknown region `{}`", raw))?;
+            vec![("region", Value::from(region.code()))]
+        }
+        "shop" => {
+            let platform =
+                platform_from_input(raw).ok_or_else(|| format!("unknown shop `{}`", raw))?;
+            vec![("default_platform", Value::from(platform.name()))]
+        }
+        "sort" => {
+            let (criteria, direction) = match raw.split_once(':') {
+                Some((criteria, direction)) => (criteria, Some(direction)),
+                None => (raw, None),
+            };
+            let criteria = SORT_CRITERIA_NAMES
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(criteria))
+                .ok_or_else(|| format!("unknown sort `{}`", criteria))?;
+            let mut values = vec![("default_sort_criteria", Value::from(*criteria))];
+            if let Some(direction) = direction {
+                let direction = match direction.to_lowercase().as_str() {
+                    "asc" | "ascending" => SortDirection::Ascending,
+                    "desc" | "descending" => SortDirection::Descending,
+                    _ => return Err(format!("unknown sort direction `{}`", direction)),
+                };
+                values.push((
+                    "default_sort_direction",
+                    Value::from(sort_direction_name(direction)),
+                ));
+            }
+            values
+        }
+        "theme" => vec![("theme", Value::from(raw))],
+        "page_size" => {
+            let size = raw
+                .parse::<usize>()
+                .ok()
+                .filter(|size| (1..=MAX_PAGE_SIZE).contains(size))
+                .ok_or_else(|| format!("`{}` is not between 1 and {}", raw, MAX_PAGE_SIZE))?;
+            vec![("deals_page_size", Value::from(size))]
+        }
+        "game_info_delay" => {
+            let delay = raw
+                .parse::<u64>()
+                .map_err(|_| format!("`{}` is not a number of milliseconds", raw))?;
+            vec![("game_info_delay_ms", Value::from(delay))]
+        }
+        "accessibility" => {
+            let enabled = match raw.to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => true,
+                "0" | "false" | "no" | "off" => false,
+                _ => return Err(format!("`{}` is not true or false", raw)),
+            };
+            vec![("accessibility", Value::from(enabled))]
+        }
+        "api_key" => vec![("api_key", Value::from(raw))],
+        _ => return Err(format!("unknown setting `{}`", setting)),
+    };
+    Ok(values)
+}
+
+/// Named combination of region, shop, sort, price range and query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub region: String,
+    pub platform: String,
+    pub sort_criteria: String,
+    pub sort_direction: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_min: Option<Amount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_max: Option<Amount>,
+    /// Filter expression, e.g. `cut>=80 shop:gog`
+    #[serde(default)]
+    pub query: String,
+}
+
+impl Preset {
+    /// Capture the current view under a name
+    pub fn capture(
+        name: String,
+        region: Region,
+        platform: Platform,
+        sort: SortState,
+        price_filter: &PriceFilterState,
+        query: &str,
+    ) -> Self {
+        Self {
+            name,
+            region: region.code().to_string(),
+            platform: platform.name().to_string(),
+            sort_criteria: sort.criteria.name().to_string(),
+            sort_direction: sort_direction_name(sort.direction).to_string(),
+            price_min: price_filter.active_min,
+            price_max: price_filter.active_max,
+            query: query.to_string(),
+        }
+    }
+
+    pub fn get_region(&self) -> Region {
+        Region::from_code(&self.region).unwrap_or_default()
+    }
+
+    pub fn get_platform(&self) -> Platform {
+        platform_from_name(&self.platform)
+    }
+
+    pub fn get_sort(&self) -> SortState {
+        parse_sort(&self.sort_criteria, &self.sort_direction)
+    }
 }
 
 fn default_region() -> String {
@@ -57,67 +402,447 @@ fn default_theme() -> String {
     "default".to_string()
 }
 
+fn default_animations() -> bool {
+    true
+}
+
+fn default_compare_regions() -> Vec<String> {
+    ["US", "GB", "FR", "PL", "TR", "BR"]
+        .iter()
+        .map(|code| code.to_string())
+        .collect()
+}
+
+fn default_export_columns() -> Vec<String> {
+    ["title", "shop", "price", "cut", "regular", "url"]
+        .iter()
+        .map(|id| id.to_string())
+        .collect()
+}
+
+fn default_columns() -> Vec<String> {
+    columns::default_columns()
+        .iter()
+        .filter(|setting| setting.visible)
+        .map(|setting| setting.column.id().to_string())
+        .collect()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
-            default_platform: "All".to_string(),
+            version: CONFIG_VERSION,
+            default_platform: Platform::All.name().to_string(),
             enabled_platforms: Platform::ALL.iter().map(|p| p.name().to_string()).collect(),
             region: default_region(),
             deals_page_size: default_page_size(),
             game_info_delay_ms: default_game_info_delay(),
             api_key: None,
+            secret_backend: None,
+            key_command: None,
             default_sort_criteria: default_sort_criteria(),
             default_sort_direction: default_sort_direction(),
             theme: default_theme(),
+            accessibility: false,
+            animations: default_animations(),
+            compare_regions: default_compare_regions(),
+            display_currency: None,
+            rates_command: None,
+            export_columns: default_export_columns(),
+            columns: default_columns(),
+            column_widths: BTreeMap::new(),
+            clipboard_command: None,
+            open_command: None,
+            presets: Vec::new(),
+            warnings: Vec::new(),
+            sources: BTreeMap::new(),
+        }
+    }
+}
+
+/// Upgrade a config file to the current format, one version at a time
+fn migrate(file: &mut Map<String, Value>, warnings: &mut Vec<String>) {
+    let version = file.get("version").and_then(Value::as_u64).unwrap_or(0);
+
+    if version > CONFIG_VERSION as u64 {
+        warnings.push(format!(
+            "config version {} is newer than this dealve ({}), some settings may be ignored",
+            version, CONFIG_VERSION
+        ));
+        return;
+    }
+
+    // 0 → 1: regions were EU1/EU2/UK before they became country codes, and
+    // the default platform was saved as "All"
+    if version < 1 {
+        migrate_region(file.get_mut("region"));
+        if file.get("default_platform").and_then(Value::as_str) == Some("All") {
+            file.insert(
+                "default_platform".to_string(),
+                Value::from(Platform::All.name()),
+            );
         }
+        if let Some(Value::Array(presets)) = file.get_mut("presets") {
+            for preset in presets {
+                migrate_region(preset.get_mut("region"));
+            }
+        }
+    }
+
+    file.insert("version".to_string(), Value::from(CONFIG_VERSION));
+}
+
+fn migrate_region(region: Option<&mut Value>) {
+    let Some(region) = region else {
+        return;
+    };
+    if let Some(migrated) = region.as_str().and_then(Region::from_alias) {
+        *region = Value::from(migrated.code());
+    }
+}
+
+fn is_platform_name(name: &str) -> bool {
+    Platform::ALL.iter().any(|p| p.name() == name)
+}
+
+/// Platform for a shop typed by the user, ignoring case, spaces and dashes
+fn platform_from_input(input: &str) -> Option<Platform> {
+    let normalize = |name: &str| {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let input = normalize(input);
+    if input == "all" {
+        return Some(Platform::All);
+    }
+    Platform::ALL
+        .iter()
+        .find(|p| normalize(p.name()) == input)
+        .copied()
+}
+
+fn platform_from_name(name: &str) -> Platform {
+    Platform::ALL
+        .iter()
+        .find(|p| p.name() == name)
+        .copied()
+        .unwrap_or(Platform::All)
+}
+
+const SORT_CRITERIA_NAMES: &[&str] = &["Price", "Cut", "Hottest", "Release", "Expiring", "Popular"];
+
+fn sort_criteria_from_name(name: &str) -> Option<SortCriteria> {
+    match name {
+        "Price" => Some(SortCriteria::Price),
+        "Cut" => Some(SortCriteria::Cut),
+        "Hottest" => Some(SortCriteria::Hottest),
+        "Release" => Some(SortCriteria::ReleaseDate),
+        "Expiring" => Some(SortCriteria::Expiring),
+        "Popular" => Some(SortCriteria::Popular),
+        _ => None,
+    }
+}
+
+fn sort_direction_from_name(name: &str) -> Option<SortDirection> {
+    match name {
+        "Ascending" => Some(SortDirection::Ascending),
+        "Descending" => Some(SortDirection::Descending),
+        _ => None,
+    }
+}
+
+fn parse_sort(criteria: &str, direction: &str) -> SortState {
+    SortState {
+        criteria: sort_criteria_from_name(criteria).unwrap_or(SortCriteria::Price),
+        direction: sort_direction_from_name(direction).unwrap_or(SortDirection::Ascending),
+    }
+}
+
+fn sort_direction_name(direction: SortDirection) -> &'static str {
+    match direction {
+        SortDirection::Ascending => "Ascending",
+        SortDirection::Descending => "Descending",
     }
 }
 
 impl Config {
-    /// Get the config file path (~/.config/dealve/config.json)
+    /// Get the config file path (~/.config/dealve/config.toml)
     pub fn config_path() -> Option<PathBuf> {
-        dirs::config_dir().map(|p| p.join("dealve").join("config.json"))
+        dirs::config_dir().map(|p| p.join("dealve").join("config.toml"))
     }
 
-    /// Load config from disk, or return default if not found
-    pub fn load() -> Self {
-        let Some(path) = Self::config_path() else {
-            return Self::default();
+    /// Get the exchange rates file path (~/.config/dealve/rates.json)
+    pub fn rates_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("dealve").join("rates.json"))
+    }
+
+    /// Load a config file, or return default if not found. Invalid settings
+    /// fall back to their default and are listed in `warnings`. When a TOML
+    /// file doesn't exist yet, a JSON file next to it is read instead, so
+    /// the first save moves a `config.json` over to `config.toml`.
+    pub fn load_from(path: &Path) -> Self {
+        let legacy = path.with_extension("json");
+        let path = if !path.exists() && path != legacy && legacy.exists() {
+            legacy.as_path()
+        } else {
+            path
         };
 
-        if !path.exists() {
-            return Self::default();
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content, path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => Self {
+                warnings: vec![format!("can't read {}: {}", path.display(), e)],
+                ..Self::default()
+            },
         }
+    }
 
-        match fs::read_to_string(&path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => Self::default(),
+    /// Parse a config file, keeping every setting that is valid on its own
+    fn parse(content: &str, path: &Path) -> Self {
+        let mut warnings = Vec::new();
+
+        let parsed = if is_json(path) {
+            serde_json::from_str(content).map_err(|e| e.to_string())
+        } else {
+            toml::from_str::<toml::Value>(content)
+                .map_err(|e| e.message().to_string())
+                .and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+        };
+        let mut file = match parsed {
+            Ok(Value::Object(file)) => file,
+            result => {
+                let error = match result {
+                    Err(e) => e,
+                    Ok(_) => "not a table of settings".to_string(),
+                };
+                // Keep the broken file, the next save replaces it
+                let kept = with_suffix(path, "invalid");
+                let kept = match fs::copy(path, &kept) {
+                    Ok(_) => format!(", kept as {}", kept.display()),
+                    Err(_) => String::new(),
+                };
+                warnings.push(format!(
+                    "invalid config file ({}), using defaults{}",
+                    error, kept
+                ));
+                return Self {
+                    warnings,
+                    ..Self::default()
+                };
+            }
+        };
+
+        migrate(&mut file, &mut warnings);
+
+        // Take settings one at a time so a bad one doesn't discard the others
+        let mut merged = match serde_json::to_value(Self::default()) {
+            Ok(Value::Object(defaults)) => defaults,
+            _ => Map::new(),
+        };
+        let mut sources = BTreeMap::new();
+        for (key, value) in file {
+            let mut candidate = merged.clone();
+            candidate.insert(key.clone(), value.clone());
+            match serde_json::from_value::<Self>(Value::Object(candidate)) {
+                Ok(_) => {
+                    sources.insert(key.clone(), Source::File);
+                    merged.insert(key, value);
+                }
+                Err(e) => warnings.push(format!("`{}`: {}", key, e)),
+            }
         }
+
+        let mut config: Self = serde_json::from_value(Value::Object(merged)).unwrap_or_default();
+        config.sources = sources;
+        config.validate(&mut warnings);
+        config.warnings = warnings;
+        config
     }
 
-    /// Save config to disk
-    pub fn save(&self) -> Result<(), std::io::Error> {
-        let Some(path) = Self::config_path() else {
-            return Ok(());
+    /// Apply overrides on top of the settings from the file
+    fn apply(&mut self, overrides: &Overrides) {
+        self.warnings.extend(overrides.warnings.iter().cloned());
+        if overrides.values.is_empty() {
+            return;
+        }
+        let Ok(Value::Object(mut settings)) = serde_json::to_value(&*self) else {
+            return;
+        };
+        for (key, value, _) in &overrides.values {
+            settings.insert(key.to_string(), value.clone());
+        }
+        if let Ok(mut config) = serde_json::from_value::<Self>(Value::Object(settings)) {
+            config.warnings = std::mem::take(&mut self.warnings);
+            config.sources = std::mem::take(&mut self.sources);
+            for (key, _, source) in &overrides.values {
+                config.sources.insert(key.to_string(), source.clone());
+            }
+            *self = config;
+        }
+    }
+
+    /// Copy of this config with overridden settings that still have their
+    /// override value put back to their value in `file`
+    fn without(&self, overrides: &Overrides, file: &Config) -> Self {
+        let (Ok(Value::Object(mut settings)), Ok(Value::Object(file))) =
+            (serde_json::to_value(self), serde_json::to_value(file))
+        else {
+            return self.clone();
         };
+        for (key, value, _) in &overrides.values {
+            if settings.get(*key) != Some(value) {
+                continue;
+            }
+            match file.get(*key) {
+                Some(saved) => settings.insert(key.to_string(), saved.clone()),
+                None => settings.remove(*key),
+            };
+        }
+        serde_json::from_value(Value::Object(settings)).unwrap_or_else(|_| self.clone())
+    }
+
+    /// Where a setting's value came from
+    pub fn source(&self, key: &str) -> Source {
+        self.sources.get(key).cloned().unwrap_or(Source::Default)
+    }
+
+    /// Reset settings that parse but mean nothing to their default
+    fn validate(&mut self, warnings: &mut Vec<String>) {
+        let defaults = Self::default();
+
+        if Region::from_code(&self.region).is_none() {
+            warnings.push(format!("`region`: unknown region `{}`", self.region));
+            self.region = defaults.region;
+            self.sources.remove("region");
+        }
+        if !is_platform_name(&self.default_platform) {
+            warnings.push(format!(
+                "`default_platform`: unknown platform `{}`",
+                self.default_platform
+            ));
+            self.default_platform = defaults.default_platform;
+            self.sources.remove("default_platform");
+        }
+        for name in &self.enabled_platforms {
+            if !is_platform_name(name) {
+                warnings.push(format!("`enabled_platforms`: unknown platform `{}`", name));
+            }
+        }
+        self.enabled_platforms.retain(|name| is_platform_name(name));
+        for code in &self.compare_regions {
+            if Region::from_code(code).is_none() {
+                warnings.push(format!("`compare_regions`: unknown region `{}`", code));
+            }
+        }
+        self.compare_regions
+            .retain(|code| Region::from_code(code).is_some());
+        if let Some(code) = &self.display_currency {
+            if Currency::new(code).is_none() {
+                warnings.push(format!("`display_currency`: unknown currency `{}`", code));
+                self.display_currency = defaults.display_currency;
+                self.sources.remove("display_currency");
+            }
+        }
+        for id in &self.export_columns {
+            if DealsColumn::from_export_id(id).is_none() {
+                warnings.push(format!("`export_columns`: unknown column `{}`", id));
+            }
+        }
+        self.export_columns
+            .retain(|id| DealsColumn::from_export_id(id).is_some());
+        let mut seen_columns = HashSet::new();
+        for id in &self.columns {
+            if DealsColumn::from_id(id).is_none() {
+                warnings.push(format!("`columns`: unknown column `{}`", id));
+            } else if !seen_columns.insert(id.clone()) {
+                warnings.push(format!("`columns`: `{}` is listed twice", id));
+            }
+        }
+        let mut seen_columns = HashSet::new();
+        self.columns
+            .retain(|id| DealsColumn::from_id(id).is_some() && seen_columns.insert(id.clone()));
+        if self.columns.is_empty() {
+            warnings.push("`columns`: no column to show, using the defaults".to_string());
+            self.columns = defaults.columns.clone();
+        }
+        for (id, width) in &self.column_widths {
+            if DealsColumn::from_id(id).is_none() {
+                warnings.push(format!("`column_widths`: unknown column `{}`", id));
+            } else if *width < ColumnSetting::MIN_WIDTH {
+                warnings.push(format!(
+                    "`column_widths`: `{}` is narrower than {} cells",
+                    id,
+                    ColumnSetting::MIN_WIDTH
+                ));
+            }
+        }
+        self.column_widths.retain(|id, width| {
+            DealsColumn::from_id(id).is_some() && *width >= ColumnSetting::MIN_WIDTH
+        });
+        if self.api_key.is_some() {
+            warnings.push(
+                "`api_key`: stored in plain text, run `dealve setup` to move it to a secret store"
+                    .to_string(),
+            );
+        }
+        if !(1..=MAX_PAGE_SIZE).contains(&self.deals_page_size) {
+            warnings.push(format!(
+                "`deals_page_size`: {} is not between 1 and {}",
+                self.deals_page_size, MAX_PAGE_SIZE
+            ));
+            self.deals_page_size = defaults.deals_page_size;
+            self.sources.remove("deals_page_size");
+        }
+        if sort_criteria_from_name(&self.default_sort_criteria).is_none() {
+            warnings.push(format!(
+                "`default_sort_criteria`: unknown sort `{}`",
+                self.default_sort_criteria
+            ));
+            self.default_sort_criteria = defaults.default_sort_criteria;
+            self.sources.remove("default_sort_criteria");
+        }
+        if sort_direction_from_name(&self.default_sort_direction).is_none() {
+            warnings.push(format!(
+                "`default_sort_direction`: unknown direction `{}`",
+                self.default_sort_direction
+            ));
+            self.default_sort_direction = defaults.default_sort_direction;
+            self.sources.remove("default_sort_direction");
+        }
+    }
 
+    /// Save config to a file, as JSON if its extension is `.json` and TOML
+    /// otherwise. The previous file is kept with a `.bak` suffix and the new
+    /// one swapped in whole, so a crash never truncates it.
+    pub fn save_to(&self, path: &Path) -> Result<(), std::io::Error> {
         // Create config directory if it doesn't exist
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(&path, content)?;
+        let content = if is_json(path) {
+            serde_json::to_string_pretty(self)?
+        } else {
+            toml::to_string_pretty(self).map_err(std::io::Error::other)?
+        };
+
+        if path.exists() {
+            fs::copy(path, with_suffix(path, "bak"))?;
+        }
+        let temp_path = with_suffix(path, "tmp");
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(content.as_bytes())?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, path)?;
         Ok(())
     }
 
     /// Convert default_platform string to Platform enum
     pub fn get_default_platform(&self) -> Platform {
-        Platform::ALL
-            .iter()
-            .find(|p| p.name() == self.default_platform)
-            .copied()
-            .unwrap_or(Platform::All)
+        platform_from_name(&self.default_platform)
     }
 
     /// Convert enabled_platforms strings to Platform HashSet
@@ -133,9 +858,35 @@ impl Config {
         Region::from_code(&self.region).unwrap_or_default()
     }
 
-    /// Get the theme from config
-    pub fn get_theme(&self) -> Theme {
-        Theme::from_id(&self.theme).unwrap_or_default()
+    /// Regions to compare, in the order of the config
+    pub fn get_compare_regions(&self) -> Vec<Region> {
+        self.compare_regions
+            .iter()
+            .filter_map(|code| Region::from_code(code))
+            .collect()
+    }
+
+    /// Columns to export, in the order of the config
+    pub fn get_export_columns(&self) -> Vec<DealsColumn> {
+        self.export_columns
+            .iter()
+            .filter_map(|id| DealsColumn::from_export_id(id))
+            .collect()
+    }
+
+    /// Every column of the deals table: the shown ones in order, then the
+    /// others in their default order
+    pub fn get_columns(&self) -> Vec<ColumnSetting> {
+        columns::columns_from_config(&self.columns, &self.column_widths)
+    }
+
+    /// Store the deals table columns from the options popup
+    pub fn set_columns(&mut self, columns: &[ColumnSetting]) {
+        (self.columns, self.column_widths) = columns::columns_to_config(columns);
+    }
+
+    pub fn get_display_currency(&self) -> Option<Currency> {
+        self.display_currency.as_deref().and_then(Currency::new)
     }
 
     /// Update from OptionsState
@@ -145,7 +896,7 @@ impl Config {
         enabled_platforms: &HashSet<Platform>,
         region: Region,
         default_sort: SortState,
-        theme: Theme,
+        theme: &str,
     ) {
         self.default_platform = default_platform.name().to_string();
         self.enabled_platforms = enabled_platforms
@@ -154,52 +905,297 @@ impl Config {
             .collect();
         self.region = region.code().to_string();
         self.default_sort_criteria = default_sort.criteria.name().to_string();
-        self.default_sort_direction = match default_sort.direction {
-            SortDirection::Ascending => "Ascending".to_string(),
-            SortDirection::Descending => "Descending".to_string(),
-        };
-        self.theme = theme.id().to_string();
+        self.default_sort_direction = sort_direction_name(default_sort.direction).to_string();
+        self.theme = theme.to_string();
     }
 
     /// Get the default sort state from config
     pub fn get_default_sort(&self) -> SortState {
-        let criteria = match self.default_sort_criteria.as_str() {
-            "Price" => SortCriteria::Price,
-            "Cut" => SortCriteria::Cut,
-            "Hottest" => SortCriteria::Hottest,
-            "Release" => SortCriteria::ReleaseDate,
-            "Expiring" => SortCriteria::Expiring,
-            "Popular" => SortCriteria::Popular,
-            _ => SortCriteria::Price,
-        };
-        let direction = match self.default_sort_direction.as_str() {
-            "Descending" => SortDirection::Descending,
-            _ => SortDirection::Ascending,
-        };
-        SortState {
-            criteria,
-            direction,
+        parse_sort(&self.default_sort_criteria, &self.default_sort_direction)
+    }
+
+    /// Insert a preset, replacing any existing preset with the same name
+    pub fn upsert_preset(&mut self, preset: Preset) {
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
         }
     }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
 
-    /// Set the API key and save to config
-    pub fn set_api_key(&mut self, key: String) -> Result<(), std::io::Error> {
-        self.api_key = Some(key);
-        self.save()
+/// `config.toml` → `config.toml.<suffix>`
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty directory of its own for each test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dealve-config-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
     }
 
-    /// Load API key from environment variable or config file
-    /// Priority: 1. ITAD_API_KEY env var, 2. config file
-    pub fn load_api_key() -> Option<String> {
-        // Priority 1: Environment variable
-        if let Ok(key) = std::env::var("ITAD_API_KEY") {
-            if !key.is_empty() {
-                return Some(key);
-            }
-        }
+    const V0_TOML: &str = r#"
+default_platform = "All"
+enabled_platforms = ["Steam", "GOG"]
+region = "UK"
+deals_page_size = 100
+
+[[presets]]
+name = "cheap"
+region = "EU1"
+platform = "Steam"
+sort_criteria = "Price"
+sort_direction = "Ascending"
+"#;
+
+    #[test]
+    fn migrate_v0_toml() {
+        let config = Config::parse(V0_TOML, Path::new("config.toml"));
+        assert_eq!(config.warnings, Vec::<String>::new());
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.region, "GB");
+        assert_eq!(config.default_platform, Platform::All.name());
+        assert_eq!(config.deals_page_size, 100);
+        assert_eq!(config.presets[0].region, "FR");
+        assert_eq!(config.source("region"), Source::File);
+        assert_eq!(config.source("theme"), Source::Default);
+    }
+
+    #[test]
+    fn migrate_v0_json() {
+        let content = r#"{
+            "default_platform": "All",
+            "enabled_platforms": ["Steam"],
+            "region": "EU2",
+            "presets": [{"name": "uk", "region": "UK", "platform": "Steam",
+                "sort_criteria": "Cut", "sort_direction": "Descending"}]
+        }"#;
+        let config = Config::parse(content, Path::new("config.json"));
+        assert!(config.warnings.is_empty());
+        assert_eq!(config.region, "PL");
+        assert_eq!(config.presets[0].region, "GB");
+        assert_eq!(config.get_region(), Region::PL);
+    }
+
+    #[test]
+    fn v1_files_are_left_as_they_are() {
+        // The old codes mean nothing once migrated
+        let config = Config::parse("version = 1\nregion = \"UK\"", Path::new("config.toml"));
+        assert_eq!(config.region, Region::default().code());
+        assert_eq!(config.warnings, ["`region`: unknown region `UK`"]);
+    }
+
+    #[test]
+    fn newer_versions_are_read_with_a_warning() {
+        let config = Config::parse("version = 99\nregion = \"DE\"", Path::new("config.toml"));
+        assert_eq!(config.region, "DE");
+        assert_eq!(
+            config.warnings,
+            [format!(
+                "config version 99 is newer than this dealve ({}), some settings may be ignored",
+                CONFIG_VERSION
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_settings_fall_back_on_their_own() {
+        let content = r#"
+version = 1
+region = "DE"
+deals_page_size = "many"
+game_info_delay_ms = 300
+default_sort_criteria = "Vibes"
+compare_regions = ["US", "XX", "FR"]
+columns = ["title", "nope", "price", "title"]
+column_widths = { shop = 1, nope = 5, price = 8 }
+"#;
+        let config = Config::parse(content, Path::new("config.toml"));
+        assert_eq!(config.region, "DE");
+        assert_eq!(config.game_info_delay_ms, 300);
+        assert_eq!(config.deals_page_size, default_page_size());
+        assert_eq!(config.default_sort_criteria, default_sort_criteria());
+        assert_eq!(config.compare_regions, ["US", "FR"]);
+        assert_eq!(config.columns, ["title", "price"]);
+        assert_eq!(
+            config.column_widths,
+            BTreeMap::from([("price".to_string(), 8)])
+        );
+        assert_eq!(config.source("deals_page_size"), Source::Default);
+        assert_eq!(config.source("default_sort_criteria"), Source::Default);
+        assert_eq!(
+            config.warnings,
+            [
+                "`deals_page_size`: invalid type: string \"many\", expected usize",
+                "`compare_regions`: unknown region `XX`",
+                "`columns`: unknown column `nope`",
+                "`columns`: `title` is listed twice",
+                "`column_widths`: unknown column `nope`",
+                "`column_widths`: `shop` is narrower than 3 cells",
+                "`default_sort_criteria`: unknown sort `Vibes`",
+            ]
+        );
+    }
+
+    #[test]
+    fn an_empty_table_shows_the_default_columns() {
+        let config = Config::parse("version = 1\ncolumns = [\"url\"]", Path::new("c.toml"));
+        assert_eq!(config.columns, default_columns());
+        assert_eq!(
+            config.warnings,
+            [
+                "`columns`: unknown column `url`",
+                "`columns`: no column to show, using the defaults",
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_range_page_size_is_reset() {
+        let config = Config::parse("version = 1\ndeals_page_size = 500", Path::new("c.toml"));
+        assert_eq!(config.deals_page_size, default_page_size());
+        assert_eq!(
+            config.warnings,
+            ["`deals_page_size`: 500 is not between 1 and 200"]
+        );
+    }
+
+    #[test]
+    fn broken_files_are_kept_aside() {
+        let dir = temp_dir("broken");
+        let path = dir.join("config.toml");
+        fs::write(&path, "region = ").unwrap();
+
+        let config = Config::load_from(&path);
+        assert_eq!(config.region, Region::default().code());
+        assert_eq!(config.warnings.len(), 1);
+        assert!(config.warnings[0].starts_with("invalid config file ("));
+        assert_eq!(
+            fs::read_to_string(dir.join("config.toml.invalid")).unwrap(),
+            "region = "
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_legacy_json_file_is_saved_as_toml_with_a_backup() {
+        let dir = temp_dir("legacy");
+        let path = dir.join("config.toml");
+        fs::write(dir.join("config.json"), r#"{"region": "UK"}"#).unwrap();
+
+        let mut config = Config::load_from(&path);
+        assert_eq!(config.region, "GB");
+        config.save_to(&path).unwrap();
+        assert!(!dir.join("config.toml.bak").exists());
+
+        config.region = "DE".to_string();
+        config.save_to(&path).unwrap();
+        let backup = fs::read_to_string(dir.join("config.toml.bak")).unwrap();
+        assert!(backup.contains("region = \"GB\""));
+        assert!(!dir.join("config.toml.tmp").exists());
+
+        let saved = Config::load_from(&path);
+        assert_eq!(saved.region, "DE");
+        assert_eq!(saved.version, CONFIG_VERSION);
+        assert!(saved.warnings.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn preset_price_ranges_are_read_exactly() {
+        let dir = temp_dir("presets");
+        let path = dir.join("config.toml");
+        fs::write(
+            &path,
+            "version = 1\n\n[[presets]]\nname = \"cheap\"\nregion = \"US\"\n\
+             platform = \"All\"\nsort_criteria = \"price\"\nsort_direction = \"asc\"\n\
+             price_min = 5\nprice_max = 19.99\n",
+        )
+        .unwrap();
+        let config = Config::load_from(&path);
+        assert!(config.warnings.is_empty(), "{:?}", config.warnings);
+        assert_eq!(config.presets[0].price_min, Amount::parse("5"));
+        assert_eq!(config.presets[0].price_max, Amount::parse("19.99"));
+
+        config.save_to(&path).unwrap();
+        assert_eq!(
+            Config::load_from(&path).presets[0].price_max,
+            Amount::parse("19.99")
+        );
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("price_max = 19.99"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn overrides_shadow_the_file_and_are_not_saved() {
+        let dir = temp_dir("overrides");
+        let path = dir.join("config.toml");
+        fs::write(&path, "version = 1\nregion = \"DE\"\ntheme = \"nord\"\n").unwrap();
+        let mut overrides = Overrides::default();
+        overrides
+            .set("region", "fr", Source::Env("DEALVE_REGION"))
+            .unwrap();
+        overrides
+            .set("region", "us", Source::Flag("--region"))
+            .unwrap();
+        overrides
+            .set("page_size", "25", Source::Flag("--page-size"))
+            .unwrap();
+        let mut store = FileConfigStore::new(Some(path.clone()), overrides);
+
+        // The last override wins
+        let mut config = store.load();
+        assert_eq!(config.region, "US");
+        assert_eq!(config.deals_page_size, 25);
+        assert_eq!(config.theme, "nord");
+        assert_eq!(config.source("region"), Source::Flag("--region"));
+        assert_eq!(config.source("theme"), Source::File);
+
+        // Saving another change keeps the file's values of overridden settings
+        config.theme = "dracula".to_string();
+        store.save(&config).unwrap();
+        let saved = Config::load_from(&path);
+        assert_eq!(saved.region, "DE");
+        assert_eq!(saved.deals_page_size, default_page_size());
+        assert!(!fs::read_to_string(&path)
+            .unwrap()
+            .contains("deals_page_size = 25"));
+        assert_eq!(saved.theme, "dracula");
+
+        // A setting changed during the session is saved
+        config.region = "GB".to_string();
+        store.save(&config).unwrap();
+        assert_eq!(Config::load_from(&path).region, "GB");
+        assert_eq!(store.load().region, "US");
+        fs::remove_dir_all(&dir).unwrap();
+    }
 
-        // Priority 2: Config file
-        let config = Self::load();
-        config.api_key.filter(|k| !k.is_empty())
+    #[test]
+    fn old_region_codes_are_accepted_as_overrides() {
+        let mut overrides = Overrides::default();
+        overrides
+            .set("region", "uk", Source::Flag("--region"))
+            .unwrap();
+        assert_eq!(overrides.values[0].1, Value::from("GB"));
+        assert_eq!(
+            overrides.set("region", "EU9", Source::Flag("--region")),
+            Err("unknown region `EU9`".to_string())
+        );
     }
 }
@@ -0,0 +1,211 @@
+//! Demo mode: deals, game info and price history from the bundled fixtures
+//! instead of the IsThereAnyDeal API, so the app can be tried without a key.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDate};
+use dealve_core::models::{
+    Deal, GameInfo, Platform, PriceHistoryPoint, Region, RegionalOffer, RegionalPrice,
+};
+use dealve_core::DealveError;
+
+use crate::fixtures;
+use crate::tasks::{
+    DealsLoadTask, GameInfoBatchTask, GameInfoTask, Loader, PriceHistoryTask, RegionalPricesTask,
+    Task,
+};
+
+/// Delay before each result, so loading states show up as they would online
+const LATENCY: Duration = Duration::from_millis(150);
+
+struct Data {
+    deals: Vec<Deal>,
+    game_info: HashMap<String, GameInfo>,
+    price_history: HashMap<String, Vec<PriceHistoryPoint>>,
+    regional_prices: HashMap<String, Vec<RegionalPrice>>,
+}
+
+/// Loader answering from the fixtures in `tui/fixtures/`. Prices are the
+/// same whatever the region, except in the regional prices of the few games
+/// that have some.
+pub struct DemoLoader {
+    data: Arc<Data>,
+}
+
+impl DemoLoader {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Data {
+                deals: fixtures::deals(),
+                game_info: fixtures::game_info()
+                    .into_iter()
+                    .map(|info| (info.id.clone(), info))
+                    .collect(),
+                price_history: fixtures::price_history(),
+                regional_prices: fixtures::regional_prices(),
+            }),
+        }
+    }
+
+    fn spawn<T: Send + 'static>(&self, load: impl FnOnce(&Data) -> T + Send + 'static) -> Task<T> {
+        let data = self.data.clone();
+        Task::spawn(async move {
+            tokio::time::sleep(LATENCY).await;
+            load(&data)
+        })
+    }
+}
+
+impl Loader for DemoLoader {
+    fn deals(
+        &self,
+        platform: Platform,
+        _region_code: String,
+        offset: usize,
+        page_size: usize,
+        sort: String,
+    ) -> DealsLoadTask {
+        self.spawn(move |data| {
+            let mut deals: Vec<Deal> = data
+                .deals
+                .iter()
+                .filter(|deal| {
+                    platform
+                        .shop_id()
+                        .is_none_or(|id| deal.shop.id == id.to_string())
+                })
+                .cloned()
+                .collect();
+            sort_deals(&mut deals, &sort, &data.game_info);
+            Ok(deals.into_iter().skip(offset).take(page_size).collect())
+        })
+    }
+
+    fn search(
+        &self,
+        query: String,
+        platform: Platform,
+        _region_code: String,
+        limit: usize,
+    ) -> DealsLoadTask {
+        self.spawn(move |data| {
+            let query = query.to_lowercase();
+            Ok(data
+                .deals
+                .iter()
+                .filter(|deal| {
+                    platform
+                        .shop_id()
+                        .is_none_or(|id| deal.shop.id == id.to_string())
+                })
+                .filter(|deal| deal.title.to_lowercase().contains(&query))
+                .take(limit)
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn game_info(&self, game_id: String) -> GameInfoTask {
+        self.spawn(move |data| {
+            let result = data
+                .game_info
+                .get(&game_id)
+                .cloned()
+                .ok_or_else(|| DealveError::Api(format!("No demo data for game {}", game_id)));
+            (game_id, result)
+        })
+    }
+
+    fn game_info_batch(&self, game_ids: Vec<String>) -> GameInfoBatchTask {
+        self.spawn(move |data| {
+            game_ids
+                .iter()
+                .filter_map(|id| data.game_info.get(id).cloned())
+                .collect()
+        })
+    }
+
+    fn price_history(&self, game_id: String, _region_code: String) -> PriceHistoryTask {
+        self.spawn(move |data| {
+            let history = data
+                .price_history
+                .get(&game_id)
+                .cloned()
+                .unwrap_or_default();
+            (game_id, Ok(history))
+        })
+    }
+
+    fn regional_prices(&self, game_id: String, regions: Vec<Region>) -> RegionalPricesTask {
+        self.spawn(move |data| {
+            let offer = data
+                .deals
+                .iter()
+                .filter(|deal| deal.id == game_id)
+                .min_by_key(|deal| deal.price.amount.minor())
+                .map(|deal| RegionalOffer {
+                    shop: deal.shop.clone(),
+                    price: deal.price.clone(),
+                    regular_price: deal.regular_price,
+                    url: deal.url.clone(),
+                });
+            let known = data.regional_prices.get(&game_id);
+            let prices = regions
+                .into_iter()
+                .map(|region| {
+                    known
+                        .and_then(|prices| prices.iter().find(|price| price.region == region))
+                        .cloned()
+                        .unwrap_or_else(|| RegionalPrice {
+                            region,
+                            offer: offer.clone(),
+                        })
+                })
+                .collect();
+            (game_id, Ok(prices))
+        })
+    }
+}
+
+/// Sort like the API does for a `sort` parameter such as `price` or `-cut`.
+/// Deals without a value for the key (no expiry...) come last.
+fn sort_deals(deals: &mut [Deal], sort: &str, game_info: &HashMap<String, GameInfo>) {
+    let (descending, key) = match sort.strip_prefix('-') {
+        Some(key) => (true, key),
+        None => (false, sort),
+    };
+    let stats = |deal: &Deal| game_info.get(&deal.id).and_then(|info| info.stats);
+    let value = |deal: &Deal| -> Option<f64> {
+        match key {
+            "price" => Some(deal.price.amount.to_major()),
+            "cut" => Some(deal.price.discount as f64),
+            "expiry" => deal
+                .expiry
+                .as_deref()
+                .and_then(|expiry| DateTime::parse_from_rfc3339(expiry).ok())
+                .map(|expiry| expiry.timestamp() as f64),
+            "release-date" => game_info
+                .get(&deal.id)
+                .and_then(|info| info.release_date.as_deref())
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+                .map(|date| date.num_days_from_ce() as f64),
+            // Most waitlisted first when ascending, like the API
+            "hot" => stats(deal)
+                .and_then(|s| s.waitlisted)
+                .map(|count| -(count as f64)),
+            "rank" => stats(deal).and_then(|s| s.rank).map(|rank| rank as f64),
+            _ => None,
+        }
+    };
+
+    deals.sort_by(|a, b| match (value(a), value(b)) {
+        (Some(a), Some(b)) if descending => b.total_cmp(&a),
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+}
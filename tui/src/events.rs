@@ -1,60 +1,93 @@
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::{Position, Rect};
+use std::time::{Duration, Instant};
 
+use crate::keymap::{Action, KeyChord, KeyContext, KeyResolution, PendingKeys};
 use crate::message::Message;
 use crate::model::{Model, Popup};
 
-pub fn handle_event(model: &Model, poll_duration: std::time::Duration) -> Result<Option<Message>> {
-    if !event::poll(poll_duration)? {
-        return Ok(Some(Message::Tick));
-    }
-    if let Event::Key(key) = event::read()? {
-        if key.kind == KeyEventKind::Press {
-            return Ok(handle_key(model, key.code));
-        }
-    }
-    Ok(None)
-}
+// Rows moved per scroll wheel notch in the deals list
+const WHEEL_SCROLL_SIZE: isize = 3;
 
-fn handle_key(model: &Model, code: KeyCode) -> Option<Message> {
-    match model.ui.popup {
-        Popup::Platform => handle_platform_key(code),
-        Popup::Options => handle_options_key(code),
-        Popup::Keybinds => handle_keybinds_key(code),
-        Popup::PriceFilter => handle_price_filter_key(code),
-        Popup::None if model.ui.show_menu => handle_menu_key(code),
-        Popup::None if model.filter.active => handle_filter_key(code),
-        Popup::None => handle_main_key(code),
+/// Wait up to `timeout` for a terminal event
+pub fn poll_event(timeout: Duration) -> Result<Option<Event>> {
+    if !event::poll(timeout)? {
+        return Ok(None);
     }
+    Ok(Some(event::read()?))
 }
 
-fn handle_platform_key(code: KeyCode) -> Option<Message> {
-    match code {
-        KeyCode::Esc => Some(Message::ClosePopup),
-        KeyCode::Down | KeyCode::Char('j') => Some(Message::PlatformPopupNext),
-        KeyCode::Up | KeyCode::Char('k') => Some(Message::PlatformPopupPrev),
-        KeyCode::Enter => Some(Message::PlatformPopupSelect),
+/// Map a terminal event to a message, `now` being when it was received
+pub fn handle_event(model: &Model, event: Event, now: Instant) -> Option<Message> {
+    match event {
+        Event::Key(key) if key.kind == KeyEventKind::Press => handle_key(model, key, now),
+        Event::Mouse(mouse) => handle_mouse(model, mouse, now),
+        Event::Resize(_, _) => Some(Message::Resize),
         _ => None,
     }
 }
 
-fn handle_options_key(code: KeyCode) -> Option<Message> {
-    match code {
-        KeyCode::Esc => Some(Message::ClosePopup),
-        KeyCode::Tab | KeyCode::Right => Some(Message::OptionsNextTab),
-        KeyCode::BackTab | KeyCode::Left => Some(Message::OptionsPrevTab),
-        KeyCode::Down | KeyCode::Char('j') => Some(Message::OptionsNextItem),
-        KeyCode::Up | KeyCode::Char('k') => Some(Message::OptionsPrevItem),
-        KeyCode::Char('s') => Some(Message::OptionsToggleSortDirection),
-        KeyCode::Enter | KeyCode::Char(' ') => Some(Message::OptionsToggleItem),
-        _ => None,
-    }
+fn handle_key(model: &Model, key: KeyEvent, now: Instant) -> Option<Message> {
+    let context = match model.ui.popup {
+        Popup::Platform => KeyContext::Platform,
+        Popup::Options => KeyContext::Options,
+        Popup::Keybinds => KeyContext::Keybinds,
+        Popup::PriceFilter => return handle_price_filter_key(key.code),
+        Popup::Presets if model.ui.preset_name_input.is_some() => {
+            return handle_preset_name_key(key.code)
+        }
+        Popup::Presets => KeyContext::Presets,
+        Popup::Regions => KeyContext::Regions,
+        Popup::Export if model.export.path_input.is_some() => {
+            return handle_export_path_key(key.code)
+        }
+        Popup::Export => KeyContext::Export,
+        Popup::Yank => KeyContext::Yank,
+        Popup::None if model.ui.show_menu => KeyContext::Menu,
+        Popup::None if model.filter.active => return handle_filter_key(key.code),
+        Popup::None => KeyContext::Main,
+    };
+    handle_mapped_key(model, context, key, now)
 }
 
-fn handle_keybinds_key(code: KeyCode) -> Option<Message> {
-    match code {
-        KeyCode::Esc => Some(Message::ClosePopup),
-        _ => None,
+/// Resolve a key through the keymap, tracking multi-key sequences like `gg`
+fn handle_mapped_key(
+    model: &Model,
+    context: KeyContext,
+    key: KeyEvent,
+    now: Instant,
+) -> Option<Message> {
+    let pending = match &model.ui.pending_keys {
+        Some(pending) => pending.active(now),
+        None => &[],
+    };
+    match model
+        .keymap
+        .resolve(context, pending, KeyChord::from_event(key))
+    {
+        KeyResolution::Pending(keys) => Some(Message::KeySequence {
+            pending: Some(PendingKeys { keys, at: now }),
+            action: None,
+        }),
+        KeyResolution::Action(action) => {
+            let msg = action.message(context);
+            if pending.is_empty() {
+                msg
+            } else {
+                Some(Message::KeySequence {
+                    pending: None,
+                    action: msg.map(Box::new),
+                })
+            }
+        }
+        KeyResolution::Unbound if !pending.is_empty() => Some(Message::KeySequence {
+            pending: None,
+            action: None,
+        }),
+        KeyResolution::Unbound => None,
     }
 }
 
@@ -70,13 +103,22 @@ fn handle_price_filter_key(code: KeyCode) -> Option<Message> {
     }
 }
 
-fn handle_menu_key(code: KeyCode) -> Option<Message> {
+fn handle_preset_name_key(code: KeyCode) -> Option<Message> {
     match code {
-        KeyCode::Esc => Some(Message::ToggleMenu),
-        KeyCode::Char('q') => Some(Message::Quit),
-        KeyCode::Down | KeyCode::Char('j') => Some(Message::MenuNext),
-        KeyCode::Up | KeyCode::Char('k') => Some(Message::MenuPrevious),
-        KeyCode::Enter => Some(Message::MenuSelect),
+        KeyCode::Esc => Some(Message::ClosePopup),
+        KeyCode::Enter => Some(Message::PresetConfirmSave),
+        KeyCode::Backspace => Some(Message::PresetNamePop),
+        KeyCode::Char(c) => Some(Message::PresetNamePush(c)),
+        _ => None,
+    }
+}
+
+fn handle_export_path_key(code: KeyCode) -> Option<Message> {
+    match code {
+        KeyCode::Esc => Some(Message::ClosePopup),
+        KeyCode::Enter => Some(Message::ExportConfirm),
+        KeyCode::Backspace => Some(Message::ExportPathPop),
+        KeyCode::Char(c) => Some(Message::ExportPathPush(c)),
         _ => None,
     }
 }
@@ -91,24 +133,93 @@ fn handle_filter_key(code: KeyCode) -> Option<Message> {
     }
 }
 
-fn handle_main_key(code: KeyCode) -> Option<Message> {
-    match code {
-        KeyCode::Esc | KeyCode::Char('q') => Some(Message::ToggleMenu),
-        KeyCode::Down | KeyCode::Char('j') => Some(Message::SelectNext),
-        KeyCode::Up | KeyCode::Char('k') => Some(Message::SelectPrevious),
-        KeyCode::PageUp => Some(Message::SelectPageUp),
-        KeyCode::PageDown => Some(Message::SelectPageDown),
-        KeyCode::Home => Some(Message::SelectTop),
-        KeyCode::End => Some(Message::SelectBottom),
-        KeyCode::Char('p') => Some(Message::OpenPlatformPopup),
-        KeyCode::Char('f') => Some(Message::StartFilter),
-        KeyCode::Enter => Some(Message::OpenSelectedDeal),
-        KeyCode::Char('r') => Some(Message::RequestRefresh),
-        KeyCode::Char('s') => Some(Message::ToggleSortDirection),
-        KeyCode::Left => Some(Message::PrevSortCriteria),
-        KeyCode::Right => Some(Message::NextSortCriteria),
-        KeyCode::Char('c') => Some(Message::ClearFilters),
-        KeyCode::Char('$') => Some(Message::OpenPriceFilter),
+fn handle_mouse(model: &Model, mouse: MouseEvent, now: Instant) -> Option<Message> {
+    // Mouse is ignored while typing into a text field
+    if model.filter.active
+        || model.ui.popup == Popup::PriceFilter
+        || model.ui.preset_name_input.is_some()
+        || model.export.path_input.is_some()
+    {
+        return None;
+    }
+
+    let position = Position::new(mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::ScrollDown => handle_wheel(model, position, Action::Next),
+        MouseEventKind::ScrollUp => handle_wheel(model, position, Action::Previous),
+        MouseEventKind::Down(MouseButton::Left) => handle_click(model, position, now),
+        _ => None,
+    }
+}
+
+fn handle_wheel(model: &Model, position: Position, action: Action) -> Option<Message> {
+    let context = match model.ui.popup {
+        Popup::Platform => KeyContext::Platform,
+        Popup::Options => KeyContext::Options,
+        Popup::Keybinds => KeyContext::Keybinds,
+        Popup::Presets => KeyContext::Presets,
+        Popup::Regions => KeyContext::Regions,
+        Popup::Export => KeyContext::Export,
+        Popup::Yank => KeyContext::Yank,
+        Popup::PriceFilter => return None,
+        Popup::None if model.ui.show_menu => KeyContext::Menu,
+        Popup::None => {
+            if !model.ui.layout.deals.contains(position) {
+                return None;
+            }
+            let delta = match action {
+                Action::Next => WHEEL_SCROLL_SIZE,
+                _ => -WHEEL_SCROLL_SIZE,
+            };
+            return Some(Message::ScrollDeals(delta));
+        }
+    };
+    action.message(context)
+}
+
+fn handle_click(model: &Model, position: Position, now: Instant) -> Option<Message> {
+    let layout = &model.ui.layout;
+    match model.ui.popup {
+        Popup::Options => layout
+            .options_tabs
+            .iter()
+            .position(|tab| tab.contains(position))
+            .map(Message::OptionsSelectTab),
+        Popup::Platform => {
+            let row = row_in(layout.popup_list, position)?;
+            let index = layout.popup_scroll + row;
+            (index < model.enabled_platforms().len()).then_some(Message::PlatformPopupClick(index))
+        }
+        Popup::None if !model.ui.show_menu => {
+            if let Some(row) = row_in(panel_body(layout.deals), position) {
+                let index = model.ui.table_state.offset() + row;
+                (index < model.filtered_deals().len())
+                    .then_some(Message::DealClicked { index, at: now })
+            } else if panel_body(layout.chart).contains(position) {
+                // One sparkline column per history point, from the left edge
+                let index = (position.x - panel_body(layout.chart).x) as usize;
+                let points = model.selected_price_history()?.len();
+                (index < points).then_some(Message::InspectChartPoint(index))
+            } else {
+                None
+            }
+        }
         _ => None,
     }
 }
+
+/// Row of the position inside an area, if it falls inside
+fn row_in(area: Rect, position: Position) -> Option<usize> {
+    area.contains(position)
+        .then(|| (position.y - area.y) as usize)
+}
+
+/// Inside a panel's borders, below its first line (table header or chart summary)
+fn panel_body(panel: Rect) -> Rect {
+    Rect::new(
+        panel.x + 1,
+        panel.y + 2,
+        panel.width.saturating_sub(2),
+        panel.height.saturating_sub(3),
+    )
+}
@@ -0,0 +1,526 @@
+//! Export of the deals list to CSV, JSON, a Markdown table or a standalone
+//! HTML page, for pasting into a chat or a wiki.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use dealve_core::models::{Deal, GameInfo};
+use dealve_core::money::{Money, MoneyFormat};
+use serde_json::Value;
+
+use crate::columns::DealsColumn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    pub const ALL: &'static [ExportFormat] = &[
+        ExportFormat::Csv,
+        ExportFormat::Json,
+        ExportFormat::Markdown,
+        ExportFormat::Html,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+
+    /// Format named on the command line, by name or extension
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.to_lowercase();
+        ExportFormat::ALL
+            .iter()
+            .find(|format| format.name().to_lowercase() == input || format.extension() == input)
+            .copied()
+    }
+
+    /// Format matching a file's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "htm" => Some(ExportFormat::Html),
+            "markdown" => Some(ExportFormat::Markdown),
+            extension => ExportFormat::parse(extension),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = ExportFormat::ALL
+            .iter()
+            .position(|f| f == self)
+            .unwrap_or(0);
+        ExportFormat::ALL[(index + 1) % ExportFormat::ALL.len()]
+    }
+
+    pub fn prev(&self) -> Self {
+        let index = ExportFormat::ALL
+            .iter()
+            .position(|f| f == self)
+            .unwrap_or(0);
+        ExportFormat::ALL[(index + ExportFormat::ALL.len() - 1) % ExportFormat::ALL.len()]
+    }
+}
+
+/// Value of a column as written in the text formats
+fn text(
+    column: DealsColumn,
+    deal: &Deal,
+    info: Option<&GameInfo>,
+    money_format: MoneyFormat,
+) -> String {
+    let money = |money: Option<Money>| {
+        money
+            .map(|money| money.format(money_format))
+            .unwrap_or_default()
+    };
+    match column {
+        DealsColumn::Title => deal.title.clone(),
+        DealsColumn::Shop => deal.shop.name.clone(),
+        DealsColumn::Price => deal.price.amount.format(money_format),
+        DealsColumn::Regular => deal.regular_price.format(money_format),
+        DealsColumn::Savings => money(deal.regular_price.checked_sub(deal.price.amount)),
+        DealsColumn::Cut => format!("-{}%", deal.price.discount),
+        DealsColumn::Atl => if deal.is_historical_low() { "yes" } else { "" }.to_string(),
+        DealsColumn::HistoryLow => money(deal.history_low),
+        DealsColumn::StoreLow => money(deal.store_low),
+        DealsColumn::Expiry => deal.expiry.clone().unwrap_or_default(),
+        DealsColumn::ReleaseDate => info
+            .and_then(|info| info.release_date.clone())
+            .unwrap_or_default(),
+        DealsColumn::Tags => info.map(|info| info.tags.join(", ")).unwrap_or_default(),
+        DealsColumn::Reviews => info
+            .and_then(first_score)
+            .map(|(score, source)| format!("{} {}", score, source))
+            .unwrap_or_default(),
+        DealsColumn::Bundled => info
+            .and_then(|info| info.bundled)
+            .map(|count| count.to_string())
+            .unwrap_or_default(),
+        DealsColumn::Url => deal.url.clone(),
+    }
+}
+
+/// Value of a column in the JSON export, amounts as numbers with their currency
+fn json(column: DealsColumn, deal: &Deal, info: Option<&GameInfo>) -> Value {
+    let money = |money: Option<Money>| match money {
+        Some(money) => serde_json::json!({
+            "amount": money.to_major(),
+            "currency": money.currency().code(),
+        }),
+        None => Value::Null,
+    };
+    let optional = |value: Option<Value>| value.unwrap_or(Value::Null);
+    match column {
+        DealsColumn::Title => Value::from(deal.title.as_str()),
+        DealsColumn::Shop => Value::from(deal.shop.name.as_str()),
+        DealsColumn::Price => money(Some(deal.price.amount)),
+        DealsColumn::Regular => money(Some(deal.regular_price)),
+        DealsColumn::Savings => money(deal.regular_price.checked_sub(deal.price.amount)),
+        DealsColumn::Cut => Value::from(deal.price.discount),
+        DealsColumn::Atl => Value::from(deal.is_historical_low()),
+        DealsColumn::HistoryLow => money(deal.history_low),
+        DealsColumn::StoreLow => money(deal.store_low),
+        DealsColumn::Expiry => optional(deal.expiry.as_deref().map(Value::from)),
+        DealsColumn::ReleaseDate => optional(
+            info.and_then(|info| info.release_date.as_deref())
+                .map(Value::from),
+        ),
+        DealsColumn::Tags => info
+            .map(|info| Value::from(info.tags.clone()))
+            .unwrap_or_else(|| Value::Array(Vec::new())),
+        DealsColumn::Reviews => optional(
+            info.and_then(first_score)
+                .map(|(score, source)| serde_json::json!({ "score": score, "source": source })),
+        ),
+        DealsColumn::Bundled => optional(info.and_then(|info| info.bundled).map(Value::from)),
+        DealsColumn::Url => Value::from(deal.url.as_str()),
+    }
+}
+
+/// The first review with a score, as the table shows it
+fn first_score(info: &GameInfo) -> Option<(u8, &str)> {
+    info.reviews
+        .iter()
+        .find_map(|review| Some((review.score?, review.source.as_str())))
+}
+
+/// What to export and where
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub path: PathBuf,
+    pub format: ExportFormat,
+    pub columns: Vec<DealsColumn>,
+}
+
+impl Export {
+    /// Games among the deals without game info, when the columns need it
+    pub fn missing_game_info(
+        &self,
+        deals: &[&Deal],
+        game_info: &HashMap<String, GameInfo>,
+    ) -> Vec<String> {
+        if !self.columns.iter().any(DealsColumn::needs_game_info) {
+            return Vec::new();
+        }
+        let mut ids: Vec<String> = Vec::new();
+        for deal in deals {
+            if !game_info.contains_key(&deal.id) && !ids.contains(&deal.id) {
+                ids.push(deal.id.clone());
+            }
+        }
+        ids
+    }
+
+    /// The deals in the chosen format
+    pub fn render(
+        &self,
+        deals: &[&Deal],
+        game_info: &HashMap<String, GameInfo>,
+        money_format: MoneyFormat,
+    ) -> String {
+        let info = |deal: &Deal| game_info.get(&deal.id);
+        let rows = || {
+            deals.iter().map(|deal| {
+                self.columns
+                    .iter()
+                    .map(|&column| text(column, deal, info(deal), money_format))
+                    .collect::<Vec<_>>()
+            })
+        };
+        let headers = self.columns.iter().map(DealsColumn::export_header);
+
+        match self.format {
+            ExportFormat::Csv => {
+                let mut text = csv_line(headers.map(str::to_string));
+                for row in rows() {
+                    text.push_str(&csv_line(row));
+                }
+                text
+            }
+            ExportFormat::Json => {
+                // Written by hand so the keys keep the order of the columns
+                let objects: Vec<String> = deals
+                    .iter()
+                    .map(|deal| {
+                        let fields: Vec<String> = self
+                            .columns
+                            .iter()
+                            .map(|column| {
+                                format!(
+                                    "    {}: {}",
+                                    Value::from(column.id()),
+                                    json(*column, deal, info(deal))
+                                )
+                            })
+                            .collect();
+                        format!("  {{\n{}\n  }}", fields.join(",\n"))
+                    })
+                    .collect();
+                if objects.is_empty() {
+                    "[]\n".to_string()
+                } else {
+                    format!("[\n{}\n]\n", objects.join(",\n"))
+                }
+            }
+            ExportFormat::Markdown => {
+                let mut text = markdown_line(headers.map(str::to_string));
+                text.push_str(&markdown_line(
+                    self.columns.iter().map(|_| "---".to_string()),
+                ));
+                for row in rows() {
+                    text.push_str(&markdown_line(row.iter().map(|cell| markdown_escape(cell))));
+                }
+                text
+            }
+            ExportFormat::Html => {
+                let mut text = String::from(HTML_HEAD);
+                text.push_str("<thead>\n<tr>");
+                for header in headers {
+                    text.push_str(&format!("<th>{}</th>", html_escape(header)));
+                }
+                text.push_str("</tr>\n</thead>\n<tbody>\n");
+                for row in rows() {
+                    text.push_str("<tr>");
+                    for (column, cell) in self.columns.iter().zip(&row) {
+                        let cell = html_escape(cell);
+                        if *column == DealsColumn::Url && !cell.is_empty() {
+                            text.push_str(&format!("<td><a href=\"{0}\">{0}</a></td>", cell));
+                        } else {
+                            text.push_str(&format!("<td>{}</td>", cell));
+                        }
+                    }
+                    text.push_str("</tr>\n");
+                }
+                text.push_str(HTML_FOOT);
+                text
+            }
+        }
+    }
+
+    /// Write the deals to the file, replacing it whole
+    pub fn write(
+        &self,
+        deals: &[&Deal],
+        game_info: &HashMap<String, GameInfo>,
+        money_format: MoneyFormat,
+    ) -> std::io::Result<()> {
+        let content = self.render(deals, game_info, money_format);
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        let temp_path = self.path.with_file_name(name);
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(content.as_bytes())?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, &self.path)
+    }
+}
+
+/// Path typed by the user, with a leading `~` for the home directory
+pub fn expand_path(input: &str) -> PathBuf {
+    let input = input.trim();
+    match input.strip_prefix("~/").zip(dirs::home_dir()) {
+        Some((rest, home)) => home.join(rest),
+        None => PathBuf::from(input),
+    }
+}
+
+/// A path with its extension replaced by the format's
+pub fn with_format_extension(path: &str, format: ExportFormat) -> String {
+    let stem = match ExportFormat::from_path(Path::new(path)) {
+        Some(_) => path.rsplit_once('.').map_or(path, |(stem, _)| stem),
+        None => path,
+    };
+    format!("{}.{}", stem, format.extension())
+}
+
+fn csv_line(cells: impl IntoIterator<Item = String>) -> String {
+    let cells: Vec<String> = cells
+        .into_iter()
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell
+            }
+        })
+        .collect();
+    format!("{}\r\n", cells.join(","))
+}
+
+fn markdown_line(cells: impl IntoIterator<Item = String>) -> String {
+    let cells: Vec<String> = cells.into_iter().collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+fn markdown_escape(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_HEAD: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Deals</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; text-align: left; }
+th { background: #f4f4f4; }
+</style>
+</head>
+<body>
+<table>
+";
+
+const HTML_FOOT: &str = "</tbody>
+</table>
+</body>
+</html>
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn export(format: ExportFormat, columns: &[DealsColumn]) -> Export {
+        Export {
+            path: PathBuf::from("deals"),
+            format,
+            columns: columns.to_vec(),
+        }
+    }
+
+    /// Celeste with a title that needs escaping in every format
+    fn tricky_deal() -> Deal {
+        let mut deal = fixtures::deals()
+            .into_iter()
+            .find(|deal| deal.title == "Celeste")
+            .unwrap();
+        deal.title = "Celeste | \"Farewell\", <DLC> & more\nedition".to_string();
+        deal
+    }
+
+    fn game_info() -> HashMap<String, GameInfo> {
+        fixtures::game_info()
+            .into_iter()
+            .map(|info| (info.id.clone(), info))
+            .collect()
+    }
+
+    #[test]
+    fn csv_cells_are_quoted_when_needed() {
+        let line = |cells: &[&str]| csv_line(cells.iter().map(|cell| cell.to_string()));
+        assert_eq!(line(&["plain", "€3.99"]), "plain,€3.99\r\n");
+        assert_eq!(
+            line(&["a,b", "say \"hi\""]),
+            "\"a,b\",\"say \"\"hi\"\"\"\r\n"
+        );
+        assert_eq!(line(&["two\nlines", "cr\r"]), "\"two\nlines\",\"cr\r\"\r\n");
+        assert_eq!(line(&["", ""]), ",\r\n");
+    }
+
+    #[test]
+    fn markdown_and_html_cells_are_escaped() {
+        assert_eq!(markdown_escape("a | b"), "a \\| b");
+        assert_eq!(markdown_escape("two\r\nlines"), "two  lines");
+        assert_eq!(
+            html_escape("<a href=\"x\">Q&A</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Q&amp;A&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn the_extension_follows_the_format() {
+        let path = |path, format| with_format_extension(path, format);
+        assert_eq!(path("deals", ExportFormat::Csv), "deals.csv");
+        assert_eq!(path("deals.csv", ExportFormat::Markdown), "deals.md");
+        assert_eq!(
+            path("~/out/deals.html", ExportFormat::Json),
+            "~/out/deals.json"
+        );
+        // Only known extensions are replaced
+        assert_eq!(path("deals.2025", ExportFormat::Csv), "deals.2025.csv");
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.MD")),
+            Some(ExportFormat::Markdown)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("a.txt")), None);
+    }
+
+    #[test]
+    fn every_format_escapes_the_cells() {
+        let deal = tricky_deal();
+        let deals = [&deal];
+        let info = game_info();
+        let columns = [
+            DealsColumn::Title,
+            DealsColumn::Price,
+            DealsColumn::ReleaseDate,
+        ];
+        let render =
+            |format| export(format, &columns).render(&deals, &info, MoneyFormat::default());
+
+        assert_eq!(
+            render(ExportFormat::Csv),
+            "Title,Price,Release date\r\n\
+             \"Celeste | \"\"Farewell\"\", <DLC> & more\nedition\",€3.99,2018-01-25\r\n"
+        );
+        assert_eq!(
+            render(ExportFormat::Markdown),
+            "| Title | Price | Release date |\n\
+             | --- | --- | --- |\n\
+             | Celeste \\| \"Farewell\", <DLC> & more edition | €3.99 | 2018-01-25 |\n"
+        );
+        assert!(render(ExportFormat::Html).contains(
+            "<tr><td>Celeste | &quot;Farewell&quot;, &lt;DLC&gt; &amp; more\nedition</td>\
+             <td>€3.99</td><td>2018-01-25</td></tr>"
+        ));
+        assert_eq!(
+            render(ExportFormat::Json),
+            "[\n  {\n    \"title\": \"Celeste | \\\"Farewell\\\", <DLC> & more\\nedition\",\n    \
+             \"price\": {\"amount\":3.99,\"currency\":\"EUR\"},\n    \
+             \"release_date\": \"2018-01-25\"\n  }\n]\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Json, &columns).render(&[], &info, MoneyFormat::default()),
+            "[]\n"
+        );
+    }
+
+    #[test]
+    fn table_columns_can_be_exported() {
+        let deal = tricky_deal();
+        let info = game_info();
+        let columns = [
+            DealsColumn::Savings,
+            DealsColumn::HistoryLow,
+            DealsColumn::Expiry,
+            DealsColumn::Reviews,
+            DealsColumn::Bundled,
+        ];
+        let render =
+            |format| export(format, &columns).render(&[&deal], &info, MoneyFormat::default());
+
+        assert_eq!(
+            render(ExportFormat::Csv),
+            "Savings,Historical low,Expires,Review score,Bundled count\r\n\
+             €16.00,€3.99,,96 Steam,1\r\n"
+        );
+        assert_eq!(
+            render(ExportFormat::Json),
+            "[\n  {\n    \"savings\": {\"amount\":16.0,\"currency\":\"EUR\"},\n    \
+             \"history_low\": {\"amount\":3.99,\"currency\":\"EUR\"},\n    \
+             \"expiry\": null,\n    \
+             \"reviews\": {\"score\":96,\"source\":\"Steam\"},\n    \
+             \"bundled\": 1\n  }\n]\n"
+        );
+        // The link is for exports only
+        assert_eq!(DealsColumn::from_export_id("url"), Some(DealsColumn::Url));
+        assert_eq!(DealsColumn::from_id("url"), None);
+    }
+
+    #[test]
+    fn game_info_is_only_needed_for_its_columns() {
+        let deals = fixtures::deals();
+        let deals: Vec<&Deal> = deals.iter().take(3).collect();
+        let cached = HashMap::new();
+        let plain = export(ExportFormat::Csv, &[DealsColumn::Title, DealsColumn::Url]);
+        assert!(plain.missing_game_info(&deals, &cached).is_empty());
+
+        let tags = export(ExportFormat::Csv, &[DealsColumn::Title, DealsColumn::Tags]);
+        assert_eq!(tags.missing_game_info(&deals, &cached).len(), 3);
+        assert!(tags.missing_game_info(&deals, &game_info()).is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+//! Sample deals, game info, price history and regional prices from
+//! `tui/fixtures/`
+
+use dealve_core::models::{Deal, GameInfo, PriceHistoryPoint, RegionalPrice};
+use std::collections::HashMap;
+
+pub fn deals() -> Vec<Deal> {
+    serde_json::from_str(include_str!("../fixtures/deals.json")).expect("valid deals fixture")
+}
+
+pub fn game_info() -> Vec<GameInfo> {
+    serde_json::from_str(include_str!("../fixtures/game_info.json"))
+        .expect("valid game info fixture")
+}
+
+/// Price history by game id
+pub fn price_history() -> HashMap<String, Vec<PriceHistoryPoint>> {
+    serde_json::from_str(include_str!("../fixtures/price_history.json"))
+        .expect("valid price history fixture")
+}
+
+/// Prices across regions by game id, for the first deal only
+pub fn regional_prices() -> HashMap<String, Vec<RegionalPrice>> {
+    serde_json::from_str(include_str!("../fixtures/regional_prices.json"))
+        .expect("valid regional prices fixture")
+}
@@ -0,0 +1,1223 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::message::Message;
+
+/// A single key press with its modifiers (`j`, `ctrl-d`, `pagedown`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already encoded in the character itself ('G' vs 'g')
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parse a single chord: `j`, `G`, `$`, `enter`, `ctrl-d`, `alt-shift-left`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = input;
+
+        loop {
+            let lower = rest.to_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            if rest.len() == len {
+                break;
+            }
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let code = match rest.to_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            "minus" => KeyCode::Char('-'),
+            lower => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n @ 1..=12) => KeyCode::F(n),
+                        _ => return Err(format!("unknown key `{}`", input)),
+                    },
+                }
+            }
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// The chord as `parse` reads it back, e.g. `ctrl-d`, `pagedown`, `G`
+    pub fn id(&self) -> String {
+        let mut id = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            id.push_str("ctrl-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            id.push_str("alt-");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            id.push_str("shift-");
+        }
+        match self.code {
+            KeyCode::Up => id.push_str("up"),
+            KeyCode::Down => id.push_str("down"),
+            KeyCode::Left => id.push_str("left"),
+            KeyCode::Right => id.push_str("right"),
+            KeyCode::Enter => id.push_str("enter"),
+            KeyCode::Esc => id.push_str("esc"),
+            KeyCode::Tab => id.push_str("tab"),
+            KeyCode::BackTab => id.push_str("backtab"),
+            KeyCode::Backspace => id.push_str("backspace"),
+            KeyCode::Delete => id.push_str("delete"),
+            KeyCode::Insert => id.push_str("insert"),
+            KeyCode::Home => id.push_str("home"),
+            KeyCode::End => id.push_str("end"),
+            KeyCode::PageUp => id.push_str("pageup"),
+            KeyCode::PageDown => id.push_str("pagedown"),
+            KeyCode::F(n) => id.push_str(&format!("f{}", n)),
+            KeyCode::Char(' ') => id.push_str("space"),
+            KeyCode::Char('-') => id.push_str("minus"),
+            KeyCode::Char(c) => id.push(c),
+            _ => id.push('?'),
+        }
+        id
+    }
+
+    #[cfg(test)]
+    pub fn to_event(self) -> KeyEvent {
+        KeyEvent::new(self.code, self.modifiers)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDown"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+/// Parse a key sequence. Space-separated chords (`ctrl-w j`), a single named
+/// chord (`pagedown`, `ctrl-d`) or a run of plain characters (`gg`).
+fn parse_sequence(input: &str) -> Result<Vec<KeyChord>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("empty key".to_string());
+    }
+    if input.contains(' ') {
+        return input.split_whitespace().map(KeyChord::parse).collect();
+    }
+    match KeyChord::parse(input) {
+        Ok(chord) => Ok(vec![chord]),
+        Err(_) if input.chars().count() > 1 && !looks_like_chord(input) => Ok(input
+            .chars()
+            .map(|c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE))
+            .collect()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether a key that doesn't parse was meant as a single chord, a modifier
+/// or function key with a typo, rather than a run of characters
+fn looks_like_chord(input: &str) -> bool {
+    let lower = input.to_lowercase();
+    ["ctrl-", "alt-", "shift-"]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+        || lower
+            .strip_prefix('f')
+            .is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn sequence_label(keys: &[KeyChord]) -> String {
+    let plain_chars = keys.len() > 1
+        && keys
+            .iter()
+            .all(|k| matches!(k.code, KeyCode::Char(c) if c != ' ') && k.modifiers.is_empty());
+    let separator = if plain_chars { "" } else { " " };
+    keys.iter()
+        .map(|k| k.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Where a key is pressed; each context has its own bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyContext {
+    Main,
+    Menu,
+    Options,
+    Platform,
+    Presets,
+    Regions,
+    Export,
+    Yank,
+    Keybinds,
+}
+
+impl KeyContext {
+    pub const ALL: &'static [KeyContext] = &[
+        KeyContext::Main,
+        KeyContext::Menu,
+        KeyContext::Options,
+        KeyContext::Platform,
+        KeyContext::Presets,
+        KeyContext::Regions,
+        KeyContext::Export,
+        KeyContext::Yank,
+        KeyContext::Keybinds,
+    ];
+
+    /// Section name in the keymap file
+    pub fn id(&self) -> &str {
+        match self {
+            KeyContext::Main => "main",
+            KeyContext::Menu => "menu",
+            KeyContext::Options => "options",
+            KeyContext::Platform => "platform",
+            KeyContext::Presets => "presets",
+            KeyContext::Regions => "regions",
+            KeyContext::Export => "export",
+            KeyContext::Yank => "yank",
+            KeyContext::Keybinds => "keybinds",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            KeyContext::Main => "Deals",
+            KeyContext::Menu => "Menu",
+            KeyContext::Options => "Options",
+            KeyContext::Platform => "Platform popup",
+            KeyContext::Presets => "Presets popup",
+            KeyContext::Regions => "Regional prices popup",
+            KeyContext::Export => "Export popup",
+            KeyContext::Yank => "Copy popup",
+            KeyContext::Keybinds => "Keybinds popup",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<KeyContext> {
+        KeyContext::ALL.iter().find(|c| c.id() == id).copied()
+    }
+}
+
+/// Something a key can be bound to. The same action can mean different
+/// messages depending on the context (`Next` moves in the list or the menu).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Next,
+    Previous,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Select,
+    Close,
+    NextTab,
+    PrevTab,
+    Menu,
+    Quit,
+    Filter,
+    ClearFilters,
+    NewOnly,
+    PriceFilter,
+    Platform,
+    Presets,
+    ApplyPreset(u8),
+    Regions,
+    Currency,
+    Export,
+    Yank,
+    ToggleSelection,
+    SelectRange,
+    Watch,
+    Ignore,
+    ShowIgnored,
+    NextPane,
+    Maximize,
+    Details,
+    Debug,
+    SaveView,
+    Delete,
+    Refresh,
+    SortDirection,
+    NextSort,
+    PrevSort,
+    MoveUp,
+    MoveDown,
+    Wider,
+    Narrower,
+}
+
+impl Action {
+    /// Actions available in a context, in the order shown in the keybinds popup
+    pub fn for_context(context: KeyContext) -> Vec<Action> {
+        let mut actions = match context {
+            KeyContext::Main => vec![
+                Action::Next,
+                Action::Previous,
+                Action::PageUp,
+                Action::PageDown,
+                Action::First,
+                Action::Last,
+                Action::Select,
+                Action::ToggleSelection,
+                Action::SelectRange,
+                Action::Filter,
+                Action::ClearFilters,
+                Action::PriceFilter,
+                Action::NewOnly,
+                Action::Platform,
+                Action::Presets,
+                Action::Regions,
+                Action::Export,
+                Action::Yank,
+                Action::Watch,
+                Action::Ignore,
+                Action::ShowIgnored,
+                Action::SortDirection,
+                Action::PrevSort,
+                Action::NextSort,
+                Action::Refresh,
+                Action::NextPane,
+                Action::Maximize,
+                Action::Details,
+                Action::Debug,
+                Action::Menu,
+            ],
+            KeyContext::Menu => vec![
+                Action::Next,
+                Action::Previous,
+                Action::Select,
+                Action::Close,
+                Action::Quit,
+            ],
+            KeyContext::Options => vec![
+                Action::Next,
+                Action::Previous,
+                Action::NextTab,
+                Action::PrevTab,
+                Action::Select,
+                Action::SortDirection,
+                Action::Regions,
+                Action::Currency,
+                Action::Refresh,
+                Action::MoveUp,
+                Action::MoveDown,
+                Action::Wider,
+                Action::Narrower,
+                Action::Close,
+            ],
+            KeyContext::Platform => vec![
+                Action::Next,
+                Action::Previous,
+                Action::Select,
+                Action::Close,
+            ],
+            KeyContext::Presets => vec![
+                Action::Next,
+                Action::Previous,
+                Action::Select,
+                Action::SaveView,
+                Action::Delete,
+                Action::Close,
+            ],
+            KeyContext::Regions => vec![
+                Action::Next,
+                Action::Previous,
+                Action::Select,
+                Action::Refresh,
+                Action::Close,
+            ],
+            KeyContext::Export => vec![
+                Action::Next,
+                Action::Previous,
+                Action::Select,
+                Action::NextTab,
+                Action::PrevTab,
+                Action::Export,
+                Action::Close,
+            ],
+            KeyContext::Yank => vec![
+                Action::Next,
+                Action::Previous,
+                Action::Select,
+                Action::Close,
+            ],
+            KeyContext::Keybinds => vec![Action::Next, Action::Previous, Action::Close],
+        };
+        if matches!(context, KeyContext::Main | KeyContext::Presets) {
+            actions.extend((1..=9).map(Action::ApplyPreset));
+        }
+        actions
+    }
+
+    /// Name used in the keymap file
+    pub fn id(&self) -> String {
+        match self {
+            Action::Next => "next".to_string(),
+            Action::Previous => "previous".to_string(),
+            Action::PageUp => "page_up".to_string(),
+            Action::PageDown => "page_down".to_string(),
+            Action::First => "first".to_string(),
+            Action::Last => "last".to_string(),
+            Action::Select => "select".to_string(),
+            Action::Close => "close".to_string(),
+            Action::NextTab => "next_tab".to_string(),
+            Action::PrevTab => "prev_tab".to_string(),
+            Action::Menu => "menu".to_string(),
+            Action::Quit => "quit".to_string(),
+            Action::Filter => "filter".to_string(),
+            Action::ClearFilters => "clear_filters".to_string(),
+            Action::NewOnly => "new_only".to_string(),
+            Action::PriceFilter => "price_filter".to_string(),
+            Action::Platform => "platform".to_string(),
+            Action::Presets => "presets".to_string(),
+            Action::ApplyPreset(n) => format!("preset_{}", n),
+            Action::Regions => "regions".to_string(),
+            Action::Currency => "currency".to_string(),
+            Action::Export => "export".to_string(),
+            Action::Yank => "yank".to_string(),
+            Action::ToggleSelection => "toggle_selection".to_string(),
+            Action::SelectRange => "select_range".to_string(),
+            Action::Watch => "watch".to_string(),
+            Action::Ignore => "ignore".to_string(),
+            Action::ShowIgnored => "show_ignored".to_string(),
+            Action::SaveView => "save_view".to_string(),
+            Action::Delete => "delete".to_string(),
+            Action::Refresh => "refresh".to_string(),
+            Action::NextPane => "next_pane".to_string(),
+            Action::Maximize => "maximize".to_string(),
+            Action::Details => "details".to_string(),
+            Action::Debug => "debug".to_string(),
+            Action::SortDirection => "sort_direction".to_string(),
+            Action::NextSort => "next_sort".to_string(),
+            Action::PrevSort => "prev_sort".to_string(),
+            Action::MoveUp => "move_up".to_string(),
+            Action::MoveDown => "move_down".to_string(),
+            Action::Wider => "wider".to_string(),
+            Action::Narrower => "narrower".to_string(),
+        }
+    }
+
+    pub fn description(&self, context: KeyContext) -> String {
+        let text = match (context, self) {
+            (KeyContext::Main, Action::Next) => "Next deal",
+            (KeyContext::Main, Action::Previous) => "Previous deal",
+            (KeyContext::Main, Action::Select) => "Open deal in browser",
+            (KeyContext::Options, Action::Next) => "Next item",
+            (KeyContext::Options, Action::Previous) => "Previous item",
+            (KeyContext::Options, Action::Select) => "Toggle / cycle item",
+            (KeyContext::Options, Action::SortDirection) => "Default sort direction",
+            (KeyContext::Options, Action::Regions) => "Compare prices in region",
+            (KeyContext::Options, Action::Refresh) => "Update exchange rates",
+            (KeyContext::Regions, Action::Select) => "Open offer in browser",
+            (KeyContext::Regions, Action::Refresh) => "Reload prices",
+            (KeyContext::Export, Action::Select) => "Toggle column",
+            (KeyContext::Export, Action::NextTab) => "Next format",
+            (KeyContext::Export, Action::PrevTab) => "Previous format",
+            (KeyContext::Export, Action::Export) => "Choose file and export",
+            (KeyContext::Yank, Action::Select) => "Copy",
+            (KeyContext::Keybinds, Action::Next) => "Scroll down",
+            (KeyContext::Keybinds, Action::Previous) => "Scroll up",
+            (_, Action::Next) => "Next",
+            (_, Action::Previous) => "Previous",
+            (_, Action::PageUp) => "Page up",
+            (_, Action::PageDown) => "Page down",
+            (_, Action::First) => "First deal",
+            (_, Action::Last) => "Last deal",
+            (_, Action::Select) => "Select",
+            (_, Action::Close) => "Close",
+            (_, Action::NextTab) => "Next tab",
+            (_, Action::PrevTab) => "Previous tab",
+            (_, Action::Menu) => "Menu",
+            (_, Action::Quit) => "Quit",
+            (_, Action::Filter) => "Filter (cut>=75 shop:gog)",
+            (_, Action::ClearFilters) => "Clear filters",
+            (_, Action::NewOnly) => "New since last visit",
+            (_, Action::PriceFilter) => "Price filter",
+            (_, Action::Platform) => "Change platform",
+            (_, Action::Presets) => "Presets / save view",
+            (_, Action::ApplyPreset(n)) => return format!("Apply preset {}", n),
+            (_, Action::Regions) => "Compare prices across regions",
+            (_, Action::Currency) => "Display currency",
+            (_, Action::Export) => "Export deals to a file",
+            (_, Action::Yank) => "Copy link, title or summary",
+            (_, Action::ToggleSelection) => "Select deal for batch actions",
+            (_, Action::SelectRange) => "Select range from last selected",
+            (_, Action::Watch) => "Add to / remove from watchlist",
+            (_, Action::Ignore) => "Ignore / unignore game",
+            (_, Action::ShowIgnored) => "Show ignored games",
+            (_, Action::SaveView) => "Save current view",
+            (_, Action::Delete) => "Delete preset",
+            (_, Action::Refresh) => "Refresh deals",
+            (_, Action::NextPane) => "Focus next pane",
+            (_, Action::Maximize) => "Maximize focused pane",
+            (_, Action::Details) => "Details overlay (small terminals)",
+            (_, Action::Debug) => "Debug overlay",
+            (_, Action::SortDirection) => "Toggle sort direction",
+            (_, Action::NextSort) => "Next sort criteria",
+            (_, Action::PrevSort) => "Previous sort criteria",
+            (_, Action::MoveUp) => "Move column earlier",
+            (_, Action::MoveDown) => "Move column later",
+            (_, Action::Wider) => "Widen column",
+            (_, Action::Narrower) => "Narrow column",
+        };
+        text.to_string()
+    }
+
+    /// Message sent when the action fires in a context
+    pub fn message(&self, context: KeyContext) -> Option<Message> {
+        let msg = match (context, self) {
+            (KeyContext::Main, Action::Next) => Message::SelectNext,
+            (KeyContext::Main, Action::Previous) => Message::SelectPrevious,
+            (KeyContext::Main, Action::PageUp) => Message::SelectPageUp,
+            (KeyContext::Main, Action::PageDown) => Message::SelectPageDown,
+            (KeyContext::Main, Action::First) => Message::SelectTop,
+            (KeyContext::Main, Action::Last) => Message::SelectBottom,
+            (KeyContext::Main, Action::Select) => Message::OpenSelectedDeal,
+            (KeyContext::Main, Action::Menu) => Message::ToggleMenu,
+            (KeyContext::Main, Action::Filter) => Message::StartFilter,
+            (KeyContext::Main, Action::ClearFilters) => Message::ClearFilters,
+            (KeyContext::Main, Action::NewOnly) => Message::ToggleNewOnly,
+            (KeyContext::Main, Action::PriceFilter) => Message::OpenPriceFilter,
+            (KeyContext::Main, Action::Platform) => Message::OpenPlatformPopup,
+            (KeyContext::Main, Action::Presets) => Message::OpenPresetPopup,
+            (KeyContext::Main, Action::Regions) => Message::OpenRegions,
+            (KeyContext::Main, Action::Export) => Message::OpenExport,
+            (KeyContext::Main, Action::Yank) => Message::OpenYank,
+            (KeyContext::Main, Action::ToggleSelection) => Message::ToggleDealSelection,
+            (KeyContext::Main, Action::SelectRange) => Message::SelectDealRange,
+            (KeyContext::Main, Action::Watch) => Message::ToggleWatchlist,
+            (KeyContext::Main, Action::Ignore) => Message::ToggleIgnored,
+            (KeyContext::Main, Action::ShowIgnored) => Message::ToggleShowIgnored,
+            (KeyContext::Main, Action::Refresh) => Message::RequestRefresh,
+            (KeyContext::Main, Action::NextPane) => Message::FocusNextPane,
+            (KeyContext::Main, Action::Maximize) => Message::ToggleMaximize,
+            (KeyContext::Main, Action::Details) => Message::ToggleDetails,
+            (KeyContext::Main, Action::Debug) => Message::ToggleDebug,
+            (KeyContext::Main, Action::SortDirection) => Message::ToggleSortDirection,
+            (KeyContext::Main, Action::NextSort) => Message::NextSortCriteria,
+            (KeyContext::Main, Action::PrevSort) => Message::PrevSortCriteria,
+
+            (KeyContext::Menu, Action::Next) => Message::MenuNext,
+            (KeyContext::Menu, Action::Previous) => Message::MenuPrevious,
+            (KeyContext::Menu, Action::Select) => Message::MenuSelect,
+            (KeyContext::Menu, Action::Close) => Message::ToggleMenu,
+            (KeyContext::Menu, Action::Quit) => Message::Quit,
+
+            (KeyContext::Options, Action::Next) => Message::OptionsNextItem,
+            (KeyContext::Options, Action::Previous) => Message::OptionsPrevItem,
+            (KeyContext::Options, Action::NextTab) => Message::OptionsNextTab,
+            (KeyContext::Options, Action::PrevTab) => Message::OptionsPrevTab,
+            (KeyContext::Options, Action::Select) => Message::OptionsToggleItem,
+            (KeyContext::Options, Action::SortDirection) => Message::OptionsToggleSortDirection,
+            (KeyContext::Options, Action::Regions) => Message::OptionsToggleCompareRegion,
+            (KeyContext::Options, Action::Currency) => Message::OptionsCycleDisplayCurrency,
+            (KeyContext::Options, Action::Refresh) => Message::OptionsRefreshRates,
+            (KeyContext::Options, Action::MoveUp) => Message::OptionsMoveColumn(-1),
+            (KeyContext::Options, Action::MoveDown) => Message::OptionsMoveColumn(1),
+            (KeyContext::Options, Action::Wider) => Message::OptionsResizeColumn(1),
+            (KeyContext::Options, Action::Narrower) => Message::OptionsResizeColumn(-1),
+
+            (KeyContext::Platform, Action::Next) => Message::PlatformPopupNext,
+            (KeyContext::Platform, Action::Previous) => Message::PlatformPopupPrev,
+            (KeyContext::Platform, Action::Select) => Message::PlatformPopupSelect,
+
+            (KeyContext::Presets, Action::Next) => Message::PresetPopupNext,
+            (KeyContext::Presets, Action::Previous) => Message::PresetPopupPrev,
+            (KeyContext::Presets, Action::Select) => Message::PresetPopupSelect,
+            (KeyContext::Presets, Action::SaveView) => Message::PresetStartSave,
+            (KeyContext::Presets, Action::Delete) => Message::PresetDelete,
+
+            (KeyContext::Regions, Action::Next) => Message::RegionsNext,
+            (KeyContext::Regions, Action::Previous) => Message::RegionsPrev,
+            (KeyContext::Regions, Action::Select) => Message::RegionsOpenOffer,
+            (KeyContext::Regions, Action::Refresh) => Message::RegionsRefresh,
+
+            (KeyContext::Export, Action::Next) => Message::ExportNext,
+            (KeyContext::Export, Action::Previous) => Message::ExportPrev,
+            (KeyContext::Export, Action::Select) => Message::ExportToggleColumn,
+            (KeyContext::Export, Action::NextTab) => Message::ExportNextFormat,
+            (KeyContext::Export, Action::PrevTab) => Message::ExportPrevFormat,
+            (KeyContext::Export, Action::Export) => Message::ExportStartPath,
+
+            (KeyContext::Yank, Action::Next) => Message::YankNext,
+            (KeyContext::Yank, Action::Previous) => Message::YankPrev,
+            (KeyContext::Yank, Action::Select) => Message::YankSelect,
+
+            (KeyContext::Keybinds, Action::Next) => Message::KeybindsScrollDown,
+            (KeyContext::Keybinds, Action::Previous) => Message::KeybindsScrollUp,
+
+            (_, Action::ApplyPreset(n)) => Message::ApplyPreset(*n as usize - 1),
+            (_, Action::Close) => Message::ClosePopup,
+            _ => return None,
+        };
+        Some(msg)
+    }
+
+    fn from_id(context: KeyContext, id: &str) -> Option<Action> {
+        Action::for_context(context)
+            .into_iter()
+            .find(|action| action.id() == id)
+    }
+}
+
+/// Default bindings, as `(context, keys, action)`
+const DEFAULT_BINDINGS: &[(KeyContext, &str, Action)] = &[
+    // Main
+    (KeyContext::Main, "down", Action::Next),
+    (KeyContext::Main, "j", Action::Next),
+    (KeyContext::Main, "up", Action::Previous),
+    (KeyContext::Main, "k", Action::Previous),
+    (KeyContext::Main, "pageup", Action::PageUp),
+    (KeyContext::Main, "pagedown", Action::PageDown),
+    (KeyContext::Main, "home", Action::First),
+    (KeyContext::Main, "gg", Action::First),
+    (KeyContext::Main, "end", Action::Last),
+    (KeyContext::Main, "G", Action::Last),
+    (KeyContext::Main, "enter", Action::Select),
+    (KeyContext::Main, "space", Action::ToggleSelection),
+    (KeyContext::Main, "V", Action::SelectRange),
+    (KeyContext::Main, "f", Action::Filter),
+    (KeyContext::Main, "c", Action::ClearFilters),
+    (KeyContext::Main, "$", Action::PriceFilter),
+    (KeyContext::Main, "n", Action::NewOnly),
+    (KeyContext::Main, "p", Action::Platform),
+    (KeyContext::Main, "v", Action::Presets),
+    (KeyContext::Main, "R", Action::Regions),
+    (KeyContext::Main, "e", Action::Export),
+    (KeyContext::Main, "y", Action::Yank),
+    (KeyContext::Main, "w", Action::Watch),
+    (KeyContext::Main, "x", Action::Ignore),
+    (KeyContext::Main, "X", Action::ShowIgnored),
+    (KeyContext::Main, "s", Action::SortDirection),
+    (KeyContext::Main, "left", Action::PrevSort),
+    (KeyContext::Main, "right", Action::NextSort),
+    (KeyContext::Main, "r", Action::Refresh),
+    (KeyContext::Main, "tab", Action::NextPane),
+    (KeyContext::Main, "z", Action::Maximize),
+    (KeyContext::Main, "i", Action::Details),
+    (KeyContext::Main, "f12", Action::Debug),
+    (KeyContext::Main, "esc", Action::Menu),
+    (KeyContext::Main, "q", Action::Menu),
+    (KeyContext::Main, "1", Action::ApplyPreset(1)),
+    (KeyContext::Main, "2", Action::ApplyPreset(2)),
+    (KeyContext::Main, "3", Action::ApplyPreset(3)),
+    (KeyContext::Main, "4", Action::ApplyPreset(4)),
+    (KeyContext::Main, "5", Action::ApplyPreset(5)),
+    (KeyContext::Main, "6", Action::ApplyPreset(6)),
+    (KeyContext::Main, "7", Action::ApplyPreset(7)),
+    (KeyContext::Main, "8", Action::ApplyPreset(8)),
+    (KeyContext::Main, "9", Action::ApplyPreset(9)),
+    // Menu
+    (KeyContext::Menu, "down", Action::Next),
+    (KeyContext::Menu, "j", Action::Next),
+    (KeyContext::Menu, "up", Action::Previous),
+    (KeyContext::Menu, "k", Action::Previous),
+    (KeyContext::Menu, "enter", Action::Select),
+    (KeyContext::Menu, "esc", Action::Close),
+    (KeyContext::Menu, "q", Action::Quit),
+    // Options
+    (KeyContext::Options, "down", Action::Next),
+    (KeyContext::Options, "j", Action::Next),
+    (KeyContext::Options, "up", Action::Previous),
+    (KeyContext::Options, "k", Action::Previous),
+    (KeyContext::Options, "tab", Action::NextTab),
+    (KeyContext::Options, "right", Action::NextTab),
+    (KeyContext::Options, "backtab", Action::PrevTab),
+    (KeyContext::Options, "left", Action::PrevTab),
+    (KeyContext::Options, "enter", Action::Select),
+    (KeyContext::Options, "space", Action::Select),
+    (KeyContext::Options, "s", Action::SortDirection),
+    (KeyContext::Options, "c", Action::Regions),
+    (KeyContext::Options, "d", Action::Currency),
+    (KeyContext::Options, "r", Action::Refresh),
+    (KeyContext::Options, "K", Action::MoveUp),
+    (KeyContext::Options, "J", Action::MoveDown),
+    (KeyContext::Options, "+", Action::Wider),
+    (KeyContext::Options, "=", Action::Wider),
+    (KeyContext::Options, "minus", Action::Narrower),
+    (KeyContext::Options, "esc", Action::Close),
+    // Platform popup
+    (KeyContext::Platform, "down", Action::Next),
+    (KeyContext::Platform, "j", Action::Next),
+    (KeyContext::Platform, "up", Action::Previous),
+    (KeyContext::Platform, "k", Action::Previous),
+    (KeyContext::Platform, "enter", Action::Select),
+    (KeyContext::Platform, "esc", Action::Close),
+    // Presets popup
+    (KeyContext::Presets, "down", Action::Next),
+    (KeyContext::Presets, "j", Action::Next),
+    (KeyContext::Presets, "up", Action::Previous),
+    (KeyContext::Presets, "k", Action::Previous),
+    (KeyContext::Presets, "enter", Action::Select),
+    (KeyContext::Presets, "a", Action::SaveView),
+    (KeyContext::Presets, "d", Action::Delete),
+    (KeyContext::Presets, "esc", Action::Close),
+    (KeyContext::Presets, "1", Action::ApplyPreset(1)),
+    (KeyContext::Presets, "2", Action::ApplyPreset(2)),
+    (KeyContext::Presets, "3", Action::ApplyPreset(3)),
+    (KeyContext::Presets, "4", Action::ApplyPreset(4)),
+    (KeyContext::Presets, "5", Action::ApplyPreset(5)),
+    (KeyContext::Presets, "6", Action::ApplyPreset(6)),
+    (KeyContext::Presets, "7", Action::ApplyPreset(7)),
+    (KeyContext::Presets, "8", Action::ApplyPreset(8)),
+    (KeyContext::Presets, "9", Action::ApplyPreset(9)),
+    // Regional prices popup
+    (KeyContext::Regions, "down", Action::Next),
+    (KeyContext::Regions, "j", Action::Next),
+    (KeyContext::Regions, "up", Action::Previous),
+    (KeyContext::Regions, "k", Action::Previous),
+    (KeyContext::Regions, "enter", Action::Select),
+    (KeyContext::Regions, "r", Action::Refresh),
+    (KeyContext::Regions, "esc", Action::Close),
+    // Export popup
+    (KeyContext::Export, "down", Action::Next),
+    (KeyContext::Export, "j", Action::Next),
+    (KeyContext::Export, "up", Action::Previous),
+    (KeyContext::Export, "k", Action::Previous),
+    (KeyContext::Export, "space", Action::Select),
+    (KeyContext::Export, "tab", Action::NextTab),
+    (KeyContext::Export, "right", Action::NextTab),
+    (KeyContext::Export, "backtab", Action::PrevTab),
+    (KeyContext::Export, "left", Action::PrevTab),
+    (KeyContext::Export, "enter", Action::Export),
+    (KeyContext::Export, "esc", Action::Close),
+    // Copy popup
+    (KeyContext::Yank, "down", Action::Next),
+    (KeyContext::Yank, "j", Action::Next),
+    (KeyContext::Yank, "up", Action::Previous),
+    (KeyContext::Yank, "k", Action::Previous),
+    (KeyContext::Yank, "enter", Action::Select),
+    (KeyContext::Yank, "y", Action::Select),
+    (KeyContext::Yank, "esc", Action::Close),
+    // Keybinds popup
+    (KeyContext::Keybinds, "down", Action::Next),
+    (KeyContext::Keybinds, "j", Action::Next),
+    (KeyContext::Keybinds, "up", Action::Previous),
+    (KeyContext::Keybinds, "k", Action::Previous),
+    (KeyContext::Keybinds, "esc", Action::Close),
+];
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub context: KeyContext,
+    pub keys: Vec<KeyChord>,
+    pub action: Action,
+}
+
+/// How long a multi-key sequence waits for its next key
+pub const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Keys typed so far of an incomplete multi-key binding
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingKeys {
+    pub keys: Vec<KeyChord>,
+    /// When the last of them was pressed
+    pub at: Instant,
+}
+
+impl PendingKeys {
+    /// The keys, or none once the sequence has timed out
+    pub fn active(&self, now: Instant) -> &[KeyChord] {
+        if now.duration_since(self.at) > SEQUENCE_TIMEOUT {
+            &[]
+        } else {
+            &self.keys
+        }
+    }
+}
+
+/// Outcome of feeding a key into the keymap
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyResolution {
+    /// The sequence is complete and bound to an action
+    Action(Action),
+    /// The keys so far are the start of a longer sequence
+    Pending(Vec<KeyChord>),
+    /// Nothing is bound to these keys
+    Unbound,
+}
+
+/// Active key bindings: the defaults with user overrides applied
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+    /// Problems found while loading overrides or checking for conflicts
+    pub warnings: Vec<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .map(|(context, keys, action)| Binding {
+                context: *context,
+                keys: parse_sequence(keys).expect("default keymap must parse"),
+                action: *action,
+            })
+            .collect();
+        Self {
+            bindings,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Get the keymap file path (~/.config/dealve/keymap.toml)
+    pub fn keymap_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("dealve").join("keymap.toml"))
+    }
+
+    /// Load the default keymap with overrides from the keymap file, if any
+    pub fn load() -> Self {
+        let mut keymap = Self::default();
+
+        if let Some(path) = Self::keymap_path() {
+            if let Ok(content) = fs::read_to_string(&path) {
+                keymap.apply_overrides(&content);
+            }
+        }
+
+        keymap.warnings.extend(keymap.conflicts());
+        keymap
+    }
+
+    /// Apply overrides from a keymap file. Each `[context]` table maps action
+    /// names to a key or a list of keys, replacing that action's defaults:
+    ///
+    /// ```toml
+    /// [main]
+    /// next = ["j", "down", "ctrl-n"]
+    /// first = "gg"
+    /// refresh = []
+    /// ```
+    pub fn apply_overrides(&mut self, content: &str) {
+        let table: HashMap<String, HashMap<String, toml::Value>> = match toml::from_str(content) {
+            Ok(table) => table,
+            Err(e) => {
+                self.warnings
+                    .push(format!("keymap.toml: {}", e.message().trim()));
+                return;
+            }
+        };
+
+        for (context_id, actions) in table {
+            let Some(context) = KeyContext::from_id(&context_id) else {
+                self.warnings
+                    .push(format!("keymap.toml: unknown section [{}]", context_id));
+                continue;
+            };
+
+            for (action_id, value) in actions {
+                let Some(action) = Action::from_id(context, &action_id) else {
+                    self.warnings.push(format!(
+                        "keymap.toml: unknown action `{}` in [{}]",
+                        action_id, context_id
+                    ));
+                    continue;
+                };
+
+                let keys: Vec<String> = match value {
+                    toml::Value::String(key) => vec![key],
+                    toml::Value::Array(keys) => keys
+                        .into_iter()
+                        .filter_map(|k| k.as_str().map(str::to_string))
+                        .collect(),
+                    _ => {
+                        self.warnings.push(format!(
+                            "keymap.toml: `{}` in [{}] must be a key or a list of keys",
+                            action_id, context_id
+                        ));
+                        continue;
+                    }
+                };
+
+                self.bindings
+                    .retain(|b| !(b.context == context && b.action == action));
+                for key in keys {
+                    match parse_sequence(&key) {
+                        Ok(keys) => self.bindings.push(Binding {
+                            context,
+                            keys,
+                            action,
+                        }),
+                        Err(e) => self
+                            .warnings
+                            .push(format!("keymap.toml: {} for `{}`", e, action_id)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sequences bound to several actions, or shadowed by a shorter sequence
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+
+        for (i, a) in self.bindings.iter().enumerate() {
+            for b in self.bindings.iter().skip(i + 1) {
+                if a.context != b.context || a.action == b.action {
+                    continue;
+                }
+                if a.keys == b.keys {
+                    conflicts.push(format!(
+                        "[{}] {} is bound to both `{}` and `{}`",
+                        a.context.id(),
+                        sequence_label(&a.keys),
+                        a.action.id(),
+                        b.action.id()
+                    ));
+                } else if a.keys.starts_with(&b.keys) || b.keys.starts_with(&a.keys) {
+                    let (short, long) = if a.keys.len() < b.keys.len() {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    };
+                    conflicts.push(format!(
+                        "[{}] {} (`{}`) shadows {} (`{}`)",
+                        a.context.id(),
+                        sequence_label(&short.keys),
+                        short.action.id(),
+                        sequence_label(&long.keys),
+                        long.action.id()
+                    ));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Feed a key press, given the keys already pending in this context
+    pub fn resolve(
+        &self,
+        context: KeyContext,
+        pending: &[KeyChord],
+        chord: KeyChord,
+    ) -> KeyResolution {
+        let mut keys = pending.to_vec();
+        keys.push(chord);
+
+        let candidates: Vec<&Binding> = self
+            .bindings
+            .iter()
+            .filter(|b| b.context == context && b.keys.starts_with(&keys))
+            .collect();
+
+        // Shortest match wins, so a shadowed longer sequence never fires
+        if let Some(binding) = candidates.iter().find(|b| b.keys == keys) {
+            return KeyResolution::Action(binding.action);
+        }
+        if !candidates.is_empty() {
+            return KeyResolution::Pending(keys);
+        }
+        if !pending.is_empty() {
+            // Abandon the sequence and try the key on its own
+            return self.resolve(context, &[], chord);
+        }
+        KeyResolution::Unbound
+    }
+
+    /// Keys bound to an action, formatted for display (`j/Down`)
+    pub fn keys_label(&self, context: KeyContext, action: Action) -> Option<String> {
+        let labels: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|b| b.context == context && b.action == action)
+            .map(|b| sequence_label(&b.keys))
+            .collect();
+        if labels.is_empty() {
+            None
+        } else {
+            Some(labels.join("/"))
+        }
+    }
+
+    /// Rows for the keybinds popup: `(context, keys, description)` for every bound action
+    pub fn help_entries(&self) -> Vec<(KeyContext, String, String)> {
+        let mut entries = Vec::new();
+        for &context in KeyContext::ALL {
+            let actions = Action::for_context(context);
+
+            // Show the nine preset slots as a single row while they keep their digits
+            let presets_on_digits = (1..=9).all(|n| {
+                actions.contains(&Action::ApplyPreset(n))
+                    && self.keys_label(context, Action::ApplyPreset(n)) == Some(n.to_string())
+            });
+
+            for action in actions {
+                if let (true, Action::ApplyPreset(n)) = (presets_on_digits, action) {
+                    if n == 1 {
+                        entries.push((context, "1-9".to_string(), "Apply preset".to_string()));
+                    }
+                    continue;
+                }
+                if let Some(keys) = self.keys_label(context, action) {
+                    entries.push((context, keys, action.description(context)));
+                }
+            }
+        }
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> KeyChord {
+        KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn keymap(overrides: &str) -> Keymap {
+        let mut keymap = Keymap::default();
+        keymap.apply_overrides(overrides);
+        keymap
+    }
+
+    #[test]
+    fn parse_chords_with_modifiers() {
+        assert_eq!(
+            KeyChord::parse("ctrl-d"),
+            Ok(KeyChord::new(KeyCode::Char('d'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            KeyChord::parse("Alt-Shift-Left"),
+            Ok(KeyChord::new(
+                KeyCode::Left,
+                KeyModifiers::ALT | KeyModifiers::SHIFT
+            ))
+        );
+        // Shift is part of the character
+        assert_eq!(KeyChord::parse("shift-g"), Ok(key('g')));
+        assert_eq!(KeyChord::parse("G"), Ok(key('G')));
+        // A trailing modifier name is the key itself
+        assert_eq!(
+            KeyChord::parse("ctrl--"),
+            Ok(KeyChord::new(KeyCode::Char('-'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn parse_named_keys() {
+        let code = |input: &str| KeyChord::parse(input).map(|chord| chord.code);
+        assert_eq!(code("PageDown"), Ok(KeyCode::PageDown));
+        assert_eq!(code("return"), Ok(KeyCode::Enter));
+        assert_eq!(code("escape"), Ok(KeyCode::Esc));
+        assert_eq!(code("del"), Ok(KeyCode::Delete));
+        assert_eq!(code("space"), Ok(KeyCode::Char(' ')));
+        assert_eq!(code("minus"), Ok(KeyCode::Char('-')));
+        assert_eq!(code("f12"), Ok(KeyCode::F(12)));
+        assert_eq!(code("$"), Ok(KeyCode::Char('$')));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_keys() {
+        assert_eq!(KeyChord::parse("f13"), Err("unknown key `f13`".to_string()));
+        assert_eq!(
+            KeyChord::parse("ctrl-page"),
+            Err("unknown key `ctrl-page`".to_string())
+        );
+        assert!(KeyChord::parse("").is_err());
+    }
+
+    #[test]
+    fn chord_ids_parse_back() {
+        for input in [
+            "ctrl-d", "alt-left", "pagedown", "G", "space", "minus", "f5",
+        ] {
+            let chord = KeyChord::parse(input).unwrap();
+            assert_eq!(chord.id(), input);
+            assert_eq!(KeyChord::parse(&chord.id()), Ok(chord));
+        }
+    }
+
+    #[test]
+    fn parse_sequences() {
+        assert_eq!(parse_sequence("gg"), Ok(vec![key('g'), key('g')]));
+        assert_eq!(
+            parse_sequence("ctrl-w j"),
+            Ok(vec![
+                KeyChord::new(KeyCode::Char('w'), KeyModifiers::CONTROL),
+                key('j')
+            ])
+        );
+        assert_eq!(
+            parse_sequence(" pagedown "),
+            Ok(vec![KeyChord::new(KeyCode::PageDown, KeyModifiers::NONE)])
+        );
+        assert_eq!(parse_sequence("ff"), Ok(vec![key('f'), key('f')]));
+        assert_eq!(parse_sequence("  "), Err("empty key".to_string()));
+        // Typos in chords aren't read as runs of characters
+        assert_eq!(
+            parse_sequence("ctrl-pgdn"),
+            Err("unknown key `ctrl-pgdn`".to_string())
+        );
+        assert_eq!(parse_sequence("F13"), Err("unknown key `F13`".to_string()));
+        assert_eq!(
+            parse_sequence("ctrl-w nope"),
+            Err("unknown key `nope`".to_string())
+        );
+    }
+
+    #[test]
+    fn resolve_multi_key_sequences() {
+        let keymap = Keymap::default();
+        let main = KeyContext::Main;
+        assert_eq!(
+            keymap.resolve(main, &[], key('g')),
+            KeyResolution::Pending(vec![key('g')])
+        );
+        assert_eq!(
+            keymap.resolve(main, &[key('g')], key('g')),
+            KeyResolution::Action(Action::First)
+        );
+        // A key that breaks the sequence is tried on its own
+        assert_eq!(
+            keymap.resolve(main, &[key('g')], key('j')),
+            KeyResolution::Action(Action::Next)
+        );
+        assert_eq!(
+            keymap.resolve(main, &[key('g')], key('%')),
+            KeyResolution::Unbound
+        );
+        assert_eq!(
+            keymap.resolve(KeyContext::Menu, &[], key('g')),
+            KeyResolution::Unbound
+        );
+    }
+
+    #[test]
+    fn a_shorter_binding_shadows_a_longer_one() {
+        let keymap = keymap("[main]\nrefresh = \"g\"");
+        assert_eq!(
+            keymap.resolve(KeyContext::Main, &[], key('g')),
+            KeyResolution::Action(Action::Refresh)
+        );
+        assert_eq!(
+            keymap.conflicts(),
+            ["[main] g (`refresh`) shadows gg (`first`)"]
+        );
+    }
+
+    #[test]
+    fn pending_keys_time_out() {
+        let at = Instant::now();
+        let pending = PendingKeys {
+            keys: vec![key('g')],
+            at,
+        };
+        assert_eq!(pending.active(at + SEQUENCE_TIMEOUT), [key('g')]);
+        assert!(pending
+            .active(at + SEQUENCE_TIMEOUT + Duration::from_millis(1))
+            .is_empty());
+    }
+
+    #[test]
+    fn conflicts_in_one_context() {
+        assert!(Keymap::default().conflicts().is_empty());
+
+        let keymap = keymap("[main]\nrefresh = [\"j\", \"ctrl-r\"]\n[menu]\nquit = \"j\"");
+        assert_eq!(
+            keymap.conflicts(),
+            [
+                "[main] j is bound to both `next` and `refresh`",
+                "[menu] j is bound to both `next` and `quit`"
+            ]
+        );
+    }
+
+    #[test]
+    fn overrides_replace_defaults_and_report_problems() {
+        let keymap = keymap(
+            "[main]\nfirst = \"home\"\nrefresh = []\nnope = \"a\"\nnext = 3\nlast = [\"f99\", \"ctrl-pgdn\"]\n[nowhere]\n",
+        );
+        assert_eq!(
+            keymap.keys_label(KeyContext::Main, Action::First),
+            Some("Home".to_string())
+        );
+        assert_eq!(keymap.keys_label(KeyContext::Main, Action::Refresh), None);
+        assert_eq!(keymap.keys_label(KeyContext::Main, Action::Last), None);
+        let mut warnings = keymap.warnings.clone();
+        warnings.sort();
+        assert_eq!(
+            warnings,
+            [
+                "keymap.toml: `next` in [main] must be a key or a list of keys",
+                "keymap.toml: unknown action `nope` in [main]",
+                "keymap.toml: unknown key `ctrl-pgdn` for `last`",
+                "keymap.toml: unknown key `f99` for `last`",
+                "keymap.toml: unknown section [nowhere]",
+            ]
+        );
+    }
+}
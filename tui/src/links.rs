@@ -0,0 +1,110 @@
+//! Opening links in a browser: with `open_command` when set, otherwise with the
+//! system's default browser. Over SSH there is no browser to open, so links
+//! are copied instead (see `Model::open_link`).
+
+use dealve_core::shell::{self, Streams};
+
+use crate::tasks::Task;
+
+pub type OpenTask = Task<(String, Result<(), String>)>;
+
+/// Where links are opened
+pub trait Opener {
+    /// Open `url`, with `command` (`{url}` standing for the link) if given
+    fn open(&self, url: &str, command: Option<&str>) -> OpenTask;
+}
+
+/// The default browser, or the configured command
+pub struct SystemOpener;
+
+impl Opener for SystemOpener {
+    fn open(&self, url: &str, command: Option<&str>) -> OpenTask {
+        let url = url.to_string();
+        match command {
+            Some(command) => {
+                let command = command_line(command);
+                Task::spawn(async move {
+                    let result = run_open_command(command, url.clone()).await;
+                    (url, result)
+                })
+            }
+            // Some browsers only return once they've started
+            None => Task::spawn(async move {
+                let opened = url.clone();
+                let result = tokio::task::spawn_blocking(move || webbrowser::open(&opened))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result.map_err(|e| e.to_string()));
+                (url, result)
+            }),
+        }
+    }
+}
+
+/// Whether we're running in an SSH session, where a browser would open on
+/// the server if at all
+pub fn is_ssh() -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some())
+}
+
+/// `open_command` with `{url}` standing for the link, or the link added at
+/// the end when there is no `{url}`. The link is passed to the shell as an
+/// argument rather than spliced into the command, so nothing in it runs.
+fn command_line(template: &str) -> String {
+    let url = shell::argument(1);
+    if template.contains("{url}") {
+        // The argument is quoted already, quotes around it would undo that
+        template
+            .replace("'{url}'", "{url}")
+            .replace("\"{url}\"", "{url}")
+            .replace("{url}", &url)
+    } else {
+        format!("{} {}", template, url)
+    }
+}
+
+/// Run the open command with the shell. The terminal belongs to the TUI, and
+/// a browser may keep running long after the command returns.
+async fn run_open_command(command: String, url: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || shell::run_with_args(&command, &[&url], Streams::Discard))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// What the command receives as the link
+    fn received(template: &str, url: &str) -> String {
+        let template = template.replace("echo-url", "printf %s");
+        shell::run_with_args(&command_line(&template), &[url], Streams::Capture).unwrap()
+    }
+
+    #[test]
+    fn the_link_is_never_run_by_the_shell() {
+        for url in [
+            "https://example.com/it's",
+            "https://example.com/a;touch /tmp/dealve-pwned",
+            "https://example.com/$(touch /tmp/dealve-pwned)",
+            "https://example.com/`id`?a=1&b=\"2\"",
+        ] {
+            assert_eq!(received("echo-url {url}", url), url);
+            assert_eq!(received("echo-url", url), url);
+        }
+        assert!(!std::path::Path::new("/tmp/dealve-pwned").exists());
+    }
+
+    #[test]
+    fn quotes_around_the_placeholder_are_dropped() {
+        let url = "https://example.com/it's;$(id)";
+        assert_eq!(received("echo-url '{url}'", url), url);
+        assert_eq!(received("echo-url \"{url}\"", url), url);
+        assert_eq!(command_line("firefox '{url}'"), "firefox \"$1\"");
+        assert_eq!(command_line("open"), "open \"$1\"");
+    }
+}
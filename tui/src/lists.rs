@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Games on the watchlist or ignored, by game id with their title so the file
+/// can be read by hand. Kept locally: writing to the IsThereAnyDeal waitlist
+/// needs an OAuth login, not an API key.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GameLists {
+    #[serde(default)]
+    watchlist: BTreeMap<String, String>,
+    #[serde(default)]
+    ignored: BTreeMap<String, String>,
+    /// Where changes are saved, `None` to keep them in memory
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// Why the file couldn't be read, for the status line
+    #[serde(skip)]
+    pub warning: Option<String>,
+}
+
+impl GameLists {
+    /// Get the lists file path (~/.local/share/dealve/lists.json)
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|p| p.join("dealve").join("lists.json"))
+    }
+
+    /// Load the lists, or start empty, saving changes back to the file
+    pub fn load() -> Self {
+        Self::load_from(Self::path())
+    }
+
+    /// A file that can't be parsed is kept as `lists.json.invalid` before the
+    /// next save replaces it, or not replaced at all when it can't be kept
+    fn load_from(path: Option<PathBuf>) -> Self {
+        let Some(path) = path else {
+            return Self::default();
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self {
+                path: Some(path),
+                ..Self::default()
+            };
+        };
+        match serde_json::from_str::<Self>(&content) {
+            Ok(lists) => Self {
+                path: Some(path),
+                ..lists
+            },
+            Err(e) => {
+                let mut name = path.file_name().unwrap_or_default().to_os_string();
+                name.push(".invalid");
+                let kept = path.with_file_name(name);
+                let (path, outcome) = match fs::copy(&path, &kept) {
+                    Ok(_) => (Some(path), format!("kept as {}", kept.display())),
+                    Err(_) => (None, "changes won't be saved".to_string()),
+                };
+                Self {
+                    path,
+                    warning: Some(format!("invalid watchlist file ({}), {}", e, outcome)),
+                    ..Self::default()
+                }
+            }
+        }
+    }
+
+    pub fn is_watched(&self, game_id: &str) -> bool {
+        self.watchlist.contains_key(game_id)
+    }
+
+    pub fn is_ignored(&self, game_id: &str) -> bool {
+        self.ignored.contains_key(game_id)
+    }
+
+    /// Add games to the watchlist, or remove them when they are all on it.
+    /// Returns whether they were added.
+    pub fn toggle_watched(&mut self, games: &[(String, String)]) -> bool {
+        toggle(&mut self.watchlist, games)
+    }
+
+    /// Ignore games, or stop ignoring them when they all are. Returns
+    /// whether they were ignored.
+    pub fn toggle_ignored(&mut self, games: &[(String, String)]) -> bool {
+        toggle(&mut self.ignored, games)
+    }
+
+    pub fn ignored_count(&self) -> usize {
+        self.ignored.len()
+    }
+
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Swapped in whole, a truncated file would be set aside at the next
+        // load and the lists lost
+        let content = serde_json::to_string_pretty(self)?;
+        let temp_path = path.with_extension("json.tmp");
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(content.as_bytes())?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, path)
+    }
+}
+
+fn toggle(list: &mut BTreeMap<String, String>, games: &[(String, String)]) -> bool {
+    let add = games.iter().any(|(id, _)| !list.contains_key(id));
+    for (id, title) in games {
+        if add {
+            list.insert(id.clone(), title.clone());
+        } else {
+            list.remove(id);
+        }
+    }
+    add
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn games(ids: &[&str]) -> Vec<(String, String)> {
+        ids.iter()
+            .map(|id| (id.to_string(), format!("Game {}", id)))
+            .collect()
+    }
+
+    #[test]
+    fn toggling_adds_unless_every_game_is_listed() {
+        let mut list = BTreeMap::new();
+        assert!(toggle(&mut list, &games(&["a", "b"])));
+        assert_eq!(list.get("a").map(String::as_str), Some("Game a"));
+
+        // One missing game adds the rest again
+        assert!(toggle(&mut list, &games(&["b", "c"])));
+        assert_eq!(list.len(), 3);
+
+        assert!(!toggle(&mut list, &games(&["a", "c"])));
+        assert_eq!(list.keys().collect::<Vec<_>>(), ["b"]);
+    }
+
+    #[test]
+    fn toggling_nothing_changes_nothing() {
+        let mut list = BTreeMap::new();
+        assert!(!toggle(&mut list, &[]));
+        assert!(list.is_empty());
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dealve-lists-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn lists_are_saved_and_loaded() {
+        let dir = temp_dir("saved");
+        let path = dir.join("lists.json");
+        let mut lists = GameLists::load_from(Some(path.clone()));
+        assert!(lists.warning.is_none());
+        lists.toggle_watched(&games(&["a"]));
+        lists.save().unwrap();
+        assert!(!dir.join("lists.json.tmp").exists());
+
+        let lists = GameLists::load_from(Some(path));
+        assert!(lists.is_watched("a"));
+        assert!(lists.warning.is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_broken_file_is_kept_before_it_is_replaced() {
+        let dir = temp_dir("broken");
+        let path = dir.join("lists.json");
+        fs::write(&path, "{\"watchlist\": {\"a\": ").unwrap();
+        let mut lists = GameLists::load_from(Some(path.clone()));
+        let warning = lists.warning.clone().unwrap();
+        assert!(
+            warning.starts_with("invalid watchlist file ("),
+            "{}",
+            warning
+        );
+        assert!(warning.ends_with("lists.json.invalid"), "{}", warning);
+
+        lists.toggle_watched(&games(&["b"]));
+        lists.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("lists.json.invalid")).unwrap(),
+            "{\"watchlist\": {\"a\": "
+        );
+        assert!(GameLists::load_from(Some(path)).is_watched("b"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn watched_and_ignored_games_are_separate() {
+        let mut lists = GameLists::default();
+        assert!(lists.toggle_watched(&games(&["a"])));
+        assert!(lists.toggle_ignored(&games(&["b"])));
+        assert!(lists.is_watched("a") && !lists.is_ignored("a"));
+        assert!(lists.is_ignored("b") && !lists.is_watched("b"));
+        assert_eq!(lists.ignored_count(), 1);
+        // Without a path nothing is written
+        lists.save().unwrap();
+    }
+}
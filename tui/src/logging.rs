@@ -0,0 +1,146 @@
+//! Structured logs written to a daily rotated file in the state directory,
+//! and a record of recent API requests for the debug overlay.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{EnvFilter, Targets};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Filter used when neither `DEALVE_LOG` nor `RUST_LOG` is set
+const DEFAULT_FILTER: &str = "warn,dealve=info,dealve_api=info";
+
+/// Log files kept, one per day
+const MAX_LOG_FILES: usize = 7;
+
+/// Requests kept for the debug overlay
+const MAX_REQUESTS: usize = 50;
+
+/// Directory of the log files (~/.local/state/dealve/logs on Linux)
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("dealve").join("logs"))
+}
+
+/// Install the global subscriber. Logs go to a file filtered by `DEALVE_LOG`,
+/// or `RUST_LOG`, with the same syntax (`dealve_api=debug,info`). Requests
+/// are always recorded in the returned log, whatever the filter.
+///
+/// Keep the guard until exit, dropping it flushes the file.
+pub fn init() -> (RequestLog, Option<WorkerGuard>) {
+    let requests = RequestLog::default();
+    let request_layer = requests
+        .clone()
+        .with_filter(Targets::new().with_target(dealve_api::REQUEST_LOG_TARGET, Level::INFO));
+
+    let filter = EnvFilter::try_from_env("DEALVE_LOG")
+        .or_else(|_| EnvFilter::try_from_default_env())
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    let file = log_dir().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("dealve")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .ok()
+    });
+
+    // Without a state directory there are no logs, but the overlay still works
+    let (file_layer, guard) = match file {
+        Some(file) => {
+            let (writer, guard) = tracing_appender::non_blocking(file);
+            let layer = tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(writer)
+                .with_filter(filter);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let _ = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(request_layer)
+        .try_init();
+    (requests, guard)
+}
+
+/// One API request, from the event `ItadClient` logs for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestRecord {
+    pub at: DateTime<Local>,
+    pub endpoint: String,
+    /// HTTP status, `None` when no response came back
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Most recent API requests, shared between the subscriber and the main loop
+#[derive(Clone, Default)]
+pub struct RequestLog {
+    requests: Arc<Mutex<VecDeque<RequestRecord>>>,
+}
+
+impl RequestLog {
+    /// Requests from the most recent one
+    pub fn recent(&self) -> Vec<RequestRecord> {
+        match self.requests.lock() {
+            Ok(requests) => requests.iter().rev().cloned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RequestLog {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut record = RequestRecord {
+            at: Local::now(),
+            endpoint: String::new(),
+            status: None,
+            latency_ms: 0,
+            error: None,
+        };
+        event.record(&mut record);
+
+        if let Ok(mut requests) = self.requests.lock() {
+            if requests.len() == MAX_REQUESTS {
+                requests.pop_front();
+            }
+            requests.push_back(record);
+        }
+    }
+}
+
+impl Visit for RequestRecord {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "status" => self.status = Some(value as u16),
+            "latency_ms" => self.latency_ms = value,
+            _ => {}
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "endpoint" => self.endpoint = value.to_string(),
+            "error" => self.error = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
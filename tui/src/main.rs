@@ -1,50 +1,124 @@
+mod cli;
+mod clipboard;
+mod columns;
 mod config;
+mod demo;
 mod events;
+mod export;
+mod fixtures;
+mod keymap;
+mod links;
+mod lists;
+mod logging;
 mod message;
 mod model;
 mod onboarding;
+#[cfg(test)]
+mod replay;
+mod secrets;
+mod seen;
+mod session;
 mod tasks;
+mod themes;
 mod update;
 mod view;
 
 use anyhow::Result;
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Margin, Rect},
+    Terminal,
+};
 use std::io::{stdout, Stdout};
+use std::path::Path;
 use tachyonfx::fx::EvolveSymbolSet;
 use tachyonfx::pattern::RadialPattern;
 use tachyonfx::{fx, Effect, EffectTimer, Interpolation, Motion};
 
-use model::Model;
-use tasks::TaskManager;
-use update::UpdateResult;
+use cli::{Args, Command};
+use config::{ConfigStore, FileConfigStore};
+use logging::RequestLog;
+use message::Message;
+use model::{Model, StatusMessage};
+use session::{Recorder, Step};
+use tasks::{DataSource, SystemClock, TaskManager};
+use view::accessibility::{self, Accessibility};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
-    let api_key = config::Config::load_api_key();
+    let args = Args::parse(std::env::args().skip(1))?;
+    match args.command {
+        Command::Help => {
+            print!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Command::Version => {
+            println!("dealve {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Command::ShowConfig => {
+            cli::show_config(&args.config_store()?);
+            return Ok(());
+        }
+        Command::UpdateRates => return cli::update_rates(&args.config_store()?),
+        Command::Export => return cli::export(&args).await,
+        Command::Run | Command::Setup => {}
+    }
+
+    // Dropping the guard at the end of main flushes the log file
+    let (request_log, _log_guard) = logging::init();
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "starting");
+
+    let mut config_store = args.config_store()?;
+    let mut config = config_store.load();
+    for warning in &config.warnings {
+        tracing::warn!("config: {}", warning);
+    }
+    // Onboarding runs before the model holds the profile
+    let profile = Accessibility::new(
+        config.accessibility,
+        config.animations,
+        Accessibility::no_color_requested(),
+    );
+    // Before the TUI starts, the encrypted file asks for its passphrase
+    let source = match args.command {
+        _ if args.demo => Some(DataSource::Demo),
+        Command::Setup => None,
+        _ => {
+            secrets::migrate_plain_text_key(&mut config_store, &mut config).await?;
+            secrets::load_api_key(&config)
+                .await?
+                .map(|api_key| DataSource::Api { api_key })
+        }
+    };
     let mut terminal = setup_terminal()?;
 
-    let result = if api_key.is_none() {
-        match onboarding::run_onboarding(&mut terminal).await {
-            Ok(Some(key)) => run(&mut terminal, Some(key)).await,
+    let result = match source {
+        Some(source) => run(&mut terminal, config_store, source, request_log).await,
+        None => match onboarding::run_onboarding(&mut terminal, &mut config_store, profile).await {
+            Ok(Some(source)) => run(&mut terminal, config_store, source, request_log).await,
             Ok(None) => Ok(()),
             Err(e) => Err(e),
-        }
-    } else {
-        run(&mut terminal, api_key).await
+        },
     };
 
     restore_terminal()?;
+    if let Err(e) = &result {
+        tracing::error!("exiting with an error: {:#}", e);
+    }
     result
 }
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
     enable_raw_mode()?;
     let backend = CrosstermBackend::new(stdout());
     let terminal = Terminal::new(backend)?;
@@ -52,6 +126,7 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
 }
 
 fn restore_terminal() -> Result<()> {
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
@@ -59,45 +134,78 @@ fn restore_terminal() -> Result<()> {
 
 async fn run(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    api_key: Option<String>,
+    config_store: FileConfigStore,
+    source: DataSource,
+    request_log: RequestLog,
 ) -> Result<()> {
-    let mut model = Model::new(api_key);
-    let mut task_mgr = TaskManager::new();
+    let mut model = Model::load(Box::new(config_store));
+    model.debug.log_dir = logging::log_dir();
+    let demo = matches!(source, DataSource::Demo);
+    if demo {
+        model.ui.status = Some(StatusMessage::new(
+            "Demo mode: sample deals, run `dealve setup` to use your API key".to_string(),
+        ));
+    }
+    let mut task_mgr = TaskManager::new(source.loader(), Box::new(SystemClock));
+
+    // Record the session for a bug report, see `replay.rs`
+    if let Some(path) = std::env::var_os("DEALVE_RECORD") {
+        let size = terminal.size()?;
+        task_mgr.recorder = Some(Recorder::create(
+            Path::new(&path),
+            model.config_store.load(),
+            size.width,
+            size.height,
+            task_mgr.clock.now(),
+        )?);
+    }
 
     // Start initial load
     tasks::start_load(&mut model, &mut task_mgr);
 
     // Tachyonfx effects for animations
-    let mut effects: Vec<(Effect, Rect)> = Vec::new();
+    let mut effects: Vec<(Effect, EffectArea)> = Vec::new();
     let mut last_frame_time = std::time::Instant::now();
 
     // Initial evolve_into effect for app startup
-    let term_size = terminal.size()?;
-    let full_screen = Rect::new(0, 0, term_size.width, term_size.height);
-
-    let bg = view::styles::bg_dark();
+    let bg = model.palette.bg;
     let style = ratatui::style::Style::default()
         .fg(bg)
         .bg(ratatui::prelude::Color::Black);
 
     let timer = EffectTimer::from_ms(1200, Interpolation::CubicOut);
-    effects.push((
-        fx::evolve_into((EvolveSymbolSet::Shaded, style), timer)
-            .with_pattern(RadialPattern::center().with_transition_width(15.0)),
-        full_screen,
-    ));
+    if model.accessibility.animations {
+        effects.push((
+            fx::evolve_into((EvolveSymbolSet::Shaded, style), timer)
+                .with_pattern(RadialPattern::center().with_transition_width(15.0)),
+            EffectArea::Screen,
+        ));
+    }
 
     loop {
         // View
         let elapsed = last_frame_time.elapsed();
         last_frame_time = std::time::Instant::now();
 
+        if model.ui.show_debug {
+            model.debug.requests = request_log.recent();
+            model.debug.tasks = task_mgr.task_states();
+        }
+
         terminal.draw(|frame| {
             view::view(frame, &mut model);
 
             for (effect, area) in effects.iter_mut() {
-                effect.process(elapsed.into(), frame.buffer_mut(), *area);
+                let area = area.resolve(frame.area(), &model);
+                effect.process(elapsed.into(), frame.buffer_mut(), area);
             }
+
+            accessibility::apply(frame.buffer_mut(), model.accessibility);
+            view::hyperlinks::apply(
+                frame.buffer_mut(),
+                &model.ui.layout.links,
+                model.accessibility,
+            );
         })?;
 
         effects.retain(|(effect, _)| !effect.done());
@@ -107,21 +215,14 @@ async fn run(
         }
 
         // Check async tasks
-        let task_messages = tasks::check_tasks(&mut model, &mut task_mgr).await;
+        let task_messages = tasks::check_tasks(&mut model, &mut task_mgr);
         for msg in task_messages {
-            let result = update::update(&mut model, msg);
-            handle_result(&mut model, &mut task_mgr, &mut effects, terminal, result)?;
+            handle_message(&mut model, &mut task_mgr, &mut effects, msg);
         }
 
-        // Game info loading (debounced)
-        if task_mgr.pending_game_info_load
-            && !model.loading.deals
-            && effects.is_empty()
-            && task_mgr.last_selection_change.elapsed()
-                >= std::time::Duration::from_millis(model.game_info_delay_ms)
-        {
-            task_mgr.pending_game_info_load = false;
-            tasks::load_game_info_if_needed(&mut model).await;
+        // Game info loading (debounced), after animations
+        if effects.is_empty() {
+            tasks::load_game_info_if_due(&mut model, &mut task_mgr);
         }
 
         // Handle event
@@ -131,57 +232,67 @@ async fn run(
             std::time::Duration::from_millis(50)
         };
 
-        if let Some(msg) = events::handle_event(&model, poll_duration)? {
-            let result = update::update(&mut model, msg);
-            handle_result(&mut model, &mut task_mgr, &mut effects, terminal, result)?;
+        let msg = match events::poll_event(poll_duration)? {
+            Some(event) => {
+                if let Some(step) = Step::from_event(&event) {
+                    task_mgr.record(|| step);
+                }
+                events::handle_event(&model, event, task_mgr.clock.now())
+            }
+            None => Some(Message::Tick),
+        };
+        if let Some(msg) = msg {
+            handle_message(&mut model, &mut task_mgr, &mut effects, msg);
+        }
+    }
+
+    // Sample deals would mark real ones as seen
+    if !demo {
+        if let Err(e) = model.seen.save() {
+            tracing::warn!(error = %e, "saving the seen deals failed");
         }
     }
 
     Ok(())
 }
 
-fn handle_result(
-    model: &mut Model,
-    task_mgr: &mut TaskManager,
-    effects: &mut Vec<(Effect, Rect)>,
-    terminal: &Terminal<CrosstermBackend<Stdout>>,
-    result: UpdateResult,
-) -> Result<()> {
-    // Handle selection changed → reset debounce timer
-    if result.selection_changed {
-        task_mgr.last_selection_change = std::time::Instant::now();
-        task_mgr.pending_game_info_load = true;
+/// Where an effect is drawn, resolved on every frame so it follows resizes
+/// and layout changes
+#[derive(Debug, Clone, Copy)]
+enum EffectArea {
+    Screen,
+    /// Inside the borders of the deals list
+    DealsList,
+}
+
+impl EffectArea {
+    fn resolve(&self, screen: Rect, model: &Model) -> Rect {
+        match self {
+            EffectArea::Screen => screen,
+            EffectArea::DealsList => model.ui.layout.deals.inner(Margin::new(1, 1)),
+        }
     }
+}
 
-    // Handle reload request → spawn new deals load
-    if result.needs_reload {
-        tasks::start_load(model, task_mgr);
+fn handle_message(
+    model: &mut Model,
+    task_mgr: &mut TaskManager,
+    effects: &mut Vec<(Effect, EffectArea)>,
+    msg: Message,
+) {
+    let reloaded = tasks::dispatch(model, task_mgr, msg);
 
+    if reloaded && model.accessibility.animations {
         // Trigger sweep-in effect for deals list
-        let term_size = terminal.size()?;
-        let deals_inner = Rect::new(
-            1,
-            1,
-            term_size.width / 2 - 2,
-            term_size.height.saturating_sub(2),
-        );
         effects.push((
             fx::sweep_in(
                 Motion::UpToDown,
                 15,
                 3,
-                view::styles::bg_dark(),
+                model.palette.bg,
                 (600, Interpolation::QuadOut),
             ),
-            deals_inner,
+            EffectArea::DealsList,
         ));
     }
-
-    // Handle chained messages
-    if let Some(chained_msg) = result.msg {
-        let chained_result = update::update(model, chained_msg);
-        handle_result(model, task_mgr, effects, terminal, chained_result)?;
-    }
-
-    Ok(())
 }
@@ -1,4 +1,8 @@
-use dealve_core::models::{Deal, PriceHistoryPoint};
+use dealve_core::models::{Deal, GameInfo, PriceHistoryPoint, RegionalPrice};
+use dealve_core::rates::ExchangeRates;
+use std::time::Instant;
+
+use crate::keymap::PendingKeys;
 
 pub enum Message {
     // Navigation
@@ -9,6 +13,21 @@ pub enum Message {
     SelectTop,
     SelectBottom,
     OpenSelectedDeal,
+    ScrollDeals(isize),
+    /// Deal row clicked with the mouse; a second click on the same row opens it
+    DealClicked {
+        index: usize,
+        at: Instant,
+    },
+    InspectChartPoint(usize),
+
+    // Layout
+    FocusNextPane,
+    ToggleMaximize,
+    ToggleDetails,
+    /// Debug overlay with recent requests, tasks and cache hit rates
+    ToggleDebug,
+    Resize,
 
     // Menu
     ToggleMenu,
@@ -23,6 +42,21 @@ pub enum Message {
     FilterPush(char),
     FilterPop,
     ClearFilters,
+    ToggleNewOnly,
+
+    // Selection
+    /// Add or remove the deal under the cursor, then move down
+    ToggleDealSelection,
+    /// Select the deals from the last one toggled to the cursor
+    SelectDealRange,
+    ClearSelection,
+
+    // Watchlist and ignored games
+    /// Add the selected games to the watchlist, or remove them
+    ToggleWatchlist,
+    /// Ignore the selected games, or stop ignoring them
+    ToggleIgnored,
+    ToggleShowIgnored,
 
     // Price filter
     OpenPriceFilter,
@@ -37,6 +71,59 @@ pub enum Message {
     PlatformPopupNext,
     PlatformPopupPrev,
     PlatformPopupSelect,
+    PlatformPopupClick(usize),
+
+    // Presets
+    OpenPresetPopup,
+    PresetPopupNext,
+    PresetPopupPrev,
+    PresetPopupSelect,
+    ApplyPreset(usize),
+    PresetStartSave,
+    PresetNamePush(char),
+    PresetNamePop,
+    PresetConfirmSave,
+    PresetDelete,
+
+    // Regional prices
+    /// Compare the selected game's price across the configured regions
+    OpenRegions,
+    RegionsNext,
+    RegionsPrev,
+    RegionsOpenOffer,
+    RegionsRefresh,
+
+    // Export
+    OpenExport,
+    ExportNext,
+    ExportPrev,
+    /// Add or remove the highlighted column
+    ExportToggleColumn,
+    ExportNextFormat,
+    ExportPrevFormat,
+    /// Ask for the file to export to
+    ExportStartPath,
+    ExportPathPush(char),
+    ExportPathPop,
+    ExportConfirm,
+    /// Game info for the pending export, which is then written
+    ExportInfoLoaded(Vec<GameInfo>),
+
+    // Clipboard
+    OpenYank,
+    YankNext,
+    YankPrev,
+    /// Copy the highlighted item of the selected deals, one per line
+    YankSelect,
+    /// The clipboard command finished
+    ClipboardCommandDone(Result<(), String>),
+
+    // Links
+    /// The browser or `open_command` was started for a link
+    LinkOpened {
+        url: String,
+        result: Result<(), String>,
+    },
 
     // Sort
     ToggleSortDirection,
@@ -45,14 +132,27 @@ pub enum Message {
 
     // Popups
     ClosePopup,
+    KeybindsScrollDown,
+    KeybindsScrollUp,
 
     // Options
     OptionsNextTab,
     OptionsPrevTab,
+    OptionsSelectTab(usize),
     OptionsNextItem,
     OptionsPrevItem,
     OptionsToggleItem,
     OptionsToggleSortDirection,
+    /// Add or remove the highlighted region from the compared regions
+    OptionsToggleCompareRegion,
+    /// Next currency prices are converted to, then none
+    OptionsCycleDisplayCurrency,
+    /// Run the rates command to update the exchange rates
+    OptionsRefreshRates,
+    /// Move the highlighted deals table column earlier (-1) or later (1)
+    OptionsMoveColumn(isize),
+    /// Widen (positive) or narrow the highlighted deals table column
+    OptionsResizeColumn(i16),
 
     // Data loading results
     RequestRefresh,
@@ -71,6 +171,25 @@ pub enum Message {
         game_id: String,
         history: Vec<PriceHistoryPoint>,
     },
+    /// Game info for the selected deal, `None` when loading failed
+    GameInfoLoaded {
+        game_id: String,
+        info: Option<GameInfo>,
+    },
+    GameInfoBatchLoaded(Vec<GameInfo>),
+    RegionalPricesLoaded {
+        game_id: String,
+        result: Result<Vec<RegionalPrice>, String>,
+    },
+    RatesRefreshed(Result<ExchangeRates, String>),
+
+    // Keys
+    /// Progress of a multi-key sequence: keys typed so far, and the message
+    /// to run once the sequence completes
+    KeySequence {
+        pending: Option<PendingKeys>,
+        action: Option<Box<Message>>,
+    },
 
     // System
     Tick,
@@ -1,9 +1,29 @@
-use dealve_core::models::{Deal, GameInfo, Platform, PriceHistoryPoint, Region};
+use chrono::{DateTime, Datelike, NaiveDate};
+use dealve_core::models::{
+    Deal, GameInfo, Platform, PriceHistoryPoint, Region, RegionalOffer, RegionalPrice,
+};
+use dealve_core::money::{Amount, Currency, Money};
+use dealve_core::query::DealQuery;
+use dealve_core::rates::ExchangeRates;
+use ratatui::layout::Rect;
 use ratatui::widgets::{ListState, TableState};
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-
-use crate::config::Config;
-use crate::view::styles::Theme;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::columns::{default_columns, ColumnSetting, DealsColumn};
+use crate::config::{Config, ConfigStore, Preset};
+use crate::export::{Export, ExportFormat};
+use crate::keymap::{Keymap, PendingKeys};
+use crate::links;
+use crate::lists::GameLists;
+use crate::logging::RequestRecord;
+use crate::seen::{DealStatus, SeenTracker};
+use crate::themes::ThemeRegistry;
+use crate::view::accessibility::Accessibility;
+use crate::view::hyperlinks::Hyperlink;
+use crate::view::styles::ThemePalette;
 
 // Enums
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -39,6 +59,13 @@ pub enum Popup {
     Keybinds,
     Platform,
     PriceFilter,
+    Presets,
+    /// Prices of the selected game across regions, see `RegionComparison`
+    Regions,
+    /// Format, columns and file to export the deals list to
+    Export,
+    /// What to copy from the selected deal, see `YankItem`
+    Yank,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -86,13 +113,34 @@ impl SortCriteria {
         }
     }
 
-    pub fn toggle_search(&self) -> Self {
+    /// Secondary keys used to break ties when sorting locally, in priority order
+    pub fn tiebreakers(&self) -> &'static [SortCriteria] {
         match self {
-            SortCriteria::Price => SortCriteria::Cut,
-            _ => SortCriteria::Price,
+            SortCriteria::Price => &[SortCriteria::Cut],
+            SortCriteria::Cut => &[SortCriteria::Price],
+            SortCriteria::Hottest
+            | SortCriteria::Expiring
+            | SortCriteria::Popular
+            | SortCriteria::ReleaseDate => &[SortCriteria::Cut, SortCriteria::Price],
         }
     }
 
+    /// Direction that puts the best deals first (highest cut, lowest price)
+    pub fn preferred_direction(&self) -> SortDirection {
+        match self {
+            SortCriteria::Cut | SortCriteria::ReleaseDate => SortDirection::Descending,
+            _ => SortDirection::Ascending,
+        }
+    }
+
+    /// Whether sorting locally by this criteria needs cached game info
+    pub fn needs_game_info(&self) -> bool {
+        matches!(
+            self,
+            SortCriteria::Hottest | SortCriteria::ReleaseDate | SortCriteria::Popular
+        )
+    }
+
     pub fn api_param(&self, ascending: bool) -> String {
         let base = match self {
             SortCriteria::Price => "price",
@@ -152,6 +200,7 @@ pub enum OptionsTab {
     Platforms,
     Advanced,
     Theme,
+    Columns,
 }
 
 impl OptionsTab {
@@ -160,6 +209,7 @@ impl OptionsTab {
         OptionsTab::Platforms,
         OptionsTab::Advanced,
         OptionsTab::Theme,
+        OptionsTab::Columns,
     ];
 
     pub fn name(&self) -> &str {
@@ -168,6 +218,7 @@ impl OptionsTab {
             OptionsTab::Platforms => "Platforms",
             OptionsTab::Advanced => "Advanced",
             OptionsTab::Theme => "Theme",
+            OptionsTab::Columns => "Columns",
         }
     }
 }
@@ -178,8 +229,8 @@ pub struct PriceFilterState {
     pub min_input: String,
     pub max_input: String,
     pub selected_field: usize, // 0 = min, 1 = max
-    pub active_min: Option<f64>,
-    pub active_max: Option<f64>,
+    pub active_min: Option<Amount>,
+    pub active_max: Option<Amount>,
 }
 
 impl PriceFilterState {
@@ -191,8 +242,8 @@ impl PriceFilterState {
     }
 
     pub fn apply(&mut self) {
-        self.active_min = self.min_input.parse().ok();
-        self.active_max = self.max_input.parse().ok();
+        self.active_min = Amount::parse(&self.min_input);
+        self.active_max = Amount::parse(&self.max_input);
     }
 
     pub fn is_active(&self) -> bool {
@@ -201,21 +252,22 @@ impl PriceFilterState {
 
     pub fn label(&self) -> String {
         match (self.active_min, self.active_max) {
-            (Some(min), Some(max)) => format!("{:.0}-{:.0}", min, max),
-            (Some(min), None) => format!(">{:.0}", min),
-            (None, Some(max)) => format!("<{:.0}", max),
+            (Some(min), Some(max)) => format!("{}-{}", min, max),
+            (Some(min), None) => format!(">{}", min),
+            (None, Some(max)) => format!("<{}", max),
             (None, None) => "—".to_string(),
         }
     }
 
-    pub fn matches(&self, price: f64) -> bool {
+    /// Whether a price is in the range, read in the price's currency
+    pub fn matches(&self, price: Money) -> bool {
         if let Some(min) = self.active_min {
-            if price < min {
+            if price < min.in_currency(price.currency()) {
                 return false;
             }
         }
         if let Some(max) = self.active_max {
-            if price > max {
+            if price > max.in_currency(price.currency()) {
                 return false;
             }
         }
@@ -229,13 +281,22 @@ pub struct OptionsState {
     pub region_list_index: usize,
     pub advanced_list_index: usize,
     pub theme_list_index: usize,
+    pub columns_list_index: usize,
     pub default_platform: Platform,
     pub enabled_platforms: HashSet<Platform>,
     pub region: Region,
     pub deals_page_size: usize,
     pub game_info_delay_ms: u64,
     pub default_sort: SortState,
-    pub theme: Theme,
+    /// Id of the selected theme, built-in or from the themes directory
+    pub theme: String,
+    pub accessibility: bool,
+    pub animations: bool,
+    /// Regions compared by the regional prices popup
+    pub compare_regions: Vec<Region>,
+    pub display_currency: Option<Currency>,
+    /// Deals table columns, the shown ones first in table order
+    pub columns: Vec<ColumnSetting>,
 }
 
 impl Default for OptionsState {
@@ -250,13 +311,19 @@ impl Default for OptionsState {
             region_list_index: 0,
             advanced_list_index: 0,
             theme_list_index: 0,
+            columns_list_index: 0,
             default_platform: Platform::All,
             enabled_platforms: enabled,
             region: Region::default(),
             deals_page_size: 50,
             game_info_delay_ms: 200,
             default_sort: SortState::default(),
-            theme: Theme::default(),
+            theme: "default".to_string(),
+            accessibility: false,
+            animations: true,
+            compare_regions: Config::default().get_compare_regions(),
+            display_currency: None,
+            columns: default_columns(),
         }
     }
 }
@@ -267,7 +334,7 @@ impl OptionsState {
         let default_platform = config.get_default_platform();
         let region = config.get_region();
         let default_sort = config.get_default_sort();
-        let theme = config.get_theme();
+        let theme = config.theme.clone();
 
         let default_platform = if enabled_platforms.contains(&default_platform) {
             default_platform
@@ -281,6 +348,7 @@ impl OptionsState {
             region_list_index: 0,
             advanced_list_index: 0,
             theme_list_index: 0,
+            columns_list_index: 0,
             default_platform,
             enabled_platforms,
             region,
@@ -288,21 +356,36 @@ impl OptionsState {
             game_info_delay_ms: config.game_info_delay_ms,
             default_sort,
             theme,
+            accessibility: config.accessibility,
+            animations: config.animations,
+            compare_regions: config.get_compare_regions(),
+            display_currency: config.get_display_currency(),
+            columns: config.get_columns(),
         }
     }
 
-    pub fn save_to_config(&self) {
-        let mut config = Config::load();
+    /// Copy the options into a config, keeping its other settings
+    pub fn write_to_config(&self, config: &mut Config) {
         config.update_from_options(
             self.default_platform,
             &self.enabled_platforms,
             self.region,
             self.default_sort,
-            self.theme,
+            &self.theme,
         );
         config.deals_page_size = self.deals_page_size;
         config.game_info_delay_ms = self.game_info_delay_ms;
-        let _ = config.save();
+        config.accessibility = self.accessibility;
+        config.animations = self.animations;
+        config.compare_regions = self
+            .compare_regions
+            .iter()
+            .map(|region| region.code().to_string())
+            .collect();
+        config.display_currency = self
+            .display_currency
+            .map(|currency| currency.code().to_string());
+        config.set_columns(&self.columns);
     }
 }
 
@@ -310,6 +393,10 @@ impl OptionsState {
 pub struct FilterState {
     pub active: bool,
     pub text: String,
+    /// Last confirmed filter input, restored when editing or cancelling
+    pub applied: String,
+    /// Syntax error in the current input, shown inline in the status bar
+    pub error: Option<String>,
 }
 
 pub struct PaginationState {
@@ -333,6 +420,14 @@ pub struct LoadingState {
     pub deals: bool,
     pub game_info: Option<String>,
     pub price_history: Option<String>,
+    /// Game info prefetched for the whole list is loading
+    pub game_info_batch: bool,
+    /// Game whose regional prices are loading
+    pub regional_prices: Option<String>,
+    /// The rates command is running
+    pub rates: bool,
+    /// Game info for the release dates or tags of an export is loading
+    pub export_info: bool,
 }
 
 pub struct UiState {
@@ -343,6 +438,28 @@ pub struct UiState {
     pub list_state: ListState,
     pub spinner_frame: usize,
     pub platform_popup_index: usize,
+    pub preset_popup_index: usize,
+    pub yank_popup_index: usize,
+    /// Name being typed when saving the current view as a preset
+    pub preset_name_input: Option<String>,
+    pub keybinds_scroll: usize,
+    /// Keys typed so far of an incomplete multi-key binding
+    pub pending_keys: Option<PendingKeys>,
+    /// Screen areas from the last render, used for mouse hit-testing
+    pub layout: LayoutState,
+    /// Last clicked deal row, to detect double-clicks
+    pub last_click: Option<(usize, Instant)>,
+    /// Price history point picked with the mouse in the chart
+    pub chart_cursor: Option<usize>,
+    /// Pane targeted by the maximize key
+    pub focus: Pane,
+    pub maximized: bool,
+    /// Details overlay, when the terminal is too small to show them beside the list
+    pub show_details: bool,
+    /// Message shown in place of the status line shortcuts
+    pub status: Option<StatusMessage>,
+    /// Debug overlay over everything else, see `DebugInfo`
+    pub show_debug: bool,
 }
 
 impl Default for UiState {
@@ -359,8 +476,233 @@ impl Default for UiState {
             list_state,
             spinner_frame: 0,
             platform_popup_index: 0,
+            preset_popup_index: 0,
+            yank_popup_index: 0,
+            preset_name_input: None,
+            keybinds_scroll: 0,
+            pending_keys: None,
+            layout: LayoutState::default(),
+            last_click: None,
+            chart_cursor: None,
+            focus: Pane::Deals,
+            maximized: false,
+            show_details: false,
+            status: None,
+            show_debug: false,
+        }
+    }
+}
+
+/// Choices of the export popup, kept for the session
+pub struct ExportState {
+    pub format: ExportFormat,
+    /// Columns to write, in the order they were enabled
+    pub columns: Vec<DealsColumn>,
+    /// Highlighted entry of `DealsColumn::EXPORT`
+    pub cursor: usize,
+    /// Path being typed, once the columns are chosen
+    pub path_input: Option<String>,
+    /// Path of the last export, offered again
+    pub last_path: Option<String>,
+    /// Export waiting for game info, see `LoadingState::export_info`
+    pub pending: Option<Export>,
+}
+
+impl ExportState {
+    pub fn new(columns: Vec<DealsColumn>) -> Self {
+        Self {
+            format: ExportFormat::default(),
+            columns,
+            cursor: 0,
+            path_input: None,
+            last_path: None,
+            pending: None,
+        }
+    }
+}
+
+/// Ticks a status message stays up, about five seconds when idle
+const STATUS_MESSAGE_TICKS: u16 = 100;
+
+/// Short-lived message in the deals status line, e.g. a failed save
+pub struct StatusMessage {
+    pub text: String,
+    pub ticks_left: u16,
+}
+
+impl StatusMessage {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            ticks_left: STATUS_MESSAGE_TICKS,
+        }
+    }
+}
+
+/// Main panes of the deals screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pane {
+    #[default]
+    Deals,
+    Details,
+    Chart,
+}
+
+/// Where things were drawn on the last frame
+#[derive(Debug, Clone, Default)]
+pub struct LayoutState {
+    pub deals: Rect,
+    pub details: Rect,
+    pub chart: Rect,
+    /// Panes the current terminal size can show, in focus order
+    pub panes: Vec<Pane>,
+    pub options_tabs: Vec<Rect>,
+    /// Entries area of the platform popup and the index of its first visible entry
+    pub popup_list: Rect,
+    pub popup_scroll: usize,
+    /// URLs on screen, turned into terminal hyperlinks by the main loop
+    pub links: Vec<Hyperlink>,
+}
+
+/// What the debug overlay shows besides the cache hit rates, refreshed by
+/// the main loop while it's open
+#[derive(Default)]
+pub struct DebugInfo {
+    /// Most recent API requests first
+    pub requests: Vec<RequestRecord>,
+    /// Background tasks and their state, see `TaskManager::task_states`
+    pub tasks: Vec<(&'static str, String)>,
+    /// Where the log files are written
+    pub log_dir: Option<PathBuf>,
+}
+
+/// A game's prices across the compared regions, shown by `Popup::Regions`
+pub struct RegionComparison {
+    pub game_id: String,
+    pub title: String,
+    pub regions: Vec<Region>,
+    /// Currency the prices are compared in
+    pub currency: Currency,
+    /// `None` until loaded, then the prices or why they couldn't be loaded
+    pub prices: Option<Result<Vec<RegionalPrice>, String>>,
+    /// Selected row, an index into `rows`
+    pub selected: usize,
+}
+
+/// One region in a comparison
+pub struct ComparisonRow<'a> {
+    pub region: Region,
+    pub offer: Option<&'a RegionalOffer>,
+    /// Price in the comparison's currency, `None` without an offer or an
+    /// exchange rate
+    pub converted: Option<Money>,
+}
+
+impl RegionComparison {
+    pub fn new(game_id: String, title: String, regions: Vec<Region>, currency: Currency) -> Self {
+        Self {
+            game_id,
+            title,
+            regions,
+            currency,
+            prices: None,
+            selected: 0,
+        }
+    }
+
+    /// Loaded regions, cheapest first once converted. Offers without an
+    /// exchange rate come next, then the regions where the game isn't sold.
+    pub fn rows(&self, rates: &ExchangeRates) -> Vec<ComparisonRow<'_>> {
+        let Some(Ok(prices)) = &self.prices else {
+            return Vec::new();
+        };
+        let mut rows: Vec<ComparisonRow> = prices
+            .iter()
+            .map(|price| {
+                let offer = price.offer.as_ref();
+                ComparisonRow {
+                    region: price.region,
+                    offer,
+                    converted: offer
+                        .and_then(|offer| rates.convert(offer.price.amount, self.currency)),
+                }
+            })
+            .collect();
+        rows.sort_by_key(|row| match (row.converted, row.offer) {
+            (Some(converted), _) => (0, converted.minor()),
+            (None, Some(_)) => (1, 0),
+            (None, None) => (2, 0),
+        });
+        rows
+    }
+
+    /// Cheapest and dearest regions among `rows`, `None` when no offer
+    /// could be converted
+    pub fn spread(rows: &[ComparisonRow]) -> Option<Spread> {
+        let mut converted = rows
+            .iter()
+            .filter_map(|row| Some((row.region, row.converted?)));
+        let (cheapest, low) = converted.next()?;
+        let (dearest, high) = converted.next_back().unwrap_or((cheapest, low));
+        Some(Spread {
+            cheapest,
+            low,
+            dearest,
+            high,
+        })
+    }
+}
+
+/// Price range of a comparison, in its currency
+pub struct Spread {
+    pub cheapest: Region,
+    pub low: Money,
+    pub dearest: Region,
+    pub high: Money,
+}
+
+impl Spread {
+    /// How much more the dearest region pays
+    pub fn amount(&self) -> Money {
+        self.high.checked_sub(self.low).unwrap_or(self.high)
+    }
+
+    /// How much more a price is than the cheapest one, in percent
+    pub fn percent_over_low(&self, price: Money) -> f64 {
+        if self.low.minor() == 0 {
+            return 0.0;
+        }
+        (price.minor() as f64 / self.low.minor() as f64 - 1.0) * 100.0
+    }
+}
+
+/// Lookups of a cache when a deal gets selected
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HitCount {
+    pub hits: u32,
+    pub misses: u32,
+}
+
+impl HitCount {
+    pub fn record(&mut self, hit: bool) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
         }
     }
+
+    /// Share of hits, `None` before the first lookup
+    pub fn rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub game_info: HitCount,
+    pub price_history: HitCount,
 }
 
 // Model
@@ -375,8 +717,26 @@ pub struct Model {
 
     // Filters
     pub filter: FilterState,
+    pub query: DealQuery,
     pub active_search_query: Option<String>,
     pub price_filter: PriceFilterState,
+    /// Only show deals that are new or cheaper since the last visit
+    pub new_only: bool,
+
+    // New since last visit
+    pub seen: SeenTracker,
+
+    // Selection
+    /// Deals picked for batch actions, by `Deal::key`, so they stay picked
+    /// when the list is sorted, filtered or paged
+    pub selection: HashSet<String>,
+    /// Deal the next range selection starts from
+    pub selection_anchor: Option<String>,
+
+    // Watchlist and ignored games
+    pub lists: GameLists,
+    /// Show ignored games instead of hiding them
+    pub show_ignored: bool,
 
     // Sort
     pub sort_state: SortState,
@@ -385,6 +745,43 @@ pub struct Model {
     pub platform_filter: Platform,
     pub region: Region,
 
+    // Regional prices
+    pub comparison: Option<RegionComparison>,
+
+    // Currency conversion
+    /// Currency prices are converted to, `None` to show them as sold only
+    pub display_currency: Option<Currency>,
+    pub exchange_rates: ExchangeRates,
+    /// File the exchange rates were read from, `None` for the built-in ones
+    pub rates_file: Option<PathBuf>,
+    /// Command printing new rates, see `Config::rates_command`
+    pub rates_command: Option<String>,
+    /// A rates refresh was asked for and isn't running yet
+    pub pending_rates_refresh: bool,
+
+    // Export
+    pub export: ExportState,
+
+    // Clipboard
+    /// Command copied text is also piped to, see `Config::clipboard_command`
+    pub clipboard_command: Option<String>,
+    /// Text to copy on the next `check_tasks`
+    pub pending_copy: Option<String>,
+
+    // Links
+    /// Command links are opened with, see `Config::open_command`
+    pub open_command: Option<String>,
+    /// Running over SSH, where links are copied rather than opened
+    pub ssh: bool,
+    /// Links to open on the next `check_tasks`
+    pub pending_open: Vec<String>,
+
+    // Presets
+    pub presets: Vec<Preset>,
+    /// Preset to restore on the next `start_load`
+    pub pending_preset: Option<Preset>,
+    pub active_preset: Option<String>,
+
     // Pagination
     pub pagination: PaginationState,
 
@@ -394,11 +791,27 @@ pub struct Model {
     // Options
     pub options: OptionsState,
 
+    // Key bindings
+    pub keymap: Keymap,
+
+    // Themes
+    pub themes: ThemeRegistry,
+    /// Colours of the selected theme, installed by `view` for each frame
+    pub palette: ThemePalette,
+    /// Active accessibility profile, from the options and `NO_COLOR`
+    pub accessibility: Accessibility,
+    /// `NO_COLOR` is set, see `Accessibility::no_color_requested`
+    pub no_color: bool,
+
     // Config
-    pub api_key: Option<String>,
+    pub config_store: Box<dyn ConfigStore>,
     pub deals_page_size: usize,
     pub game_info_delay_ms: u64,
 
+    // Debug
+    pub debug: DebugInfo,
+    pub cache_stats: CacheStats,
+
     // Error
     pub error: Option<String>,
 
@@ -407,36 +820,258 @@ pub struct Model {
 }
 
 impl Model {
-    pub fn new(api_key: Option<String>) -> Self {
-        let config = Config::load();
+    /// Build the model from the config store, and the seen snapshot, game
+    /// lists, keymap, themes and exchange rates on disk
+    pub fn load(config_store: Box<dyn ConfigStore>) -> Self {
+        let mut model = Self::with_store(config_store);
+        model.seen = SeenTracker::load();
+        model.lists = GameLists::load();
+        if let Some(warning) = &model.lists.warning {
+            tracing::warn!("{}", warning);
+            model.ui.status = Some(StatusMessage::new(warning.clone()));
+        }
+        model.keymap = Keymap::load();
+        model.themes = ThemeRegistry::load();
+        model.apply_theme();
+        model.no_color = Accessibility::no_color_requested();
+        model.apply_accessibility();
+        model.load_rates();
+        model.ssh = links::is_ssh();
+        model
+    }
+
+    /// Build a model from a config store without reading anything else from
+    /// disk: no seen snapshot or game lists, default keys, built-in themes and
+    /// rates only
+    pub fn with_store(config_store: Box<dyn ConfigStore>) -> Self {
+        let config = config_store.load();
         let options = OptionsState::from_config(&config);
         let platform_filter = options.default_platform;
         let region = options.region;
         let sort_state = options.default_sort;
-        crate::view::styles::set_active_theme(options.theme);
+        let themes = ThemeRegistry::builtin();
+        let palette = themes.palette(&options.theme);
+        let accessibility = Accessibility::new(options.accessibility, options.animations, false);
+        let mut ui = UiState::default();
+        if !config.warnings.is_empty() {
+            ui.status = Some(StatusMessage::new(format!(
+                "Config: {}",
+                config.warnings.join("; ")
+            )));
+        }
 
         Self {
             deals: vec![],
             game_info_cache: HashMap::new(),
             price_history_cache: HashMap::new(),
-            ui: UiState::default(),
+            ui,
             filter: FilterState::default(),
+            query: DealQuery::default(),
             active_search_query: None,
             price_filter: PriceFilterState::default(),
+            new_only: false,
+            seen: SeenTracker::default(),
+            selection: HashSet::new(),
+            selection_anchor: None,
+            lists: GameLists::default(),
+            show_ignored: false,
             sort_state,
             platform_filter,
             region,
+            comparison: None,
+            display_currency: options.display_currency,
+            exchange_rates: ExchangeRates::builtin(),
+            rates_file: None,
+            rates_command: config.rates_command.clone(),
+            pending_rates_refresh: false,
+            export: ExportState::new(config.get_export_columns()),
+            clipboard_command: config.clipboard_command.clone(),
+            pending_copy: None,
+            open_command: config.open_command.clone(),
+            ssh: false,
+            pending_open: Vec::new(),
+            presets: config.presets.clone(),
+            pending_preset: None,
+            active_preset: None,
             pagination: PaginationState::default(),
             loading: LoadingState::default(),
             options,
-            api_key,
+            keymap: Keymap::default(),
+            themes,
+            palette,
+            accessibility,
+            no_color: false,
+            config_store,
             deals_page_size: config.deals_page_size,
             game_info_delay_ms: config.game_info_delay_ms,
+            debug: DebugInfo::default(),
+            cache_stats: CacheStats::default(),
             error: None,
             should_quit: false,
         }
     }
 
+    /// Switch to the accessibility profile of the options
+    pub fn apply_accessibility(&mut self) {
+        self.accessibility = Accessibility::new(
+            self.options.accessibility,
+            self.options.animations,
+            self.no_color,
+        );
+    }
+
+    /// Read the saved exchange rates, keeping the built-in ones when there
+    /// are none or they can't be read
+    fn load_rates(&mut self) {
+        let Some(path) = Config::rates_path().filter(|path| path.exists()) else {
+            return;
+        };
+        match ExchangeRates::load(&path) {
+            Ok(rates) => {
+                self.exchange_rates = rates;
+                self.rates_file = Some(path);
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "reading exchange rates failed");
+                self.ui.status = Some(StatusMessage::new(format!("{}, using built-in rates", e)));
+            }
+        }
+    }
+
+    /// Restore region, platform, sort, price range and query from a preset
+    pub fn apply_preset(&mut self, preset: Preset) {
+        self.region = preset.get_region();
+        self.platform_filter = preset.get_platform();
+        self.sort_state = preset.get_sort();
+
+        self.price_filter.clear();
+        self.price_filter.active_min = preset.price_min;
+        self.price_filter.active_max = preset.price_max;
+
+        let query = DealQuery::parse(&preset.query).unwrap_or_default();
+        self.active_search_query = query.text.clone();
+        self.query = query;
+        self.filter.active = false;
+        self.filter.error = None;
+        self.filter.applied = preset.query.trim().to_string();
+        self.filter.text = self.filter.applied.clone();
+
+        self.active_preset = Some(preset.name);
+    }
+
+    /// Capture the current view as a preset and persist it
+    pub fn save_preset(&mut self, name: String) {
+        let preset = Preset::capture(
+            name,
+            self.region,
+            self.platform_filter,
+            self.sort_state,
+            &self.price_filter,
+            &self.filter.applied,
+        );
+        let mut config = self.config_store.load();
+        config.upsert_preset(preset.clone());
+        self.save_config(&config);
+
+        self.active_preset = Some(preset.name.clone());
+        self.presets = config.presets;
+    }
+
+    /// Remove a preset by index and persist the change
+    pub fn delete_preset(&mut self, index: usize) {
+        let Some(name) = self.presets.get(index).map(|p| p.name.clone()) else {
+            return;
+        };
+        let mut config = self.config_store.load();
+        config.presets.retain(|p| p.name != name);
+        self.save_config(&config);
+
+        if self.active_preset.as_ref() == Some(&name) {
+            self.active_preset = None;
+        }
+        self.presets = config.presets;
+    }
+
+    /// Persist the options, keeping the other settings of the stored config
+    pub fn save_options(&mut self) {
+        let mut config = self.config_store.load();
+        self.options.write_to_config(&mut config);
+        self.save_config(&config);
+    }
+
+    /// Save the config, reporting a failure in the status line. The change
+    /// still applies for this session.
+    fn save_config(&mut self, config: &Config) {
+        if let Err(e) = self.config_store.save(config) {
+            self.ui.status = Some(StatusMessage::new(format!("Couldn't save settings: {}", e)));
+        }
+    }
+
+    /// Persist the export columns, keeping the other settings
+    pub fn save_export_columns(&mut self) {
+        let mut config = self.config_store.load();
+        config.export_columns = self
+            .export
+            .columns
+            .iter()
+            .map(|column| column.id().to_string())
+            .collect();
+        self.save_config(&config);
+    }
+
+    /// Open links, or copy them when there is no browser to open them in
+    pub fn open_links(&mut self, urls: Vec<String>) {
+        if urls.is_empty() {
+            return;
+        }
+        if self.ssh && self.open_command.is_none() {
+            let status = match urls.as_slice() {
+                [url] => format!("No browser over SSH, copied {}", url),
+                _ => format!("No browser over SSH, copied {} links", urls.len()),
+            };
+            self.ui.status = Some(StatusMessage::new(status));
+            self.pending_copy = Some(urls.join("\n"));
+        } else {
+            if urls.len() > 1 {
+                self.ui.status = Some(StatusMessage::new(format!("Opening {} deals", urls.len())));
+            }
+            self.pending_open.extend(urls);
+        }
+    }
+
+    /// Save the watchlist and ignored games, reporting a failure
+    pub fn save_lists(&mut self) {
+        if let Err(e) = self.lists.save() {
+            tracing::warn!(error = %e, "saving the game lists failed");
+            self.ui.status = Some(StatusMessage::new(format!(
+                "Couldn't save the watchlist: {}",
+                e
+            )));
+        }
+    }
+
+    /// Write the exported deals to a file and report how it went
+    pub fn write_export(&mut self, export: &Export) {
+        let deals = self.export_deals();
+        let status = match export.write(&deals, &self.game_info_cache, self.region.money_format()) {
+            Ok(()) => format!(
+                "Exported {} deals to {}",
+                deals.len(),
+                export.path.display()
+            ),
+            Err(e) => {
+                tracing::warn!(error = %e, path = %export.path.display(), "export failed");
+                format!("Couldn't export to {}: {}", export.path.display(), e)
+            }
+        };
+        self.ui.status = Some(StatusMessage::new(status));
+    }
+
+    /// Use the palette of the selected theme
+    pub fn apply_theme(&mut self) {
+        self.palette = self.themes.palette(&self.options.theme);
+    }
+
     pub fn error_clear(&mut self) {
         self.error = None;
     }
@@ -469,7 +1104,29 @@ impl Model {
 
         // Apply price filter
         if self.price_filter.is_active() {
-            deals.retain(|deal| self.price_filter.matches(deal.price.amount));
+            deals.retain(|deal| {
+                self.price_filter
+                    .matches(self.display_amount(deal.price.amount))
+            });
+        }
+
+        if self.new_only {
+            deals.retain(|deal| self.seen.status(deal) != DealStatus::Seen);
+        }
+
+        if !self.show_ignored {
+            deals.retain(|deal| !self.lists.is_ignored(&deal.id));
+        }
+
+        // Apply local query terms (cut, price, shop, tag, atl...)
+        if !self.query.terms.is_empty() {
+            deals.retain(|deal| {
+                self.query.matches(
+                    deal,
+                    self.game_info_cache.get(&deal.id),
+                    self.display_amount(deal.price.amount),
+                )
+            });
         }
 
         if self.is_search_mode() {
@@ -479,15 +1136,129 @@ impl Model {
         deals
     }
 
+    /// Selected deals still in the list, in list order
+    pub fn selected_deals(&self) -> Vec<&Deal> {
+        self.filtered_deals()
+            .into_iter()
+            .filter(|deal| self.selection.contains(&deal.key()))
+            .collect()
+    }
+
+    /// Take hidden ignored games out of the selection, so showing them again
+    /// doesn't bring them back selected
+    pub fn deselect_ignored(&mut self) {
+        if self.show_ignored {
+            return;
+        }
+        let hidden: HashSet<String> = self
+            .deals
+            .iter()
+            .filter(|deal| self.lists.is_ignored(&deal.id))
+            .map(Deal::key)
+            .collect();
+        self.selection.retain(|key| !hidden.contains(key));
+        if self
+            .selection_anchor
+            .as_ref()
+            .is_some_and(|key| hidden.contains(key))
+        {
+            self.selection_anchor = None;
+        }
+    }
+
+    /// Deals a batch action applies to: the selected ones, or the one under
+    /// the cursor when none is
+    pub fn batch_deals(&self) -> Vec<&Deal> {
+        let selected = self.selected_deals();
+        if selected.is_empty() {
+            self.selected_deal().into_iter().collect()
+        } else {
+            selected
+        }
+    }
+
+    /// Games of the batch deals, once each, with their titles
+    pub fn batch_games(&self) -> Vec<(String, String)> {
+        let mut games: Vec<(String, String)> = Vec::new();
+        for deal in self.batch_deals() {
+            if !games.iter().any(|(id, _)| *id == deal.id) {
+                games.push((deal.id.clone(), deal.title.clone()));
+            }
+        }
+        games
+    }
+
+    /// Deals an export writes: the selected ones, or the whole list
+    pub fn export_deals(&self) -> Vec<&Deal> {
+        let selected = self.selected_deals();
+        if selected.is_empty() {
+            self.filtered_deals()
+        } else {
+            selected
+        }
+    }
+
+    /// Columns shown in the deals table, in order
+    pub fn visible_columns(&self) -> Vec<ColumnSetting> {
+        self.options
+            .columns
+            .iter()
+            .filter(|setting| setting.visible)
+            .copied()
+            .collect()
+    }
+
+    fn shows_column(&self, column: DealsColumn) -> bool {
+        self.visible_columns()
+            .iter()
+            .any(|setting| setting.column == column)
+    }
+
+    /// Whether a shown column needs the game info of every deal
+    pub fn columns_need_game_info(&self) -> bool {
+        self.visible_columns()
+            .iter()
+            .any(|setting| setting.column.needs_game_info())
+    }
+
+    /// Whether the shown columns miss something from the game's info. Bundle
+    /// counts come with game info loaded for a list, not for one game.
+    pub fn lacks_column_game_info(&self, game_id: &str) -> bool {
+        match self.game_info_cache.get(game_id) {
+            None => true,
+            Some(info) => info.bundled.is_none() && self.shows_column(DealsColumn::Bundled),
+        }
+    }
+
+    /// Number of loaded deals that are new or cheaper since the last visit
+    pub fn new_deals_count(&self) -> usize {
+        self.deals
+            .iter()
+            .filter(|deal| self.seen.status(deal) != DealStatus::Seen)
+            .count()
+    }
+
     pub fn is_search_mode(&self) -> bool {
         self.active_search_query.is_some()
     }
 
-    pub fn is_search_sort_supported(&self) -> bool {
-        matches!(
-            self.sort_state.criteria,
-            SortCriteria::Price | SortCriteria::Cut
-        )
+    /// Short description of the active sort, including tiebreakers applied locally
+    pub fn sort_label(&self) -> String {
+        let mut label = format!(
+            "{}{}",
+            self.sort_state.criteria.name(),
+            self.sort_state.direction.arrow()
+        );
+        if self.is_search_mode() {
+            for tiebreaker in self.sort_state.criteria.tiebreakers() {
+                label.push_str(&format!(
+                    " +{}{}",
+                    tiebreaker.name(),
+                    tiebreaker.preferred_direction().arrow()
+                ));
+            }
+        }
+        label
     }
 
     pub fn selected_deal(&self) -> Option<&Deal> {
@@ -519,6 +1290,17 @@ impl Model {
         !self.loading.deals && !self.pagination.loading_more && self.pagination.has_more
     }
 
+    /// Count whether the caches already hold the selected deal
+    pub fn record_cache_lookup(&mut self) {
+        let Some(id) = self.selected_deal().map(|deal| deal.id.clone()) else {
+            return;
+        };
+        let game_info = self.game_info_cache.contains_key(&id);
+        let price_history = self.price_history_cache.contains_key(&id);
+        self.cache_stats.game_info.record(game_info);
+        self.cache_stats.price_history.record(price_history);
+    }
+
     pub fn needs_game_info_load(&self) -> Option<String> {
         if let Some(deal) = self.selected_deal() {
             if !self.game_info_cache.contains_key(&deal.id)
@@ -541,6 +1323,47 @@ impl Model {
         Some(deal.id.clone())
     }
 
+    /// Regional prices to load for the open comparison, with its regions
+    pub fn needs_regional_prices_load(&self) -> Option<(String, Vec<Region>)> {
+        let comparison = self.comparison.as_ref()?;
+        if comparison.prices.is_some()
+            || self.loading.regional_prices.as_ref() == Some(&comparison.game_id)
+        {
+            return None;
+        }
+        Some((comparison.game_id.clone(), comparison.regions.clone()))
+    }
+
+    /// Games whose info the pending export waits for, when not loading yet
+    pub fn needs_export_game_info(&self) -> Option<Vec<String>> {
+        let export = self.export.pending.as_ref()?;
+        if self.loading.export_info {
+            return None;
+        }
+        Some(export.missing_game_info(&self.export_deals(), &self.game_info_cache))
+    }
+
+    /// Amount written the way the selected region does
+    pub fn format_money(&self, money: Money) -> String {
+        money.format(self.region.money_format())
+    }
+
+    /// Amount in the display currency, `None` when there is none, it's
+    /// already in it or there is no rate for it
+    pub fn convert(&self, money: Money) -> Option<Money> {
+        let currency = self.display_currency?;
+        if money.currency() == currency {
+            return None;
+        }
+        self.exchange_rates.convert(money, currency)
+    }
+
+    /// Amount in the display currency when it can be converted, as sold
+    /// otherwise
+    pub fn display_amount(&self, money: Money) -> Money {
+        self.convert(money).unwrap_or(money)
+    }
+
     pub fn spinner_char(&self) -> char {
         const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
         SPINNER_FRAMES[self.ui.spinner_frame]
@@ -550,6 +1373,7 @@ impl Model {
     pub fn select(&mut self, index: Option<usize>) {
         self.ui.list_state.select(index);
         self.ui.table_state.select(index);
+        self.ui.chart_cursor = None;
     }
 
     /// Get platforms without "All" (for the checkbox list in options)
@@ -561,15 +1385,149 @@ impl Model {
             .collect()
     }
 
-    fn sort_search_results(&self, deals: &mut Vec<&Deal>) {
-        match self.sort_state.criteria {
-            SortCriteria::Price => deals.sort_by(|a, b| a.price.amount.total_cmp(&b.price.amount)),
-            SortCriteria::Cut => deals.sort_by_key(|deal| deal.price.discount),
-            _ => return,
+    fn sort_search_results(&self, deals: &mut [&Deal]) {
+        let criteria = self.sort_state.criteria;
+        let direction = self.sort_state.direction;
+
+        deals.sort_by(|a, b| {
+            let mut order = self.compare_by(criteria, direction, a, b);
+            for tiebreaker in criteria.tiebreakers() {
+                order = order.then_with(|| {
+                    self.compare_by(*tiebreaker, tiebreaker.preferred_direction(), a, b)
+                });
+            }
+            order.then_with(|| a.title.cmp(&b.title))
+        });
+    }
+
+    /// Compare two deals on a single criteria. Deals without a value for the
+    /// criteria (e.g. no release date yet) always sort last.
+    fn compare_by(
+        &self,
+        criteria: SortCriteria,
+        direction: SortDirection,
+        a: &Deal,
+        b: &Deal,
+    ) -> Ordering {
+        match (self.sort_key(criteria, a), self.sort_key(criteria, b)) {
+            (Some(a), Some(b)) => {
+                let order = a.total_cmp(&b);
+                match direction {
+                    SortDirection::Ascending => order,
+                    SortDirection::Descending => order.reverse(),
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
         }
+    }
 
-        if self.sort_state.direction == SortDirection::Descending {
-            deals.reverse();
+    /// Numeric key of a deal for local sorting, mirroring the API sort meaning:
+    /// ascending Hottest/Popular lists the most wanted/best ranked games first
+    fn sort_key(&self, criteria: SortCriteria, deal: &Deal) -> Option<f64> {
+        let stats = || {
+            self.game_info_cache
+                .get(&deal.id)
+                .and_then(|info| info.stats)
+        };
+        match criteria {
+            SortCriteria::Price => Some(deal.price.amount.to_major()),
+            SortCriteria::Cut => Some(deal.price.discount as f64),
+            SortCriteria::Hottest => stats()
+                .and_then(|s| s.waitlisted)
+                .map(|count| -(count as f64)),
+            SortCriteria::Popular => stats().and_then(|s| s.rank).map(|rank| rank as f64),
+            SortCriteria::ReleaseDate => self
+                .game_info_cache
+                .get(&deal.id)
+                .and_then(|info| info.release_date.as_deref())
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+                .map(|date| date.num_days_from_ce() as f64),
+            SortCriteria::Expiring => deal
+                .expiry
+                .as_deref()
+                .and_then(|expiry| DateTime::parse_from_rfc3339(expiry).ok())
+                .map(|expiry| expiry.timestamp() as f64),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn comparison() -> RegionComparison {
+        let (game_id, prices) = fixtures::regional_prices().into_iter().next().unwrap();
+        let regions = prices.iter().map(|price| price.region).collect();
+        let mut comparison =
+            RegionComparison::new(game_id, "Hollow Knight".to_string(), regions, Currency::EUR);
+        comparison.prices = Some(Ok(prices));
+        comparison
+    }
+
+    fn regions(rows: &[ComparisonRow]) -> Vec<String> {
+        rows.iter()
+            .map(|row| row.region.code().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn regions_are_compared_cheapest_first() {
+        let comparison = comparison();
+        let rows = comparison.rows(&ExchangeRates::builtin());
+        assert_eq!(regions(&rows), ["BR", "TR", "PL", "US", "FR", "GB", "AR"]);
+        assert!(rows[6].offer.is_none());
+
+        let spread = RegionComparison::spread(&rows).unwrap();
+        assert_eq!((spread.cheapest, spread.dearest), (Region::BR, Region::GB));
+        assert_eq!(spread.low, Money::new(188, Currency::EUR));
+        assert_eq!(spread.amount(), Money::new(193, Currency::EUR));
+        assert_eq!(spread.percent_over_low(spread.low), 0.0);
+    }
+
+    #[test]
+    fn offers_without_a_rate_come_after_the_converted_ones() {
+        let comparison = comparison();
+        let mut rates = ExchangeRates::new(Currency::EUR);
+        rates.insert(Currency::new("BRL").unwrap(), 6.10);
+        let rows = comparison.rows(&rates);
+        assert_eq!(&regions(&rows)[..2], ["BR", "FR"]);
+        assert!(rows[2..6]
+            .iter()
+            .all(|row| row.offer.is_some() && row.converted.is_none()));
+        assert_eq!(regions(&rows)[6], "AR");
+
+        // Nothing loaded yet, or nothing converted
+        assert!(RegionComparison::spread(&rows[2..]).is_none());
+        let mut loading = comparison;
+        loading.prices = None;
+        assert!(loading.rows(&rates).is_empty());
+    }
+
+    #[test]
+    fn price_bounds_are_read_in_the_currency_of_the_price() {
+        let mut filter = PriceFilterState {
+            min_input: "4".to_string(),
+            max_input: "12.5".to_string(),
+            ..PriceFilterState::default()
+        };
+        filter.apply();
+        assert_eq!(filter.label(), "4-12.5");
+        assert!(filter.matches(Money::new(1250, Currency::EUR)));
+        assert!(!filter.matches(Money::new(1251, Currency::EUR)));
+        assert!(!filter.matches(Money::new(399, Currency::USD)));
+        let yen = Currency::new("JPY").unwrap();
+        // ¥12.5 rounds to ¥13
+        assert!(filter.matches(Money::new(13, yen)));
+        assert!(!filter.matches(Money::new(14, yen)));
+
+        // Input that isn't a number leaves the bound off
+        filter.min_input = "4..".to_string();
+        filter.max_input.clear();
+        filter.apply();
+        assert!(!filter.is_active());
+        assert_eq!(filter.label(), "—");
+    }
+}
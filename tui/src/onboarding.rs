@@ -1,11 +1,11 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::io::Stdout;
@@ -14,12 +14,17 @@ use tachyonfx::fx::EvolveSymbolSet;
 use tachyonfx::pattern::RadialPattern;
 use tachyonfx::{fx, Effect, EffectTimer, Interpolation, Motion};
 
-use crate::config::Config;
+use crate::config::ConfigStore;
+use crate::secrets::{self, KeyStorage, SecretBackend};
+use crate::tasks::DataSource;
+use crate::view::accessibility::{self, Accessibility};
 use crate::view::styles::{
     accent, bg_dark, error_red, green, primary, primary_light, shortcut_key, text_primary,
     text_secondary, ASCII_LOGO,
 };
 
+const STEP_COUNT: usize = 5;
+
 const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
 #[derive(Debug, Clone, PartialEq)]
@@ -28,14 +33,30 @@ pub enum OnboardingStep {
     Instructions,
     ApiKeyEntry,
     Validating,
+    ChooseStorage,
+    /// Passphrase or command for the chosen storage
+    SecretEntry,
+    Saving,
     Success,
-    Failed { error: String },
+    Failed {
+        error: String,
+    },
+    StorageFailed {
+        error: String,
+    },
 }
 
 pub struct OnboardingState {
     pub step: OnboardingStep,
     pub api_key_input: String,
     pub key_hidden: bool,
+    /// Index in `KeyStorage::ALL`
+    pub storage_selected: usize,
+    pub secret_input: String,
+    /// Passphrase typed first, while it's typed again to confirm it
+    pub first_passphrase: Option<String>,
+    /// The confirmation didn't match and the passphrase is typed anew
+    pub passphrase_mismatch: bool,
     pub spinner_frame: usize,
     pub should_quit: bool,
 }
@@ -46,6 +67,10 @@ impl OnboardingState {
             step: OnboardingStep::Welcome,
             api_key_input: String::new(),
             key_hidden: true,
+            storage_selected: 0,
+            secret_input: String::new(),
+            first_passphrase: None,
+            passphrase_mismatch: false,
             spinner_frame: 0,
             should_quit: false,
         }
@@ -59,14 +84,51 @@ impl OnboardingState {
         SPINNER_FRAMES[self.spinner_frame]
     }
 
-    pub fn progress_dots(&self) -> [bool; 4] {
+    /// Position in the flow, from 1 to `STEP_COUNT`
+    pub fn step_number(&self) -> usize {
         match self.step {
-            OnboardingStep::Welcome => [true, false, false, false],
-            OnboardingStep::Instructions => [true, true, false, false],
-            OnboardingStep::ApiKeyEntry => [true, true, true, false],
-            OnboardingStep::Validating
-            | OnboardingStep::Success
-            | OnboardingStep::Failed { .. } => [true, true, true, true],
+            OnboardingStep::Welcome => 1,
+            OnboardingStep::Instructions => 2,
+            OnboardingStep::ApiKeyEntry
+            | OnboardingStep::Validating
+            | OnboardingStep::Failed { .. } => 3,
+            OnboardingStep::ChooseStorage
+            | OnboardingStep::SecretEntry
+            | OnboardingStep::Saving
+            | OnboardingStep::StorageFailed { .. } => 4,
+            OnboardingStep::Success => 5,
+        }
+    }
+
+    pub fn selected_storage(&self) -> KeyStorage {
+        KeyStorage::ALL[self.storage_selected]
+    }
+
+    /// Enter on the secret entry. A passphrase is typed twice before saving,
+    /// and typed anew when the two don't match.
+    pub fn submit_secret(&mut self) {
+        if self.selected_storage() == KeyStorage::Command {
+            self.step = OnboardingStep::Saving;
+            return;
+        }
+        match self.first_passphrase.take() {
+            None => {
+                self.first_passphrase = Some(std::mem::take(&mut self.secret_input));
+                self.passphrase_mismatch = false;
+            }
+            Some(first) if first == self.secret_input => self.step = OnboardingStep::Saving,
+            Some(_) => {
+                self.secret_input.clear();
+                self.passphrase_mismatch = true;
+            }
+        }
+    }
+
+    /// The passphrase is masked, the command is not
+    pub fn displayed_secret(&self) -> String {
+        match self.selected_storage() {
+            KeyStorage::Command => self.secret_input.clone(),
+            KeyStorage::Backend(_) => "*".repeat(self.secret_input.chars().count()),
         }
     }
 
@@ -92,9 +154,21 @@ pub fn render(frame: &mut Frame, state: &OnboardingState) {
         OnboardingStep::Welcome => render_welcome(frame, area),
         OnboardingStep::Instructions => render_instructions(frame, area),
         OnboardingStep::ApiKeyEntry => render_api_key_entry(frame, state, area),
-        OnboardingStep::Validating => render_validating(frame, state, area),
+        OnboardingStep::Validating => render_spinner(
+            frame,
+            state,
+            area,
+            "Validating...",
+            "Connecting to IsThereAnyDeal...",
+        ),
+        OnboardingStep::ChooseStorage => render_storage_choice(frame, state, area),
+        OnboardingStep::SecretEntry => render_secret_entry(frame, state, area),
+        OnboardingStep::Saving => {
+            render_spinner(frame, state, area, "Saving...", "Storing your API key...")
+        }
         OnboardingStep::Success => render_success(frame, area),
         OnboardingStep::Failed { error } => render_failed(frame, area, error),
+        OnboardingStep::StorageFailed { error } => render_storage_failed(frame, area, error),
     }
 
     // Render progress dots at bottom
@@ -177,7 +251,15 @@ fn render_welcome(frame: &mut Frame, area: Rect) {
     frame.render_widget(info_text, info_area);
 
     // Action hint at bottom
-    render_action_hints(frame, area, &[("Enter", "Continue"), ("Esc", "Quit")]);
+    render_action_hints(
+        frame,
+        area,
+        &[
+            ("Enter", "Continue"),
+            ("d", "Try the demo"),
+            ("Esc", "Quit"),
+        ],
+    );
 }
 
 fn render_instructions(frame: &mut Frame, area: Rect) {
@@ -269,6 +351,7 @@ fn render_instructions(frame: &mut Frame, area: Rect) {
         area,
         &[
             ("o", "Open website"),
+            ("d", "Try the demo"),
             ("Enter", "Continue"),
             ("Esc", "Back"),
         ],
@@ -361,7 +444,7 @@ fn render_api_key_entry(frame: &mut Frame, state: &OnboardingState, area: Rect)
     );
 }
 
-fn render_validating(frame: &mut Frame, state: &OnboardingState, area: Rect) {
+fn render_spinner(frame: &mut Frame, state: &OnboardingState, area: Rect, title: &str, text: &str) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -377,7 +460,7 @@ fn render_validating(frame: &mut Frame, state: &OnboardingState, area: Rect) {
     let title = Paragraph::new(Line::from(vec![
         Span::styled("┐", Style::default().fg(accent())),
         Span::styled(
-            "Validating...",
+            title,
             Style::default()
                 .fg(primary_light())
                 .add_modifier(Modifier::BOLD),
@@ -393,13 +476,176 @@ fn render_validating(frame: &mut Frame, state: &OnboardingState, area: Rect) {
             format!("{} ", state.spinner_char()),
             Style::default().fg(primary()),
         ),
+        Span::styled(text, Style::default().fg(text_secondary())),
+    ]))
+    .alignment(Alignment::Center);
+    frame.render_widget(spinner, chunks[3]);
+}
+
+fn render_storage_choice(frame: &mut Frame, state: &OnboardingState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Top padding
+            Constraint::Length(1),  // Title
+            Constraint::Length(2),  // Gap
+            Constraint::Length(12), // Choices box
+            Constraint::Min(0),     // Remaining
+        ])
+        .split(area);
+
+    let title = Paragraph::new(Line::from(vec![
+        Span::styled("┐", Style::default().fg(accent())),
         Span::styled(
-            "Connecting to IsThereAnyDeal...",
+            "Where Should Dealve Keep It?",
+            Style::default()
+                .fg(primary_light())
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("┌", Style::default().fg(accent())),
+    ]))
+    .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[1]);
+
+    let box_width = 64;
+    let box_x = area.width.saturating_sub(box_width) / 2;
+    let choices_area = Rect::new(box_x, chunks[3].y, box_width, 12);
+
+    let choices_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent()));
+
+    let mut lines = vec![Line::from("")];
+    for (i, storage) in KeyStorage::ALL.iter().enumerate() {
+        let selected = i == state.storage_selected;
+        let (marker, name_style) = if selected {
+            (
+                "▶ ",
+                Style::default()
+                    .fg(primary_light())
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            ("  ", Style::default().fg(text_primary()))
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}", marker), Style::default().fg(primary())),
+            Span::styled(storage.name(), name_style),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!("    {}", storage.description()),
             Style::default().fg(text_secondary()),
+        )));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        "  The key is never written to the config file.",
+        Style::default().fg(text_secondary()),
+    )));
+
+    frame.render_widget(Paragraph::new(lines).block(choices_block), choices_area);
+
+    render_action_hints(
+        frame,
+        area,
+        &[("↑↓", "Select"), ("Enter", "Choose"), ("Esc", "Back")],
+    );
+}
+
+fn render_secret_entry(frame: &mut Frame, state: &OnboardingState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Top padding
+            Constraint::Length(1),  // Title
+            Constraint::Length(2),  // Gap
+            Constraint::Length(12), // Input box
+            Constraint::Min(0),     // Remaining
+        ])
+        .split(area);
+
+    let (title, prompt, tip) = match state.selected_storage() {
+        KeyStorage::Command => (
+            "Enter The Command",
+            "Command that prints your API key:",
+            "It runs each time Dealve starts",
+        ),
+        KeyStorage::Backend(_) if state.first_passphrase.is_some() => (
+            "Confirm The Passphrase",
+            "Type the passphrase again:",
+            "You'll be asked for it each time Dealve starts",
+        ),
+        KeyStorage::Backend(_) => (
+            "Choose A Passphrase",
+            "Passphrase to encrypt your API key:",
+            "You'll be asked for it each time Dealve starts",
+        ),
+    };
+    let tip = if state.passphrase_mismatch {
+        Span::styled(
+            "The passphrases didn't match, try again",
+            Style::default().fg(error_red()),
+        )
+    } else {
+        Span::styled(tip, Style::default().fg(text_secondary()))
+    };
+
+    let title = Paragraph::new(Line::from(vec![
+        Span::styled("┐", Style::default().fg(accent())),
+        Span::styled(
+            title,
+            Style::default()
+                .fg(primary_light())
+                .add_modifier(Modifier::BOLD),
         ),
+        Span::styled("┌", Style::default().fg(accent())),
     ]))
     .alignment(Alignment::Center);
-    frame.render_widget(spinner, chunks[3]);
+    frame.render_widget(title, chunks[1]);
+
+    let box_width = 60;
+    let box_x = area.width.saturating_sub(box_width) / 2;
+    let input_area = Rect::new(box_x, chunks[3].y, box_width, 10);
+
+    let input_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent()));
+
+    // Keep the end of long input in view
+    let displayed = state.displayed_secret();
+    let skip = displayed.chars().count().saturating_sub(43);
+    let displayed: String = displayed.chars().skip(skip).collect();
+
+    let input_content = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(prompt, Style::default().fg(text_primary()))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("┌", Style::default().fg(text_secondary())),
+            Span::styled("─".repeat(46), Style::default().fg(text_secondary())),
+            Span::styled("┐", Style::default().fg(text_secondary())),
+        ]),
+        Line::from(vec![
+            Span::styled("│ ", Style::default().fg(text_secondary())),
+            Span::styled(
+                format!("{:<44}", format!("{}▋", displayed)),
+                Style::default().fg(primary_light()),
+            ),
+            Span::styled(" │", Style::default().fg(text_secondary())),
+        ]),
+        Line::from(vec![
+            Span::styled("└", Style::default().fg(text_secondary())),
+            Span::styled("─".repeat(46), Style::default().fg(text_secondary())),
+            Span::styled("┘", Style::default().fg(text_secondary())),
+        ]),
+        Line::from(""),
+        Line::from(tip),
+    ])
+    .alignment(Alignment::Center)
+    .block(input_block);
+    frame.render_widget(input_content, input_area);
+
+    render_action_hints(frame, area, &[("Enter", "Save"), ("Esc", "Back")]);
 }
 
 fn render_success(frame: &mut Frame, area: Rect) {
@@ -455,6 +701,41 @@ fn render_success(frame: &mut Frame, area: Rect) {
 }
 
 fn render_failed(frame: &mut Frame, area: Rect, error: &str) {
+    render_error(
+        frame,
+        area,
+        "Validation Failed",
+        "✗ Invalid API Key",
+        error,
+        "Please check your key and try again.",
+    );
+    render_action_hints(
+        frame,
+        area,
+        &[("Enter", "Try again"), ("o", "Open ITAD"), ("Esc", "Quit")],
+    );
+}
+
+fn render_storage_failed(frame: &mut Frame, area: Rect, error: &str) {
+    render_error(
+        frame,
+        area,
+        "Couldn't Save Key",
+        "✗ Key Not Saved",
+        error,
+        "Try again or choose another way to keep it.",
+    );
+    render_action_hints(frame, area, &[("Enter", "Try again"), ("Esc", "Quit")]);
+}
+
+fn render_error(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    heading: &str,
+    error: &str,
+    advice: &str,
+) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -470,7 +751,7 @@ fn render_failed(frame: &mut Frame, area: Rect, error: &str) {
     let title = Paragraph::new(Line::from(vec![
         Span::styled("┐", Style::default().fg(error_red())),
         Span::styled(
-            "Validation Failed",
+            title,
             Style::default()
                 .fg(error_red())
                 .add_modifier(Modifier::BOLD),
@@ -492,7 +773,7 @@ fn render_failed(frame: &mut Frame, area: Rect, error: &str) {
     let error_text = Paragraph::new(vec![
         Line::from(""),
         Line::from(Span::styled(
-            "✗ Invalid API Key",
+            heading,
             Style::default()
                 .fg(error_red())
                 .add_modifier(Modifier::BOLD),
@@ -500,60 +781,36 @@ fn render_failed(frame: &mut Frame, area: Rect, error: &str) {
         Line::from(""),
         Line::from(Span::styled(error, Style::default().fg(text_secondary()))),
         Line::from(""),
-        Line::from(Span::styled(
-            "Please check your key and try again.",
-            Style::default().fg(text_primary()),
-        )),
+        Line::from(Span::styled(advice, Style::default().fg(text_primary()))),
     ])
     .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true })
     .block(error_block);
     frame.render_widget(error_text, error_area);
-
-    render_action_hints(
-        frame,
-        area,
-        &[("Enter", "Try again"), ("o", "Open ITAD"), ("Esc", "Quit")],
-    );
 }
 
 fn render_progress_dots(frame: &mut Frame, state: &OnboardingState, area: Rect) {
-    let dots = state.progress_dots();
-    let dot_line = Line::from(vec![
+    let current = state.step_number();
+    let mut spans = vec![
         Span::styled("[Step ", Style::default().fg(text_secondary())),
+        Span::styled(current.to_string(), Style::default().fg(primary_light())),
         Span::styled(
-            match state.step {
-                OnboardingStep::Welcome => "1",
-                OnboardingStep::Instructions => "2",
-                OnboardingStep::ApiKeyEntry => "3",
-                _ => "4",
-            },
-            Style::default().fg(primary_light()),
-        ),
-        Span::styled(" of 4] ", Style::default().fg(text_secondary())),
-        Span::styled(
-            if dots[0] { "●" } else { "○" },
-            Style::default().fg(if dots[0] { primary() } else { text_secondary() }),
-        ),
-        Span::styled(" ", Style::default()),
-        Span::styled(
-            if dots[1] { "●" } else { "○" },
-            Style::default().fg(if dots[1] { primary() } else { text_secondary() }),
-        ),
-        Span::styled(" ", Style::default()),
-        Span::styled(
-            if dots[2] { "●" } else { "○" },
-            Style::default().fg(if dots[2] { primary() } else { text_secondary() }),
-        ),
-        Span::styled(" ", Style::default()),
-        Span::styled(
-            if dots[3] { "●" } else { "○" },
-            Style::default().fg(if dots[3] { primary() } else { text_secondary() }),
+            format!(" of {}]", STEP_COUNT),
+            Style::default().fg(text_secondary()),
         ),
-    ]);
+    ];
+    for step in 1..=STEP_COUNT {
+        let done = step <= current;
+        spans.push(Span::styled(" ", Style::default()));
+        spans.push(Span::styled(
+            if done { "●" } else { "○" },
+            Style::default().fg(if done { primary() } else { text_secondary() }),
+        ));
+    }
 
     let y = area.height.saturating_sub(4);
     let dots_area = Rect::new(0, y, area.width, 1);
-    let dots_widget = Paragraph::new(dot_line).alignment(Alignment::Center);
+    let dots_widget = Paragraph::new(Line::from(spans)).alignment(Alignment::Center);
     frame.render_widget(dots_widget, dots_area);
 }
 
@@ -583,28 +840,29 @@ fn render_action_hints(frame: &mut Frame, area: Rect, hints: &[(&str, &str)]) {
 }
 
 /// Run the onboarding flow
-/// Returns Some(api_key) on success, None if user quit
+/// Returns the API key on success or the demo if picked, None if user quit
 pub async fn run_onboarding(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-) -> Result<Option<String>> {
+    config_store: &mut dyn ConfigStore,
+    profile: Accessibility,
+) -> Result<Option<DataSource>> {
     let mut state = OnboardingState::new();
-    let mut effects: Vec<(Effect, Rect)> = Vec::new();
+    // Effects cover the whole screen, resolved on each frame to follow resizes
+    let mut effects: Vec<Effect> = Vec::new();
     let mut last_frame_time = Instant::now();
 
     // Initial evolve_into animation
-    let term_size = terminal.size()?;
-    let full_screen = Rect::new(0, 0, term_size.width, term_size.height);
-
     let style = ratatui::style::Style::default()
         .fg(bg_dark())
         .bg(Color::Black);
 
     let timer = EffectTimer::from_ms(1000, Interpolation::CubicOut);
-    effects.push((
-        fx::evolve_into((EvolveSymbolSet::Shaded, style), timer)
-            .with_pattern(RadialPattern::center().with_transition_width(12.0)),
-        full_screen,
-    ));
+    if profile.animations {
+        effects.push(
+            fx::evolve_into((EvolveSymbolSet::Shaded, style), timer)
+                .with_pattern(RadialPattern::center().with_transition_width(12.0)),
+        );
+    }
 
     loop {
         let elapsed = last_frame_time.elapsed();
@@ -614,13 +872,16 @@ pub async fn run_onboarding(
             render(frame, &state);
 
             // Apply effects
-            for (effect, area) in effects.iter_mut() {
-                effect.process(elapsed.into(), frame.buffer_mut(), *area);
+            let area = frame.area();
+            for effect in effects.iter_mut() {
+                effect.process(elapsed.into(), frame.buffer_mut(), area);
             }
+
+            accessibility::apply(frame.buffer_mut(), profile);
         })?;
 
         // Remove completed effects
-        effects.retain(|(effect, _)| !effect.done());
+        effects.retain(|effect| !effect.done());
 
         if state.should_quit {
             return Ok(None);
@@ -635,27 +896,8 @@ pub async fn run_onboarding(
                 // Perform validation
                 match dealve_api::ItadClient::validate_api_key(&state.api_key_input).await {
                     Ok(()) => {
-                        // Save to config
-                        let mut config = Config::load();
-                        if let Err(e) = config.set_api_key(state.api_key_input.clone()) {
-                            state.step = OnboardingStep::Failed {
-                                error: format!("Failed to save config: {}", e),
-                            };
-                        } else {
-                            state.step = OnboardingStep::Success;
-                            // Add success animation
-                            let term_size = terminal.size()?;
-                            effects.push((
-                                fx::sweep_in(
-                                    Motion::UpToDown,
-                                    10,
-                                    2,
-                                    bg_dark(),
-                                    (400, Interpolation::QuadOut),
-                                ),
-                                Rect::new(0, 0, term_size.width, term_size.height),
-                            ));
-                        }
+                        state.step = OnboardingStep::ChooseStorage;
+                        add_transition_effect(&mut effects, profile);
                     }
                     Err(e) => {
                         state.step = OnboardingStep::Failed {
@@ -666,8 +908,40 @@ pub async fn run_onboarding(
             }
         }
 
+        // Handle saving step
+        if state.step == OnboardingStep::Saving {
+            state.tick_spinner();
+
+            if effects.is_empty() {
+                match save_key(&state, config_store).await {
+                    Ok(()) => {
+                        state.step = OnboardingStep::Success;
+                        // Add success animation
+                        if profile.animations {
+                            effects.push(fx::sweep_in(
+                                Motion::UpToDown,
+                                10,
+                                2,
+                                bg_dark(),
+                                (400, Interpolation::QuadOut),
+                            ));
+                        }
+                    }
+                    Err(e) => {
+                        state.step = OnboardingStep::StorageFailed {
+                            error: format!("{:#}", e),
+                        };
+                    }
+                }
+            }
+        }
+
         // Poll for events
-        let poll_duration = if !effects.is_empty() || state.step == OnboardingStep::Validating {
+        let busy = matches!(
+            state.step,
+            OnboardingStep::Validating | OnboardingStep::Saving
+        );
+        let poll_duration = if !effects.is_empty() || busy {
             std::time::Duration::from_millis(16)
         } else {
             std::time::Duration::from_millis(50)
@@ -680,8 +954,9 @@ pub async fn run_onboarding(
                         OnboardingStep::Welcome => match key.code {
                             KeyCode::Enter => {
                                 state.step = OnboardingStep::Instructions;
-                                add_transition_effect(&mut effects, terminal)?;
+                                add_transition_effect(&mut effects, profile);
                             }
+                            KeyCode::Char('d') => return Ok(Some(DataSource::Demo)),
                             KeyCode::Esc => {
                                 state.should_quit = true;
                             }
@@ -690,22 +965,21 @@ pub async fn run_onboarding(
                         OnboardingStep::Instructions => match key.code {
                             KeyCode::Enter => {
                                 state.step = OnboardingStep::ApiKeyEntry;
-                                add_transition_effect(&mut effects, terminal)?;
+                                add_transition_effect(&mut effects, profile);
                             }
                             KeyCode::Char('o') => {
                                 let _ = webbrowser::open("https://isthereanydeal.com/apps/");
                             }
+                            KeyCode::Char('d') => return Ok(Some(DataSource::Demo)),
                             KeyCode::Esc => {
                                 state.step = OnboardingStep::Welcome;
-                                add_transition_effect(&mut effects, terminal)?;
+                                add_transition_effect(&mut effects, profile);
                             }
                             _ => {}
                         },
                         OnboardingStep::ApiKeyEntry => match key.code {
-                            KeyCode::Enter => {
-                                if !state.api_key_input.is_empty() {
-                                    state.step = OnboardingStep::Validating;
-                                }
+                            KeyCode::Enter if !state.api_key_input.is_empty() => {
+                                state.step = OnboardingStep::Validating;
                             }
                             KeyCode::Char('t') => {
                                 state.key_hidden = !state.key_hidden;
@@ -713,30 +987,87 @@ pub async fn run_onboarding(
                             KeyCode::Backspace => {
                                 state.api_key_input.pop();
                             }
-                            KeyCode::Char(c) => {
-                                // Allow alphanumeric and dashes (UUID format)
-                                if c.is_alphanumeric() || c == '-' {
-                                    state.api_key_input.push(c);
-                                }
+                            // Allow alphanumeric and dashes (UUID format)
+                            KeyCode::Char(c) if c.is_alphanumeric() || c == '-' => {
+                                state.api_key_input.push(c);
                             }
                             KeyCode::Esc => {
                                 state.step = OnboardingStep::Instructions;
-                                add_transition_effect(&mut effects, terminal)?;
+                                add_transition_effect(&mut effects, profile);
                             }
                             _ => {}
                         },
-                        OnboardingStep::Validating => {
-                            // No input during validation
+                        OnboardingStep::ChooseStorage => match key.code {
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                state.storage_selected = state.storage_selected.saturating_sub(1);
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                state.storage_selected =
+                                    (state.storage_selected + 1).min(KeyStorage::ALL.len() - 1);
+                            }
+                            KeyCode::Enter => {
+                                state.secret_input.clear();
+                                state.first_passphrase = None;
+                                state.passphrase_mismatch = false;
+                                state.step = match state.selected_storage() {
+                                    KeyStorage::Backend(SecretBackend::Keyring) => {
+                                        OnboardingStep::Saving
+                                    }
+                                    _ => OnboardingStep::SecretEntry,
+                                };
+                                add_transition_effect(&mut effects, profile);
+                            }
+                            KeyCode::Esc => {
+                                state.step = OnboardingStep::ApiKeyEntry;
+                                add_transition_effect(&mut effects, profile);
+                            }
+                            _ => {}
+                        },
+                        OnboardingStep::SecretEntry => match key.code {
+                            KeyCode::Enter if !state.secret_input.trim().is_empty() => {
+                                state.submit_secret();
+                            }
+                            KeyCode::Backspace => {
+                                state.secret_input.pop();
+                            }
+                            KeyCode::Char(c) if !c.is_control() => {
+                                state.secret_input.push(c);
+                            }
+                            // Back to the first passphrase, then to the choices
+                            KeyCode::Esc if state.first_passphrase.is_some() => {
+                                state.first_passphrase = None;
+                                state.secret_input.clear();
+                            }
+                            KeyCode::Esc => {
+                                state.step = OnboardingStep::ChooseStorage;
+                                add_transition_effect(&mut effects, profile);
+                            }
+                            _ => {}
+                        },
+                        OnboardingStep::Validating | OnboardingStep::Saving => {
+                            // No input while waiting
                         }
                         OnboardingStep::Success => {
                             if key.code == KeyCode::Enter {
-                                return Ok(Some(state.api_key_input));
+                                return Ok(Some(DataSource::Api {
+                                    api_key: state.api_key_input,
+                                }));
                             }
                         }
+                        OnboardingStep::StorageFailed { .. } => match key.code {
+                            KeyCode::Enter => {
+                                state.step = OnboardingStep::ChooseStorage;
+                                add_transition_effect(&mut effects, profile);
+                            }
+                            KeyCode::Esc => {
+                                state.should_quit = true;
+                            }
+                            _ => {}
+                        },
                         OnboardingStep::Failed { .. } => match key.code {
                             KeyCode::Enter => {
                                 state.step = OnboardingStep::ApiKeyEntry;
-                                add_transition_effect(&mut effects, terminal)?;
+                                add_transition_effect(&mut effects, profile);
                             }
                             KeyCode::Char('o') => {
                                 let _ = webbrowser::open("https://isthereanydeal.com/apps/");
@@ -753,20 +1084,96 @@ pub async fn run_onboarding(
     }
 }
 
-fn add_transition_effect(
-    effects: &mut Vec<(Effect, Rect)>,
-    terminal: &Terminal<CrosstermBackend<Stdout>>,
-) -> Result<()> {
-    let term_size = terminal.size()?;
-    effects.push((
-        fx::sweep_in(
-            Motion::LeftToRight,
-            8,
-            2,
-            bg_dark(),
-            (250, Interpolation::QuadOut),
-        ),
-        Rect::new(0, 0, term_size.width, term_size.height),
+/// Store the key where the user chose and record the choice in the config
+async fn save_key(state: &OnboardingState, config_store: &mut dyn ConfigStore) -> Result<()> {
+    let key = &state.api_key_input;
+    let secret = &state.secret_input;
+    let mut config = config_store.load();
+
+    match state.selected_storage() {
+        KeyStorage::Backend(backend) => {
+            // Passphrases are used as typed, like when they're asked at startup
+            secrets::store_api_key(backend, key, secret).await?;
+            config.secret_backend = Some(backend);
+            config.key_command = None;
+        }
+        KeyStorage::Command => {
+            let command = secret.trim();
+            secrets::check_key_command(command, key).await?;
+            config.key_command = Some(command.to_string());
+        }
+    }
+
+    // Drop a plain text key left by an older version
+    config.api_key = None;
+    config_store.save(&config).context("failed to save config")
+}
+
+fn add_transition_effect(effects: &mut Vec<Effect>, profile: Accessibility) {
+    if !profile.animations {
+        return;
+    }
+    effects.push(fx::sweep_in(
+        Motion::LeftToRight,
+        8,
+        2,
+        bg_dark(),
+        (250, Interpolation::QuadOut),
     ));
-    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret_entry(storage: KeyStorage) -> OnboardingState {
+        let mut state = OnboardingState::new();
+        state.storage_selected = KeyStorage::ALL.iter().position(|s| *s == storage).unwrap();
+        state.step = OnboardingStep::SecretEntry;
+        state
+    }
+
+    #[test]
+    fn passphrases_are_confirmed_before_saving() {
+        let mut state = secret_entry(KeyStorage::Backend(SecretBackend::EncryptedFile));
+        state.secret_input = "correct horse".to_string();
+        state.submit_secret();
+        assert_eq!(state.step, OnboardingStep::SecretEntry);
+        assert_eq!(state.first_passphrase.as_deref(), Some("correct horse"));
+        assert!(state.secret_input.is_empty());
+
+        state.secret_input = "correct horse".to_string();
+        state.submit_secret();
+        assert_eq!(state.step, OnboardingStep::Saving);
+        assert_eq!(state.secret_input, "correct horse");
+    }
+
+    #[test]
+    fn mismatched_passphrases_start_over() {
+        let mut state = secret_entry(KeyStorage::Backend(SecretBackend::EncryptedFile));
+        state.secret_input = "correct horse".to_string();
+        state.submit_secret();
+        state.secret_input = "correct hose".to_string();
+        state.submit_secret();
+        assert_eq!(state.step, OnboardingStep::SecretEntry);
+        assert!(state.passphrase_mismatch);
+        assert!(state.first_passphrase.is_none());
+        assert!(state.secret_input.is_empty());
+
+        state.secret_input = "battery staple".to_string();
+        state.submit_secret();
+        assert!(!state.passphrase_mismatch);
+        state.secret_input = "battery staple".to_string();
+        state.submit_secret();
+        assert_eq!(state.step, OnboardingStep::Saving);
+    }
+
+    #[test]
+    fn commands_are_saved_at_once() {
+        let mut state = secret_entry(KeyStorage::Command);
+        state.secret_input = "pass show itad".to_string();
+        state.submit_secret();
+        assert_eq!(state.step, OnboardingStep::Saving);
+        assert!(state.first_passphrase.is_none());
+    }
 }
@@ -0,0 +1,828 @@
+//! Scripted end-to-end tests. A `Session` drives `update` through a list of
+//! steps (keys, clicks, time passing and load results) with a scripted loader
+//! and a manual clock, so every run is the same. Tests then check the model
+//! and the rendered screen.
+//!
+//! To reproduce a bug report, record the session and replay it here, which
+//! prints the screen after every step:
+//!
+//! ```sh
+//! DEALVE_RECORD=session.jsonl dealve
+//! DEALVE_REPLAY=$PWD/session.jsonl cargo test -p dealve-tui replay_file -- --nocapture
+//! ```
+//!
+//! Sessions in `tui/sessions/` are replayed by `recorded_sessions` and their
+//! last screen compared with a snapshot (see `view/tests.rs`).
+
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use dealve_core::models::{Deal, GameInfo, Platform, PriceHistoryPoint, Region, RegionalPrice};
+use dealve_core::money::Currency;
+use dealve_core::{DealveError, Result};
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+use tokio::sync::oneshot::Sender;
+
+use crate::clipboard::{Clipboard, CopyTask};
+use crate::config::{Config, MemoryConfigStore};
+use crate::events;
+use crate::fixtures;
+use crate::keymap::KeyChord;
+use crate::links::{OpenTask, Opener};
+use crate::message::Message;
+use crate::model::Model;
+use crate::session::Step;
+use crate::tasks::{
+    self, Clock, DataSource, DealsLoadTask, GameInfoBatchTask, GameInfoTask, Loader,
+    PriceHistoryTask, RegionalPricesTask, SystemClock, Task, TaskManager,
+};
+use crate::view::{self, tests::assert_snapshot};
+
+/// Clock that only moves on `wait` steps
+struct ManualClock(Rc<Cell<Instant>>);
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+/// Clipboard keeping what was copied, without a clipboard command
+struct MemoryClipboard(Rc<RefCell<Vec<String>>>);
+
+impl Clipboard for MemoryClipboard {
+    fn copy(&self, text: &str, _: Option<&str>) -> Option<CopyTask> {
+        self.0.borrow_mut().push(text.to_string());
+        None
+    }
+}
+
+/// Browser keeping the links it was asked to open
+struct MemoryOpener(Rc<RefCell<Vec<String>>>);
+
+impl Opener for MemoryOpener {
+    fn open(&self, url: &str, _: Option<&str>) -> OpenTask {
+        self.0.borrow_mut().push(url.to_string());
+        let (sender, task) = Task::channel();
+        let _ = sender.send((url.to_string(), Ok(())));
+        task
+    }
+}
+
+/// Browser that can't be started, as on a headless server
+struct BrokenOpener;
+
+impl Opener for BrokenOpener {
+    fn open(&self, url: &str, _: Option<&str>) -> OpenTask {
+        let (sender, task) = Task::channel();
+        let _ = sender.send((url.to_string(), Err("no browser found".to_string())));
+        task
+    }
+}
+
+/// Requests waiting for the script to provide their result
+#[derive(Default)]
+struct Pending {
+    deals: Vec<Sender<Result<Vec<Deal>>>>,
+    game_info: Vec<Sender<(String, Result<GameInfo>)>>,
+    game_info_batch: Vec<Sender<Vec<GameInfo>>>,
+    price_history: Vec<Sender<(String, Result<Vec<PriceHistoryPoint>>)>>,
+    regional_prices: Vec<Sender<(String, Result<Vec<RegionalPrice>>)>>,
+}
+
+/// Loader whose tasks stay pending until a result step resolves them
+struct ScriptedLoader(Rc<RefCell<Pending>>);
+
+impl Loader for ScriptedLoader {
+    fn deals(&self, _: Platform, _: String, _: usize, _: usize, _: String) -> DealsLoadTask {
+        let (sender, task) = Task::channel();
+        self.0.borrow_mut().deals.push(sender);
+        task
+    }
+
+    fn search(&self, _: String, _: Platform, _: String, _: usize) -> DealsLoadTask {
+        let (sender, task) = Task::channel();
+        self.0.borrow_mut().deals.push(sender);
+        task
+    }
+
+    fn game_info(&self, _: String) -> GameInfoTask {
+        let (sender, task) = Task::channel();
+        self.0.borrow_mut().game_info.push(sender);
+        task
+    }
+
+    fn game_info_batch(&self, _: Vec<String>) -> GameInfoBatchTask {
+        let (sender, task) = Task::channel();
+        self.0.borrow_mut().game_info_batch.push(sender);
+        task
+    }
+
+    fn price_history(&self, _: String, _: String) -> PriceHistoryTask {
+        let (sender, task) = Task::channel();
+        self.0.borrow_mut().price_history.push(sender);
+        task
+    }
+
+    fn regional_prices(&self, _: String, _: Vec<Region>) -> RegionalPricesTask {
+        let (sender, task) = Task::channel();
+        self.0.borrow_mut().regional_prices.push(sender);
+        task
+    }
+}
+
+/// Oldest request still waiting for a result; aborted ones are dropped
+fn next_pending<T>(
+    queue: &mut Vec<Sender<T>>,
+    name: &str,
+) -> std::result::Result<Sender<T>, String> {
+    queue.retain(|sender| !sender.is_closed());
+    if queue.is_empty() {
+        return Err(format!("no {} load is pending", name));
+    }
+    Ok(queue.remove(0))
+}
+
+/// The app with its loads and time under the control of a script
+struct Session {
+    model: Model,
+    tasks: TaskManager,
+    pending: Rc<RefCell<Pending>>,
+    now: Rc<Cell<Instant>>,
+    /// Text copied by the yank menu, oldest first
+    copied: Rc<RefCell<Vec<String>>>,
+    /// Links opened in the browser, oldest first
+    opened: Rc<RefCell<Vec<String>>>,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Session {
+    fn new(width: u16, height: u16, config: Config, keymap: &str) -> Self {
+        let mut model = Model::with_store(Box::new(MemoryConfigStore::new(config)));
+        if !keymap.is_empty() {
+            model.keymap.apply_overrides(keymap);
+        }
+
+        let pending = Rc::new(RefCell::new(Pending::default()));
+        let now = Rc::new(Cell::new(Instant::now()));
+        let mut tasks = TaskManager::new(
+            Box::new(ScriptedLoader(pending.clone())),
+            Box::new(ManualClock(now.clone())),
+        );
+        let copied = Rc::new(RefCell::new(Vec::new()));
+        tasks.clipboard = Box::new(MemoryClipboard(copied.clone()));
+        let opened = Rc::new(RefCell::new(Vec::new()));
+        tasks.opener = Box::new(MemoryOpener(opened.clone()));
+
+        let mut session = Self {
+            model,
+            tasks,
+            pending,
+            now,
+            copied,
+            opened,
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+        };
+        tasks::start_load(&mut session.model, &mut session.tasks);
+        session.pump();
+        session
+    }
+
+    /// Default config and keys in a 120x40 terminal
+    fn start() -> Self {
+        Self::new(120, 40, Config::default(), "")
+    }
+
+    /// Replay a session. Without a `start` step it begins like `Session::start`.
+    fn replay(steps: Vec<Step>, mut on_step: impl FnMut(usize, &Session)) -> Self {
+        let mut steps = steps.into_iter().peekable();
+        let mut session = match steps.next_if(|step| matches!(step, Step::Start { .. })) {
+            Some(Step::Start {
+                width,
+                height,
+                config,
+                keymap,
+            }) => Self::new(width, height, *config, &keymap),
+            _ => Self::start(),
+        };
+        for (i, step) in steps.enumerate() {
+            if let Err(e) = session.run(step) {
+                panic!("step {}: {}", i + 1, e);
+            }
+            on_step(i + 1, &session);
+        }
+        session
+    }
+
+    /// Apply one step, then run a main loop iteration and draw
+    fn run(&mut self, step: Step) -> std::result::Result<(), String> {
+        match step {
+            Step::Start { .. } => return Err("`start` must be the first step".to_string()),
+            Step::Key { key } => {
+                let chord = KeyChord::parse(&key)?;
+                self.event(Event::Key(chord.to_event()));
+            }
+            Step::Click { column, row } => {
+                self.mouse(MouseEventKind::Down(MouseButton::Left), column, row);
+            }
+            Step::Scroll { column, row, down } => {
+                let kind = if down {
+                    MouseEventKind::ScrollDown
+                } else {
+                    MouseEventKind::ScrollUp
+                };
+                self.mouse(kind, column, row);
+            }
+            Step::Resize { width, height } => {
+                self.terminal.backend_mut().resize(width, height);
+                self.event(Event::Resize(width, height));
+            }
+            Step::Wait { ms } => {
+                self.now.set(self.now.get() + Duration::from_millis(ms));
+                self.dispatch(Message::Tick);
+            }
+            result => self.resolve(result)?,
+        }
+        self.pump();
+        Ok(())
+    }
+
+    /// Complete the pending load a result step is for
+    fn resolve(&self, step: Step) -> std::result::Result<(), String> {
+        let mut pending = self.pending.borrow_mut();
+        match step {
+            Step::Deals { deals } => {
+                let _ = next_pending(&mut pending.deals, "deals")?.send(Ok(deals));
+            }
+            Step::DealsFailed { error } => {
+                let sender = next_pending(&mut pending.deals, "deals")?;
+                // Dropping the sender fails the task
+                if let Some(error) = error {
+                    let _ = sender.send(Err(error));
+                }
+            }
+            Step::GameInfo { game_id, info } => {
+                let result = info.ok_or_else(|| DealveError::Api("not found".to_string()));
+                let _ = next_pending(&mut pending.game_info, "game info")?.send((game_id, result));
+            }
+            Step::GameInfoBatch { infos } => {
+                let _ = next_pending(&mut pending.game_info_batch, "game info batch")?.send(infos);
+            }
+            Step::PriceHistory { game_id, history } => {
+                let _ = next_pending(&mut pending.price_history, "price history")?
+                    .send((game_id, Ok(history)));
+            }
+            Step::RegionalPrices { game_id, prices } => {
+                let result = prices.ok_or_else(|| DealveError::Api("not found".to_string()));
+                let _ = next_pending(&mut pending.regional_prices, "regional prices")?
+                    .send((game_id, result));
+            }
+            step => return Err(format!("{:?} is not a load result", step)),
+        }
+        Ok(())
+    }
+
+    /// Run main loop iterations until no more loads finish: pick up results,
+    /// start the loads they lead to, then draw
+    fn pump(&mut self) {
+        loop {
+            let messages = tasks::check_tasks(&mut self.model, &mut self.tasks);
+            if messages.is_empty() {
+                break;
+            }
+            for msg in messages {
+                self.dispatch(msg);
+            }
+        }
+        tasks::load_game_info_if_due(&mut self.model, &mut self.tasks);
+        let model = &mut self.model;
+        self.terminal
+            .draw(|frame| view::view(frame, model))
+            .unwrap();
+    }
+
+    fn event(&mut self, event: Event) {
+        if let Some(msg) = events::handle_event(&self.model, event, self.now.get()) {
+            self.dispatch(msg);
+        }
+    }
+
+    fn mouse(&mut self, kind: MouseEventKind, column: u16, row: u16) {
+        self.event(Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }));
+    }
+
+    fn dispatch(&mut self, msg: Message) {
+        tasks::dispatch(&mut self.model, &mut self.tasks, msg);
+    }
+
+    /// Press keys, given as space-separated chords (`j j enter`)
+    fn keys(&mut self, keys: &str) {
+        for key in keys.split_whitespace() {
+            self.step(Step::Key {
+                key: key.to_string(),
+            });
+        }
+    }
+
+    fn wait(&mut self, ms: u64) {
+        self.step(Step::Wait { ms });
+    }
+
+    fn step(&mut self, step: Step) {
+        if let Err(e) = self.run(step) {
+            panic!("{}", e);
+        }
+    }
+
+    fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    /// Rendered text, one line per row
+    fn screen(&self) -> String {
+        let buffer = self.buffer();
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn read_session(path: &Path) -> Vec<Step> {
+    let content = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("{}:{}: {}", path.display(), i + 1, e))
+        })
+        .collect()
+}
+
+#[test]
+fn loads_deals_then_history_and_debounced_game_info() {
+    let mut session = Session::start();
+    assert!(session.model.loading.deals);
+    assert!(session.screen().contains("Loading deals"));
+
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    assert!(!session.model.loading.deals);
+    assert!(session.screen().contains("Hollow Knight"));
+    // The selected deal's price history is requested right away
+    let game_id = session.model.selected_deal().unwrap().id.clone();
+    assert_eq!(session.model.loading.price_history, Some(game_id.clone()));
+
+    session.step(Step::PriceHistory {
+        game_id: game_id.clone(),
+        history: fixtures::price_history().remove(&game_id).unwrap(),
+    });
+    assert!(session.screen().contains("Low: 3,74 €"));
+
+    // Game info waits for the selection to settle
+    session.keys("j");
+    let game_id = session.model.selected_deal().unwrap().id.clone();
+    session.wait(100);
+    assert_eq!(session.model.loading.game_info, None);
+    session.wait(150);
+    assert_eq!(session.model.loading.game_info, Some(game_id.clone()));
+
+    session.step(Step::GameInfo {
+        game_id,
+        info: None,
+    });
+    assert_eq!(session.model.loading.game_info, None);
+}
+
+#[test]
+fn filter_narrows_the_list() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+
+    session.keys("f c u t > = 7 5 enter");
+    assert!(!session.model.filter.active);
+    assert_eq!(session.model.filtered_deals().len(), 4);
+    assert!(!session.screen().contains("Outer Wilds"));
+}
+
+#[test]
+fn failed_load_is_reported() {
+    let mut session = Session::start();
+    session.step(Step::DealsFailed {
+        error: Some(DealveError::Network("connection reset".to_string())),
+    });
+    assert!(!session.model.loading.deals);
+    assert_eq!(
+        session.model.error.as_deref(),
+        Some("Network error: connection reset")
+    );
+}
+
+#[test]
+fn clicks_far_apart_are_not_a_double_click() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+
+    // Third row of the list: below the border and the table header
+    let click = Step::Click { column: 10, row: 4 };
+    session.step(click);
+    assert_eq!(session.model.ui.table_state.selected(), Some(2));
+    session.wait(500);
+    session.step(Step::Click { column: 10, row: 4 });
+    assert!(session.model.ui.last_click.is_some());
+}
+
+#[test]
+fn tag_filters_wait_for_the_game_info_of_every_deal() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+
+    session.keys("f t a g : r o g u e l i k e enter");
+    assert!(session.model.filtered_deals().is_empty());
+    assert!(session.screen().contains("loading tags…"));
+
+    session.step(Step::GameInfoBatch {
+        infos: fixtures::game_info(),
+    });
+    let titles: Vec<&str> = session
+        .model
+        .filtered_deals()
+        .iter()
+        .map(|deal| deal.title.as_str())
+        .collect();
+    assert_eq!(titles, ["Hades", "Slay the Spire"]);
+    assert!(!session.screen().contains("loading tags…"));
+}
+
+#[test]
+fn a_reload_restarts_the_game_info_prefetch() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    session.keys("f t a g : r o g u e l i k e enter");
+    assert_eq!(session.tasks.prefetched_game_ids.len(), 10);
+
+    // The prefetch for the old deals is dropped, and their ids asked again
+    session.keys("r");
+    assert!(session.tasks.game_info_batch_task.is_none());
+    assert!(session.tasks.prefetched_game_ids.is_empty());
+    assert!(!session.model.loading.game_info_batch);
+
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    assert!(session.model.loading.game_info_batch);
+    session.step(Step::GameInfoBatch {
+        infos: fixtures::game_info(),
+    });
+    assert_eq!(session.model.filtered_deals().len(), 2);
+}
+
+#[test]
+fn failed_save_is_reported_in_the_status_line() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    session.model.config_store = Box::new(MemoryConfigStore {
+        read_only: true,
+        ..MemoryConfigStore::default()
+    });
+
+    // Save the current view as a preset, then go back to the list
+    session.keys("v a c h e a p enter esc");
+    assert_eq!(session.model.presets.len(), 1);
+    assert!(session
+        .screen()
+        .contains("Couldn't save settings: read-only config"));
+
+    // The message goes away after a while
+    for _ in 0..100 {
+        session.wait(50);
+    }
+    assert!(session.model.ui.status.is_none());
+    assert!(session.screen().contains("views"));
+}
+
+#[test]
+fn regional_prices_are_compared_in_one_currency() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    let game_id = session.model.selected_deal().unwrap().id.clone();
+
+    session.keys("R");
+    assert_eq!(session.model.loading.regional_prices, Some(game_id.clone()));
+    assert!(session.screen().contains("Loading prices in 6 regions"));
+
+    let prices = fixtures::regional_prices().remove(&game_id).unwrap();
+    session.step(Step::RegionalPrices {
+        game_id: game_id.clone(),
+        prices: Some(prices),
+    });
+    assert!(session.screen().contains("Cheapest: Brazil (1,88 €)"));
+
+    // Reloading asks again, closing forgets the comparison
+    session.keys("r");
+    assert_eq!(session.model.loading.regional_prices, Some(game_id.clone()));
+    session.step(Step::RegionalPrices {
+        game_id,
+        prices: None,
+    });
+    assert!(session.screen().contains("Error: API error: not found"));
+    session.keys("esc");
+    assert!(session.model.comparison.is_none());
+}
+
+#[test]
+fn prices_are_shown_and_filtered_in_the_display_currency() {
+    let config = Config {
+        display_currency: Some("USD".to_string()),
+        ..Config::default()
+    };
+    let mut session = Session::new(120, 40, config, "");
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    let screen = session.screen();
+    assert!(screen.contains("≈ USD"));
+    // €3.74 at the built-in 1.08
+    assert!(screen.contains("4,04 $"));
+
+    // €3.99 is $4.31, over the bound even though 3.99 isn't
+    session.keys("$ tab 4 . 2 enter");
+    let titles: Vec<&str> = session
+        .model
+        .filtered_deals()
+        .iter()
+        .map(|deal| deal.title.as_str())
+        .collect();
+    assert_eq!(titles, ["Hollow Knight"]);
+
+    // The last currency is followed by none
+    session.keys("esc j enter d");
+    assert_eq!(session.model.display_currency, None);
+    assert!(!session.screen().contains("≈ USD"));
+    session.keys("d");
+    assert_eq!(session.model.display_currency, Currency::new("AUD"));
+
+    // Without a rates command there's nothing to update the rates with
+    session.keys("r");
+    assert!(!session.model.pending_rates_refresh);
+    assert!(session.screen().contains("No rates_command in config.toml"));
+}
+
+#[test]
+fn deals_are_exported_with_the_chosen_columns() {
+    let dir = std::env::temp_dir().join(format!("dealve-export-{}", std::process::id()));
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    session.keys("f c u t > = 8 0 enter");
+
+    // Markdown with the title, price and release date: untick the shop,
+    // cut, regular price and URL, then tick the release date
+    session.keys("e tab tab j space j j space j space j j space j space");
+    let columns: Vec<&str> = session
+        .model
+        .export
+        .columns
+        .iter()
+        .map(|c| c.id())
+        .collect();
+    assert_eq!(columns, ["title", "price", "release_date"]);
+    assert!(session.screen().contains("[3] Release date"));
+
+    session.model.export.last_path = Some(dir.join("deals").to_string_lossy().into_owned());
+    session.keys("enter");
+    assert!(session.screen().contains("Save to:"));
+    session.keys("enter");
+    assert_eq!(session.model.ui.popup, crate::model::Popup::None);
+    assert!(session.model.loading.export_info);
+    assert!(session
+        .screen()
+        .contains("Loading game info of 1 games to export"));
+
+    session.step(Step::GameInfoBatch {
+        infos: fixtures::game_info(),
+    });
+    assert!(session.screen().contains("Exported 1 deals to"));
+    let markdown = fs::read_to_string(dir.join("deals.md")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(markdown.ends_with("| Celeste | 3,99 € | 2018-01-25 |\n"));
+}
+
+#[test]
+fn yank_menu_copies_the_selected_deal() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    session.keys("f c u t > = 8 0 enter");
+
+    session.keys("y");
+    assert_eq!(session.model.ui.popup, crate::model::Popup::Yank);
+    assert!(session
+        .screen()
+        .contains("https://isthereanydeal.com/game/celeste/info/"));
+    session.keys("j j j enter");
+    assert_eq!(session.model.ui.popup, crate::model::Popup::None);
+    assert_eq!(session.copied.borrow().len(), 1);
+    assert!(session.screen().contains("Copied Celeste — 3,99 €"));
+}
+
+#[test]
+fn links_are_copied_when_they_cannot_be_opened() {
+    let url = "https://itad.link/018d937f-07d6-7327-8b0c-ab7bd8e8e2c1/";
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    session.keys("enter");
+    assert_eq!(*session.opened.borrow(), [url]);
+
+    // Over SSH a browser would start on the server
+    session.model.ssh = true;
+    session.keys("enter");
+    assert_eq!(session.opened.borrow().len(), 1);
+    assert_eq!(*session.copied.borrow(), [url]);
+    assert_eq!(
+        session.model.ui.status.as_ref().unwrap().text,
+        format!("No browser over SSH, copied {}", url)
+    );
+
+    // Unless there is an open command, which may forward the link
+    session.model.open_command = Some("ssh-open {url}".to_string());
+    session.keys("enter");
+    assert_eq!(session.opened.borrow().len(), 2);
+
+    session.tasks.opener = Box::new(BrokenOpener);
+    session.keys("enter");
+    // The failure comes back on the next main loop iteration
+    session.wait(50);
+    assert_eq!(session.copied.borrow().len(), 2);
+    assert_eq!(
+        session.model.ui.status.as_ref().unwrap().text,
+        "Couldn't open the link (no browser found), copied it instead"
+    );
+}
+
+#[test]
+fn selected_deals_survive_a_new_sort_and_take_batch_actions() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+    // Hollow Knight and Celeste, then Celeste down to Outer Wilds
+    session.keys("space space j j V");
+    assert!(session.screen().contains("5 selected"));
+
+    // The new order comes back from the API, the selection stays
+    session.keys("right");
+    let mut deals = fixtures::deals();
+    deals.reverse();
+    session.step(Step::Deals { deals });
+    assert!(session.screen().contains("5 selected"));
+    assert_eq!(session.model.selected_deals()[0].title, "Outer Wilds");
+
+    session.keys("y k enter");
+    assert_eq!(session.copied.borrow()[0].lines().count(), 5);
+    assert_eq!(
+        session.model.ui.status.as_ref().unwrap().text,
+        "Copied 5 summaries"
+    );
+    session.keys("enter");
+    assert_eq!(session.opened.borrow().len(), 5);
+
+    // Ignored games leave the list, and the selection with them
+    session.keys("x");
+    assert_eq!(session.model.filtered_deals().len(), 5);
+    assert!(session.model.selection.is_empty());
+    session.keys("X");
+    assert_eq!(session.model.filtered_deals().len(), 10);
+
+    session.keys("w");
+    assert!(session.screen().contains("★"));
+}
+
+#[test]
+fn columns_tab_shows_moves_and_resizes_columns() {
+    let mut session = Session::start();
+    session.step(Step::Deals {
+        deals: fixtures::deals(),
+    });
+
+    // Show the shop column, move it before the ATL marker and widen it
+    session.keys("esc j enter tab tab tab tab j j j j enter K +");
+    let config = session.model.config_store.load();
+    assert_eq!(config.columns, ["title", "price", "cut", "shop", "atl"]);
+    assert_eq!(config.column_widths.get("shop"), Some(&13));
+
+    session.keys("esc esc esc");
+    assert!(session.screen().contains("Shop"));
+}
+
+#[test]
+fn resolving_a_load_that_was_not_started_fails() {
+    let mut session = Session::start();
+    let result = session.run(Step::GameInfoBatch { infos: Vec::new() });
+    assert_eq!(
+        result,
+        Err("no game info batch load is pending".to_string())
+    );
+}
+
+/// Demo mode runs on the real runtime and clock, so it waits for each load
+#[tokio::test]
+async fn demo_mode_fills_every_pane() {
+    let mut model = Model::with_store(Box::new(MemoryConfigStore::default()));
+    let mut tasks = TaskManager::new(DataSource::Demo.loader(), Box::new(SystemClock));
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    tasks::start_load(&mut model, &mut tasks);
+
+    let pump = |model: &mut Model, tasks: &mut TaskManager| {
+        for msg in tasks::check_tasks(model, tasks) {
+            tasks::dispatch(model, tasks, msg);
+        }
+        tasks::load_game_info_if_due(model, tasks);
+    };
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while model.selected_game_info().is_none() || model.selected_price_history().is_none() {
+        assert!(Instant::now() < deadline, "demo loads didn't finish");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        pump(&mut model, &mut tasks);
+    }
+
+    // Sorted by price like the API, cheapest first
+    assert_eq!(model.deals.len(), fixtures::deals().len());
+    assert!(model
+        .deals
+        .windows(2)
+        .all(|pair| pair[0].price.amount <= pair[1].price.amount));
+    terminal
+        .draw(|frame| view::view(frame, &mut model))
+        .unwrap();
+    let screen = format!("{:?}", terminal.backend().buffer());
+    assert!(screen.contains("Released:"));
+    assert!(screen.contains("Low:"));
+}
+
+#[test]
+fn recorded_sessions() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sessions");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let session = Session::replay(read_session(&path), |_, _| {});
+        assert_snapshot(&format!("session_{}", name), session.buffer());
+    }
+}
+
+/// Replay the session in `DEALVE_REPLAY`, printing the screen after each step
+#[test]
+fn replay_file() {
+    let Some(path) = std::env::var_os("DEALVE_REPLAY") else {
+        return;
+    };
+    let steps = read_session(Path::new(&path));
+    let session = Session::replay(steps, |i, session| {
+        println!("── step {} ──\n{}", i, session.screen());
+    });
+    println!(
+        "── selected: {:?}",
+        session.model.selected_deal().map(|d| &d.title)
+    );
+}
@@ -0,0 +1,424 @@
+//! Where the IsThereAnyDeal API key is kept: the OS secret store, a file
+//! encrypted with a passphrase, or the output of a `key_command`. None of
+//! these ever print the key, errors only say what went wrong.
+
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use dealve_core::shell::{self, Streams};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, ConfigStore, Source};
+
+const KEYRING_SERVICE: &str = "dealve";
+const KEYRING_USER: &str = "itad-api-key";
+
+/// Format of `api_key.enc`
+const ENCRYPTED_FILE_VERSION: u32 = 1;
+
+/// Wrong passphrases allowed before giving up at startup
+const PASSPHRASE_ATTEMPTS: usize = 3;
+
+/// Where dealve stores the API key itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretBackend {
+    /// Secret Service on Linux, Keychain on macOS, Credential Manager on Windows
+    Keyring,
+    /// `api_key.enc` in the config directory, encrypted with a passphrase
+    EncryptedFile,
+}
+
+/// Ways to keep the key offered by the onboarding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStorage {
+    Backend(SecretBackend),
+    /// Fetch the key with a command, dealve doesn't store it
+    Command,
+}
+
+impl KeyStorage {
+    pub const ALL: &'static [KeyStorage] = &[
+        KeyStorage::Backend(SecretBackend::Keyring),
+        KeyStorage::Backend(SecretBackend::EncryptedFile),
+        KeyStorage::Command,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            KeyStorage::Backend(SecretBackend::Keyring) => "System keyring",
+            KeyStorage::Backend(SecretBackend::EncryptedFile) => "Encrypted file",
+            KeyStorage::Command => "Password manager command",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            KeyStorage::Backend(SecretBackend::Keyring) => {
+                "Secret Service, macOS Keychain or Windows Credential Manager"
+            }
+            KeyStorage::Backend(SecretBackend::EncryptedFile) => {
+                "Encrypted with a passphrase asked at startup"
+            }
+            KeyStorage::Command => "Run a command that prints the key, e.g. `pass show itad`",
+        }
+    }
+}
+
+/// Encrypted API key, as saved in `api_key.enc`
+#[derive(Serialize, Deserialize)]
+struct EncryptedKey {
+    version: u32,
+    /// Argon2id salt, base64
+    salt: String,
+    /// XChaCha20-Poly1305 nonce, base64
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Path of the encrypted key file (~/.config/dealve/api_key.enc)
+pub fn encrypted_key_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("dealve").join("api_key.enc"))
+}
+
+/// Find the API key. In order: `ITAD_API_KEY` or a key left in the config
+/// file, `key_command`, then the secret backend chosen during onboarding.
+/// `None` when no key was set up yet. Run `migrate_plain_text_key` first so a
+/// key left in the file doesn't shadow the secret store.
+///
+/// The encrypted file asks for its passphrase on the terminal, so this must
+/// run before the TUI takes over the screen.
+pub async fn load_api_key(config: &Config) -> Result<Option<String>> {
+    if let Some(key) = config.api_key.as_ref().filter(|k| !k.is_empty()) {
+        return Ok(Some(key.clone()));
+    }
+    if let Some(command) = &config.key_command {
+        return run_key_command(command).await.map(Some);
+    }
+    match config.secret_backend {
+        Some(SecretBackend::Keyring) => keyring_get().await,
+        Some(SecretBackend::EncryptedFile) => match encrypted_key_path() {
+            Some(path) if path.exists() => read_encrypted_file(&path, prompt_passphrase).map(Some),
+            _ => Ok(None),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Take a key left in plain text in the config file by an older version out
+/// of it. It goes to the secret backend chosen during onboarding unless that
+/// already holds a key, and is dropped when `key_command` is set. Without
+/// either it stays, with a warning.
+///
+/// Like `load_api_key`, this may ask for a passphrase on the terminal.
+pub async fn migrate_plain_text_key(
+    config_store: &mut dyn ConfigStore,
+    config: &mut Config,
+) -> Result<()> {
+    if config.source("api_key") != Source::File {
+        return Ok(());
+    }
+    let Some(key) = config.api_key.clone() else {
+        return Ok(());
+    };
+    if config.key_command.is_none() {
+        match config.secret_backend {
+            None => {
+                eprintln!(
+                    "Warning: the API key is stored in plain text in the config file, \
+                     run `dealve setup` to move it to a secret store"
+                );
+                return Ok(());
+            }
+            Some(SecretBackend::Keyring) => {
+                if keyring_get().await?.is_none() {
+                    keyring_set(&key).await?;
+                }
+            }
+            Some(SecretBackend::EncryptedFile) => {
+                let path = encrypted_key_path().context("no config directory")?;
+                if !path.exists() {
+                    eprintln!("Moving the API key from the config file to an encrypted file");
+                    write_encrypted_file(&path, &key, &prompt_new_passphrase()?)?;
+                }
+            }
+        }
+    }
+    config.api_key = None;
+    config_store
+        .save(config)
+        .context("can't remove the plain text API key from the config")?;
+    eprintln!("Removed the plain text API key from the config file");
+    Ok(())
+}
+
+/// Ask for the passphrase of the key file on the terminal
+fn prompt_passphrase() -> Result<String> {
+    Ok(rpassword::prompt_password(
+        "Passphrase for the dealve API key: ",
+    )?)
+}
+
+/// Ask for a new passphrase twice on the terminal. Like every passphrase it
+/// is used as typed, spaces included.
+fn prompt_new_passphrase() -> Result<String> {
+    for _ in 0..PASSPHRASE_ATTEMPTS {
+        let passphrase = rpassword::prompt_password("New passphrase for the dealve API key: ")?;
+        if passphrase.trim().is_empty() {
+            eprintln!("The passphrase can't be empty");
+            continue;
+        }
+        if rpassword::prompt_password("Type it again: ")? == passphrase {
+            return Ok(passphrase);
+        }
+        eprintln!("The passphrases didn't match");
+    }
+    bail!("no passphrase chosen")
+}
+
+/// Store the key in a backend. `secret` is the passphrase for the encrypted
+/// file and is ignored by the keyring.
+pub async fn store_api_key(backend: SecretBackend, key: &str, secret: &str) -> Result<()> {
+    match backend {
+        SecretBackend::Keyring => keyring_set(key).await,
+        SecretBackend::EncryptedFile => write_encrypted_file(
+            &encrypted_key_path().context("no config directory")?,
+            key,
+            secret,
+        ),
+    }
+}
+
+/// Run a `key_command` and check that it prints `key`
+pub async fn check_key_command(command: &str, key: &str) -> Result<()> {
+    if run_key_command(command).await? != key {
+        bail!("the command doesn't print the key you entered");
+    }
+    Ok(())
+}
+
+/// Run `key_command` with the shell and take the first line it prints
+async fn run_key_command(command: &str) -> Result<String> {
+    let command = command.to_string();
+    let (command, result) = tokio::task::spawn_blocking(move || {
+        let result = shell::run(&command, Streams::Capture);
+        (command, result)
+    })
+    .await?;
+    let stdout = result.map_err(|e| anyhow!("key_command: {}", e))?;
+    match stdout.lines().next().map(str::trim) {
+        Some(key) if !key.is_empty() => Ok(key.to_string()),
+        _ => bail!("key_command `{}` printed nothing", command),
+    }
+}
+
+// The keyring talks to D-Bus with its own blocking runtime on Linux, which
+// can't run on a thread that is driving tokio tasks
+async fn keyring_get() -> Result<Option<String>> {
+    let result = tokio::task::spawn_blocking(|| {
+        keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?.get_password()
+    })
+    .await?;
+    match result {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(anyhow!("can't read the API key from the keyring: {}", e)),
+    }
+}
+
+async fn keyring_set(key: &str) -> Result<()> {
+    let key = key.to_string();
+    tokio::task::spawn_blocking(move || {
+        keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?.set_password(&key)
+    })
+    .await?
+    .map_err(|e| anyhow!("can't save the API key to the keyring: {}", e))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<chacha20poly1305::Key> {
+    let mut key = chacha20poly1305::Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("can't derive a key from the passphrase: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt the key into `path`. The file is written whole and only readable
+/// by the user, so a failed write keeps the previous key.
+fn write_encrypted_file(path: &Path, key: &str, passphrase: &str) -> Result<()> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, key.as_bytes())
+        .map_err(|_| anyhow!("can't encrypt the API key"))?;
+
+    let file = EncryptedKey {
+        version: ENCRYPTED_FILE_VERSION,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("enc.tmp");
+    let write = || -> std::io::Result<()> {
+        // A file left by a failed write may have other permissions
+        let _ = fs::remove_file(&temp_path);
+        let mut options = File::options();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut temp = options.open(&temp_path)?;
+        temp.write_all(serde_json::to_string_pretty(&file)?.as_bytes())?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, path)
+    };
+    write().with_context(|| format!("can't write {}", path.display()))
+}
+
+/// Decrypt the key file, asking for the passphrase until it's right or the
+/// attempts run out
+fn read_encrypted_file(
+    path: &Path,
+    mut ask_passphrase: impl FnMut() -> Result<String>,
+) -> Result<String> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("can't read {}", path.display()))?;
+    let file: EncryptedKey = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not an encrypted key file", path.display()))?;
+    if file.version != ENCRYPTED_FILE_VERSION {
+        bail!(
+            "{} has version {}, expected {}",
+            path.display(),
+            file.version,
+            ENCRYPTED_FILE_VERSION
+        );
+    }
+
+    let decode = |field: &str| {
+        BASE64
+            .decode(field)
+            .with_context(|| format!("{} is corrupted", path.display()))
+    };
+    let salt = decode(&file.salt)?;
+    let nonce = decode(&file.nonce)?;
+    let ciphertext = decode(&file.ciphertext)?;
+    if nonce.len() != 24 {
+        bail!("{} is corrupted", path.display());
+    }
+    let nonce = XNonce::from_slice(&nonce);
+
+    for _ in 0..PASSPHRASE_ATTEMPTS {
+        let passphrase = ask_passphrase()?;
+        let cipher = XChaCha20Poly1305::new(&derive_key(&passphrase, &salt)?);
+        if let Ok(key) = cipher.decrypt(nonce, ciphertext.as_slice()) {
+            return String::from_utf8(key).map_err(|_| anyhow!("{} is corrupted", path.display()));
+        }
+        eprintln!("Wrong passphrase");
+    }
+    bail!("wrong passphrase for {}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MemoryConfigStore;
+
+    fn plain_text_config() -> Config {
+        let mut config = Config {
+            api_key: Some("plain-text-key".to_string()),
+            ..Config::default()
+        };
+        config.sources.insert("api_key".to_string(), Source::File);
+        config
+    }
+
+    #[test]
+    fn the_encrypted_file_needs_the_exact_passphrase() {
+        let dir = std::env::temp_dir().join(format!("dealve-secrets-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("api_key.enc");
+        write_encrypted_file(&path, "the-key", " spaced passphrase ").unwrap();
+        // Writing again replaces the file whole
+        write_encrypted_file(&path, "new-key", " spaced passphrase ").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let mut attempts = ["spaced passphrase", " spaced passphrase "].into_iter();
+        let key = read_encrypted_file(&path, || Ok(attempts.next().unwrap().to_string()));
+        assert_eq!(key.unwrap(), "new-key");
+
+        let wrong = read_encrypted_file(&path, || Ok("wrong".to_string()));
+        assert!(wrong
+            .unwrap_err()
+            .to_string()
+            .starts_with("wrong passphrase"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_plain_text_key_is_dropped_for_the_key_command() {
+        let mut config = Config {
+            key_command: Some("echo stored-key".to_string()),
+            ..plain_text_config()
+        };
+        let mut store = MemoryConfigStore::new(config.clone());
+        migrate_plain_text_key(&mut store, &mut config)
+            .await
+            .unwrap();
+        assert_eq!(config.api_key, None);
+        assert_eq!(store.config.api_key, None);
+        assert_eq!(
+            load_api_key(&config).await.unwrap().as_deref(),
+            Some("stored-key")
+        );
+    }
+
+    #[tokio::test]
+    async fn a_plain_text_key_stays_without_a_secret_store() {
+        let mut config = plain_text_config();
+        let mut store = MemoryConfigStore::new(config.clone());
+        store.read_only = true;
+        migrate_plain_text_key(&mut store, &mut config)
+            .await
+            .unwrap();
+        assert_eq!(
+            load_api_key(&config).await.unwrap().as_deref(),
+            Some("plain-text-key")
+        );
+    }
+
+    #[tokio::test]
+    async fn keys_from_the_environment_are_left_alone() {
+        let mut config = Config {
+            key_command: Some("echo stored-key".to_string()),
+            ..plain_text_config()
+        };
+        config
+            .sources
+            .insert("api_key".to_string(), Source::Env("ITAD_API_KEY"));
+        let mut store = MemoryConfigStore::new(config.clone());
+        store.read_only = true;
+        migrate_plain_text_key(&mut store, &mut config)
+            .await
+            .unwrap();
+        assert_eq!(
+            load_api_key(&config).await.unwrap().as_deref(),
+            Some("plain-text-key")
+        );
+    }
+}
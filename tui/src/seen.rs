@@ -0,0 +1,226 @@
+use dealve_core::models::Deal;
+use dealve_core::money::Money;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Entries not seen for this long are forgotten
+const SEEN_RETENTION_DAYS: i64 = 30;
+
+/// How a deal compares to what was seen on the previous visit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DealStatus {
+    New,
+    PriceDropped,
+    Seen,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct SeenEntry {
+    /// Missing in snapshots written before prices had a currency, which
+    /// stored a plain `price` number instead
+    #[serde(default)]
+    amount: Option<Money>,
+    /// Unix timestamp of the last session the deal was loaded in
+    seen_at: i64,
+}
+
+/// Snapshot of seen deals, keyed by `Deal::key` (game id + shop id)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SeenSnapshot {
+    deals: HashMap<String, SeenEntry>,
+}
+
+/// Tracks deals seen in previous sessions to highlight what changed since
+#[derive(Default)]
+pub struct SeenTracker {
+    previous: SeenSnapshot,
+    current: HashMap<String, Money>,
+}
+
+impl SeenTracker {
+    /// Get the snapshot file path (~/.local/share/dealve/seen.json)
+    pub fn snapshot_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|p| p.join("dealve").join("seen.json"))
+    }
+
+    /// Load the snapshot from the previous visit, or start empty
+    pub fn load() -> Self {
+        let previous = Self::snapshot_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            previous,
+            current: HashMap::new(),
+        }
+    }
+
+    /// Remember deals loaded during this session
+    pub fn record(&mut self, deals: &[Deal]) {
+        for deal in deals {
+            self.current.insert(deal.key(), deal.price.amount);
+        }
+    }
+
+    pub fn status(&self, deal: &Deal) -> DealStatus {
+        // First visit: nothing to compare against, don't flag everything
+        if self.previous.deals.is_empty() {
+            return DealStatus::Seen;
+        }
+        match self.previous.deals.get(&deal.key()) {
+            None => DealStatus::New,
+            Some(SeenEntry {
+                amount: Some(amount),
+                ..
+            }) if deal.price.amount < *amount => DealStatus::PriceDropped,
+            Some(_) => DealStatus::Seen,
+        }
+    }
+
+    /// Merge this session into the snapshot and write it to disk
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let Some(path) = Self::snapshot_path() else {
+            return Ok(());
+        };
+
+        let snapshot = self.merged(chrono::Utc::now().timestamp());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Swapped in whole, so a crash never truncates the snapshot
+        let content = serde_json::to_string(&snapshot)?;
+        let temp_path = path.with_extension("json.tmp");
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(content.as_bytes())?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, &path)
+    }
+
+    /// The previous snapshot without expired entries, updated with the deals
+    /// of this session
+    fn merged(&self, now: i64) -> SeenSnapshot {
+        let cutoff = now - SEEN_RETENTION_DAYS * 24 * 60 * 60;
+
+        let mut snapshot = SeenSnapshot {
+            deals: self
+                .previous
+                .deals
+                .iter()
+                .filter(|(_, entry)| entry.seen_at >= cutoff)
+                .map(|(key, entry)| (key.clone(), *entry))
+                .collect(),
+        };
+        for (key, price) in &self.current {
+            snapshot.deals.insert(
+                key.clone(),
+                SeenEntry {
+                    amount: Some(*price),
+                    seen_at: now,
+                },
+            );
+        }
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    /// A tracker whose previous visit saw `deals` at their price plus `delta`
+    fn tracker(deals: &[Deal], delta: i64, seen_at: i64) -> SeenTracker {
+        let previous = SeenSnapshot {
+            deals: deals
+                .iter()
+                .map(|deal| {
+                    let amount = deal.price.amount;
+                    let entry = SeenEntry {
+                        amount: Some(Money::new(amount.minor() + delta, amount.currency())),
+                        seen_at,
+                    };
+                    (deal.key(), entry)
+                })
+                .collect(),
+        };
+        SeenTracker {
+            previous,
+            current: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn first_visit_flags_nothing() {
+        let deals = fixtures::deals();
+        let tracker = SeenTracker::default();
+        assert!(deals
+            .iter()
+            .all(|deal| tracker.status(deal) == DealStatus::Seen));
+    }
+
+    #[test]
+    fn deals_missing_from_the_last_visit_are_new() {
+        let deals = fixtures::deals();
+        let tracker = tracker(&deals[1..], 0, 0);
+        assert_eq!(tracker.status(&deals[0]), DealStatus::New);
+        assert_eq!(tracker.status(&deals[1]), DealStatus::Seen);
+
+        // The same game in another shop is another deal
+        let mut other_shop = deals[1].clone();
+        other_shop.shop.id = "999".to_string();
+        assert_eq!(tracker.status(&other_shop), DealStatus::New);
+    }
+
+    #[test]
+    fn only_cheaper_prices_are_flagged() {
+        let deals = fixtures::deals();
+        assert_eq!(
+            tracker(&deals, 1, 0).status(&deals[0]),
+            DealStatus::PriceDropped
+        );
+        assert_eq!(tracker(&deals, -1, 0).status(&deals[0]), DealStatus::Seen);
+        assert_eq!(tracker(&deals, 0, 0).status(&deals[0]), DealStatus::Seen);
+    }
+
+    #[test]
+    fn entries_without_a_price_are_seen() {
+        let deals = fixtures::deals();
+        let previous: SeenSnapshot = serde_json::from_str(&format!(
+            r#"{{"deals": {{"{}": {{"price": 1.0, "seen_at": 0}}}}}}"#,
+            deals[0].key()
+        ))
+        .unwrap();
+        let tracker = SeenTracker {
+            previous,
+            current: HashMap::new(),
+        };
+        assert_eq!(tracker.status(&deals[0]), DealStatus::Seen);
+        assert_eq!(tracker.status(&deals[1]), DealStatus::New);
+    }
+
+    #[test]
+    fn merging_keeps_recent_entries_and_records_this_session() {
+        let deals = fixtures::deals();
+        let now = 100 * DAY;
+        let mut seen = tracker(&deals[..2], 0, now - SEEN_RETENTION_DAYS * DAY - 1);
+        seen.previous
+            .deals
+            .extend(tracker(&deals[2..3], 0, now - DAY).previous.deals);
+        seen.record(&deals[1..2]);
+
+        let merged = seen.merged(now);
+        // Expired, kept, then seen again this session
+        assert!(!merged.deals.contains_key(&deals[0].key()));
+        assert_eq!(merged.deals[&deals[2].key()].seen_at, now - DAY);
+        let entry = merged.deals[&deals[1].key()];
+        assert_eq!(entry.seen_at, now);
+        assert_eq!(entry.amount, Some(deals[1].price.amount));
+        assert_eq!(merged.deals.len(), 2);
+    }
+}
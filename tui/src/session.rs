@@ -0,0 +1,163 @@
+use crossterm::event::{Event, KeyEventKind, MouseButton, MouseEventKind};
+use dealve_core::models::{Deal, GameInfo, PriceHistoryPoint, RegionalPrice};
+use dealve_core::DealveError;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::config::Config;
+use crate::keymap::{KeyChord, Keymap};
+
+/// One step of a recorded session: an input, time passing, or the result of
+/// a load. Sessions are stored as JSON lines and replayed by the tests.
+///
+/// ```json
+/// {"step":"key","key":"ctrl-d"}
+/// {"step":"wait","ms":250}
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    /// Terminal size, config and keymap overrides when the session started
+    Start {
+        width: u16,
+        height: u16,
+        config: Box<Config>,
+        #[serde(default)]
+        keymap: String,
+    },
+    /// A key press, in keymap notation
+    Key {
+        key: String,
+    },
+    Click {
+        column: u16,
+        row: u16,
+    },
+    Scroll {
+        column: u16,
+        row: u16,
+        down: bool,
+    },
+    Resize {
+        width: u16,
+        height: u16,
+    },
+    /// Time passed since the previous step
+    Wait {
+        ms: u64,
+    },
+    /// A deals page or search finished loading
+    Deals {
+        deals: Vec<Deal>,
+    },
+    /// A deals load failed; `None` when the task itself died
+    DealsFailed {
+        error: Option<DealveError>,
+    },
+    GameInfo {
+        game_id: String,
+        info: Option<GameInfo>,
+    },
+    GameInfoBatch {
+        infos: Vec<GameInfo>,
+    },
+    PriceHistory {
+        game_id: String,
+        history: Vec<PriceHistoryPoint>,
+    },
+    /// Prices across regions; `None` when loading them failed
+    RegionalPrices {
+        game_id: String,
+        prices: Option<Vec<RegionalPrice>>,
+    },
+}
+
+impl Step {
+    /// Step for a terminal event, if it is one that reaches `update`
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some(Step::Key {
+                key: KeyChord::from_event(*key).id(),
+            }),
+            Event::Mouse(mouse) => {
+                let (column, row) = (mouse.column, mouse.row);
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => Some(Step::Click { column, row }),
+                    MouseEventKind::ScrollDown => Some(Step::Scroll {
+                        column,
+                        row,
+                        down: true,
+                    }),
+                    MouseEventKind::ScrollUp => Some(Step::Scroll {
+                        column,
+                        row,
+                        down: false,
+                    }),
+                    _ => None,
+                }
+            }
+            Event::Resize(width, height) => Some(Step::Resize {
+                width: *width,
+                height: *height,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Writes a session to a file as it happens, enabled with `DEALVE_RECORD=<path>`
+pub struct Recorder {
+    file: LineWriter<File>,
+    last_step: Instant,
+}
+
+impl Recorder {
+    /// Start a session file with the terminal size, config and keymap in use.
+    /// The API key is left out.
+    pub fn create(
+        path: &Path,
+        mut config: Config,
+        width: u16,
+        height: u16,
+        now: Instant,
+    ) -> std::io::Result<Self> {
+        config.api_key = None;
+        let keymap = Keymap::keymap_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+
+        let mut recorder = Self {
+            file: LineWriter::new(File::create(path)?),
+            last_step: now,
+        };
+        recorder.write(&Step::Start {
+            width,
+            height,
+            config: Box::new(config),
+            keymap,
+        });
+        Ok(recorder)
+    }
+
+    /// Append a step, preceded by the time elapsed since the last one.
+    /// Waits are rounded up so a replay never runs behind the recording.
+    pub fn record(&mut self, now: Instant, step: &Step) {
+        let elapsed = now.duration_since(self.last_step);
+        let ms = elapsed.as_micros().div_ceil(1000) as u64;
+        if ms > 0 {
+            self.write(&Step::Wait { ms });
+        }
+        self.last_step = now;
+        self.write(step);
+    }
+
+    fn write(&mut self, step: &Step) {
+        // Recording is best effort, it never interrupts the session
+        if let Ok(line) = serde_json::to_string(step) {
+            let _ = writeln!(self.file, "{}", line);
+        }
+    }
+}
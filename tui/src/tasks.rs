@@ -1,13 +1,271 @@
-use std::time::Instant;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use dealve_core::models::{Deal, PriceHistoryPoint};
-use tokio::task::JoinHandle;
+use dealve_core::models::{Deal, GameInfo, Platform, PriceHistoryPoint, Region, RegionalPrice};
+use dealve_core::rates::ExchangeRates;
+use dealve_core::DealveError;
+use tokio::sync::oneshot;
+use tokio::task::{JoinHandle, JoinSet};
 
+use crate::clipboard::{Clipboard, CopyTask, SystemClipboard};
+use crate::config::Config;
+use crate::demo::DemoLoader;
+use crate::links::{OpenTask, Opener, SystemOpener};
 use crate::message::Message;
 use crate::model::Model;
+use crate::session::{Recorder, Step};
+use crate::update;
 
-pub type DealsLoadTask = JoinHandle<dealve_core::Result<Vec<Deal>>>;
-pub type PriceHistoryTask = JoinHandle<(String, dealve_core::Result<Vec<PriceHistoryPoint>>)>;
+pub type DealsLoadTask = Task<dealve_core::Result<Vec<Deal>>>;
+pub type PriceHistoryTask = Task<(String, dealve_core::Result<Vec<PriceHistoryPoint>>)>;
+pub type GameInfoTask = Task<(String, dealve_core::Result<GameInfo>)>;
+pub type GameInfoBatchTask = Task<Vec<GameInfo>>;
+pub type RegionalPricesTask = Task<(String, dealve_core::Result<Vec<RegionalPrice>>)>;
+pub type RatesTask = Task<dealve_core::Result<ExchangeRates>>;
+
+/// A background load whose result is picked up by the main loop without waiting
+pub struct Task<T> {
+    result: oneshot::Receiver<T>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// The task ended without a result: it panicked, or its sender was dropped
+#[derive(Debug)]
+pub struct TaskFailed;
+
+impl<T: Send + 'static> Task<T> {
+    /// Run a future on the tokio runtime
+    pub fn spawn(future: impl Future<Output = T> + Send + 'static) -> Self {
+        let (sender, result) = oneshot::channel();
+        let handle = tokio::spawn(async move {
+            let _ = sender.send(future.await);
+        });
+        Self {
+            result,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl<T> Task<T> {
+    /// A task completed through the returned sender instead of a future
+    #[cfg(test)]
+    pub fn channel() -> (oneshot::Sender<T>, Self) {
+        let (sender, result) = oneshot::channel();
+        (
+            sender,
+            Self {
+                result,
+                handle: None,
+            },
+        )
+    }
+
+    /// Wait for the result, outside of the main loop
+    pub async fn wait(self) -> Result<T, TaskFailed> {
+        self.result.await.map_err(|_| TaskFailed)
+    }
+
+    pub fn abort(self) {
+        if let Some(handle) = self.handle {
+            handle.abort();
+        }
+    }
+
+    /// The result if the task finished
+    pub fn poll(&mut self) -> Option<Result<T, TaskFailed>> {
+        match self.result.try_recv() {
+            Ok(value) => Some(Ok(value)),
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => Some(Err(TaskFailed)),
+        }
+    }
+}
+
+/// Where deals, game info and price history come from
+pub trait Loader {
+    fn deals(
+        &self,
+        platform: Platform,
+        region_code: String,
+        offset: usize,
+        page_size: usize,
+        sort: String,
+    ) -> DealsLoadTask;
+
+    fn search(
+        &self,
+        query: String,
+        platform: Platform,
+        region_code: String,
+        limit: usize,
+    ) -> DealsLoadTask;
+
+    fn game_info(&self, game_id: String) -> GameInfoTask;
+
+    fn game_info_batch(&self, game_ids: Vec<String>) -> GameInfoBatchTask;
+
+    fn price_history(&self, game_id: String, region_code: String) -> PriceHistoryTask;
+
+    fn regional_prices(&self, game_id: String, regions: Vec<Region>) -> RegionalPricesTask;
+}
+
+/// Max results allowed by ITAD search API (`/games/search/v1`).
+/// See https://docs.isthereanydeal.com/ — `results: number [1..100]`
+const MAX_SEARCH_RESULTS: usize = 100;
+
+/// Game info requests of a batch in flight at once
+const GAME_INFO_CONCURRENCY: usize = 4;
+
+/// Games per prefetch batch; the next batch starts when one is done
+const PREFETCH_BATCH_SIZE: usize = 20;
+
+/// Loads from the IsThereAnyDeal API
+pub struct ApiLoader {
+    api_key: Option<String>,
+}
+
+impl ApiLoader {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self { api_key }
+    }
+
+    fn client(&self) -> dealve_api::ItadClient {
+        dealve_api::ItadClient::new(self.api_key.clone())
+    }
+}
+
+impl Loader for ApiLoader {
+    fn deals(
+        &self,
+        platform: Platform,
+        region_code: String,
+        offset: usize,
+        page_size: usize,
+        sort: String,
+    ) -> DealsLoadTask {
+        let client = self.client();
+        Task::spawn(async move {
+            client
+                .get_deals(
+                    &region_code,
+                    page_size,
+                    offset,
+                    platform.shop_id(),
+                    Some(&sort),
+                )
+                .await
+        })
+    }
+
+    fn search(
+        &self,
+        query: String,
+        platform: Platform,
+        region_code: String,
+        limit: usize,
+    ) -> DealsLoadTask {
+        let client = self.client();
+        Task::spawn(async move {
+            client
+                .search_deals(
+                    &query,
+                    &region_code,
+                    platform.shop_id(),
+                    limit.min(MAX_SEARCH_RESULTS),
+                )
+                .await
+        })
+    }
+
+    fn game_info(&self, game_id: String) -> GameInfoTask {
+        let client = self.client();
+        Task::spawn(async move {
+            let result = client.get_game_info(&game_id).await;
+            (game_id, result)
+        })
+    }
+
+    fn game_info_batch(&self, game_ids: Vec<String>) -> GameInfoBatchTask {
+        let client = Arc::new(self.client());
+        Task::spawn(async move {
+            let mut infos = Vec::with_capacity(game_ids.len());
+            // Dropping the set when the task is aborted cancels its requests
+            for chunk in game_ids.chunks(GAME_INFO_CONCURRENCY) {
+                let mut requests = JoinSet::new();
+                for game_id in chunk {
+                    let client = Arc::clone(&client);
+                    let game_id = game_id.clone();
+                    requests.spawn(async move { client.get_game_info(&game_id).await });
+                }
+                while let Some(result) = requests.join_next().await {
+                    if let Ok(Ok(info)) = result {
+                        infos.push(info);
+                    }
+                }
+            }
+            // Bundle counts come from another endpoint, for all games at once
+            match client.get_bundle_counts(&game_ids).await {
+                Ok(counts) => {
+                    for info in &mut infos {
+                        info.bundled = counts.get(&info.id).copied();
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "loading bundle counts failed"),
+            }
+            infos
+        })
+    }
+
+    fn price_history(&self, game_id: String, region_code: String) -> PriceHistoryTask {
+        let client = self.client();
+        Task::spawn(async move {
+            let result = client.get_price_history(&game_id, &region_code).await;
+            (game_id, result)
+        })
+    }
+
+    fn regional_prices(&self, game_id: String, regions: Vec<Region>) -> RegionalPricesTask {
+        let client = self.client();
+        Task::spawn(async move {
+            let result = client.get_regional_prices(&game_id, &regions).await;
+            (game_id, result)
+        })
+    }
+}
+
+/// Where the deals come from for a session
+pub enum DataSource {
+    Api {
+        api_key: String,
+    },
+    /// Bundled sample data, see `demo.rs`
+    Demo,
+}
+
+impl DataSource {
+    pub fn loader(self) -> Box<dyn Loader> {
+        match self {
+            DataSource::Api { api_key } => Box::new(ApiLoader::new(Some(api_key))),
+            DataSource::Demo => Box::new(DemoLoader::new()),
+        }
+    }
+}
+
+/// Source of time for the game info debounce
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoadTaskKind {
@@ -16,66 +274,129 @@ pub enum LoadTaskKind {
 }
 
 pub struct TaskManager {
+    pub loader: Box<dyn Loader>,
+    pub clock: Box<dyn Clock>,
+    pub clipboard: Box<dyn Clipboard>,
+    pub opener: Box<dyn Opener>,
+    /// Session being recorded, see `session::Recorder`
+    pub recorder: Option<Recorder>,
     pub load_task: Option<DealsLoadTask>,
     pub load_task_kind: Option<LoadTaskKind>,
     pub load_more_task: Option<DealsLoadTask>,
     pub price_history_task: Option<PriceHistoryTask>,
+    pub game_info_task: Option<GameInfoTask>,
+    /// Game info prefetch for local sorting of search results
+    pub game_info_batch_task: Option<GameInfoBatchTask>,
+    pub regional_prices_task: Option<RegionalPricesTask>,
+    /// Rates command, run whatever the data source
+    pub rates_task: Option<RatesTask>,
+    /// Game info for the release dates and tags of an export
+    pub export_info_task: Option<GameInfoBatchTask>,
+    /// Clipboard command run by the yank menu
+    pub copy_task: Option<CopyTask>,
+    /// Browser or open command starting for each link being opened
+    pub open_tasks: Vec<OpenTask>,
+    /// Game ids already requested by the prefetch, so failures aren't retried
+    /// until the next load
+    pub prefetched_game_ids: HashSet<String>,
     pub last_selection_change: Instant,
     pub pending_game_info_load: bool,
 }
 
 impl TaskManager {
-    pub fn new() -> Self {
+    pub fn new(loader: Box<dyn Loader>, clock: Box<dyn Clock>) -> Self {
+        let last_selection_change = clock.now();
         Self {
+            loader,
+            clock,
+            clipboard: Box::new(SystemClipboard),
+            opener: Box::new(SystemOpener),
+            recorder: None,
             load_task: None,
             load_task_kind: None,
             load_more_task: None,
             price_history_task: None,
-            last_selection_change: Instant::now(),
+            game_info_task: None,
+            game_info_batch_task: None,
+            regional_prices_task: None,
+            rates_task: None,
+            export_info_task: None,
+            copy_task: None,
+            open_tasks: Vec::new(),
+            prefetched_game_ids: HashSet::new(),
+            last_selection_change,
             pending_game_info_load: false,
         }
     }
-}
 
-pub fn spawn_deals_load(
-    api_key: Option<String>,
-    platform_filter: dealve_core::models::Platform,
-    region_code: String,
-    offset: usize,
-    page_size: usize,
-    sort: String,
-) -> DealsLoadTask {
-    tokio::spawn(async move {
-        let client = dealve_api::ItadClient::new(api_key);
-        let shop_id = platform_filter.shop_id();
-        client
-            .get_deals(&region_code, page_size, offset, shop_id, Some(&sort))
-            .await
-    })
+    /// Append a step to the recorded session, if recording
+    pub fn record(&mut self, step: impl FnOnce() -> Step) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            let now = self.clock.now();
+            recorder.record(now, &step());
+        }
+    }
+
+    /// Each background task and what it's doing, for the debug overlay
+    pub fn task_states(&self) -> Vec<(&'static str, String)> {
+        let state = |running: bool| if running { "running" } else { "idle" }.to_string();
+        let deals = match (&self.load_task, self.load_task_kind) {
+            (Some(_), Some(LoadTaskKind::SearchDeals)) => "searching".to_string(),
+            (Some(_), _) => "loading".to_string(),
+            (None, _) => "idle".to_string(),
+        };
+        let game_info = if self.game_info_task.is_some() {
+            "running".to_string()
+        } else if self.pending_game_info_load {
+            let waited = self.clock.now().duration_since(self.last_selection_change);
+            format!("waiting ({} ms since selection)", waited.as_millis())
+        } else {
+            "idle".to_string()
+        };
+        vec![
+            ("Deals", deals),
+            ("Load more", state(self.load_more_task.is_some())),
+            ("Game info", game_info),
+            ("Price history", state(self.price_history_task.is_some())),
+            ("Regions", state(self.regional_prices_task.is_some())),
+            ("Rates", state(self.rates_task.is_some())),
+            ("Export", state(self.export_info_task.is_some())),
+            ("Clipboard", state(self.copy_task.is_some())),
+            ("Open link", state(!self.open_tasks.is_empty())),
+            (
+                "Prefetch",
+                format!(
+                    "{}, {} games requested",
+                    state(self.game_info_batch_task.is_some()),
+                    self.prefetched_game_ids.len()
+                ),
+            ),
+        ]
+    }
 }
 
-/// Max results allowed by ITAD search API (`/games/search/v1`).
-/// See https://docs.isthereanydeal.com/ — `results: number [1..100]`
-const MAX_SEARCH_RESULTS: usize = 100;
+/// Run a message through `update` and its chained messages, starting the
+/// loads they ask for. Returns whether the deals are being reloaded.
+pub fn dispatch(model: &mut Model, tasks: &mut TaskManager, msg: Message) -> bool {
+    let result = update::update(model, msg);
 
-pub fn spawn_search_load(
-    api_key: Option<String>,
-    query: String,
-    platform_filter: dealve_core::models::Platform,
-    region_code: String,
-    limit: usize,
-) -> DealsLoadTask {
-    tokio::spawn(async move {
-        let client = dealve_api::ItadClient::new(api_key);
-        client
-            .search_deals(
-                &query,
-                &region_code,
-                platform_filter.shop_id(),
-                limit.min(MAX_SEARCH_RESULTS),
-            )
-            .await
-    })
+    // Selection changed → reset debounce timer
+    if result.selection_changed {
+        tasks.last_selection_change = tasks.clock.now();
+        tasks.pending_game_info_load = true;
+        model.record_cache_lookup();
+    }
+
+    // Reload request → spawn new deals load
+    if result.needs_reload {
+        start_load(model, tasks);
+    }
+
+    let chained_reload = match result.msg {
+        Some(chained_msg) => dispatch(model, tasks, chained_msg),
+        None => false,
+    };
+    result.needs_reload || chained_reload
 }
 
 /// Start the initial/refresh load
@@ -89,13 +410,23 @@ pub fn start_load(model: &mut Model, tasks: &mut TaskManager) {
         task.abort();
     }
 
+    // The prefetch is for deals about to be replaced
+    if let Some(task) = tasks.game_info_batch_task.take() {
+        task.abort();
+    }
+    model.loading.game_info_batch = false;
+    tasks.prefetched_game_ids.clear();
+
+    if let Some(preset) = model.pending_preset.take() {
+        model.apply_preset(preset);
+    }
+
     model.reset_pagination();
     model.set_loading(true);
 
     if let Some(query) = model.active_search_query.clone() {
         tasks.load_task_kind = Some(LoadTaskKind::SearchDeals);
-        tasks.load_task = Some(spawn_search_load(
-            model.api_key.clone(),
+        tasks.load_task = Some(tasks.loader.search(
             query,
             model.platform_filter,
             model.region.code().to_string(),
@@ -103,8 +434,7 @@ pub fn start_load(model: &mut Model, tasks: &mut TaskManager) {
         ));
     } else {
         tasks.load_task_kind = Some(LoadTaskKind::StandardDeals);
-        tasks.load_task = Some(spawn_deals_load(
-            model.api_key.clone(),
+        tasks.load_task = Some(tasks.loader.deals(
             model.platform_filter,
             model.region.code().to_string(),
             0,
@@ -115,93 +445,204 @@ pub fn start_load(model: &mut Model, tasks: &mut TaskManager) {
 }
 
 /// Check all running tasks and return messages for completed ones
-pub async fn check_tasks(model: &mut Model, tasks: &mut TaskManager) -> Vec<Message> {
+pub fn check_tasks(model: &mut Model, tasks: &mut TaskManager) -> Vec<Message> {
     let mut messages = Vec::new();
 
     // Check initial/refresh load
-    if let Some(task) = tasks.load_task.as_mut() {
-        if task.is_finished() {
-            let task = tasks.load_task.take().unwrap();
-            let load_kind = tasks
-                .load_task_kind
-                .take()
-                .unwrap_or(LoadTaskKind::StandardDeals);
-            let page_size = model.deals_page_size;
-            match task.await {
-                Ok(Ok(deals)) => {
-                    let (is_more, page_size) = match load_kind {
-                        LoadTaskKind::StandardDeals => (deals.len() >= page_size, page_size),
-                        LoadTaskKind::SearchDeals => (false, deals.len()),
-                    };
-
-                    messages.push(Message::DealsLoaded {
-                        deals,
-                        is_more,
-                        page_size,
-                    });
-                }
-                Ok(Err(e)) => {
-                    let msg = match load_kind {
-                        LoadTaskKind::StandardDeals => e.to_string(),
-                        LoadTaskKind::SearchDeals => format!("Search failed: {}", e),
-                    };
-                    messages.push(Message::DealsLoadFailed(msg));
-                }
-                Err(_) => {
-                    let msg = match load_kind {
-                        LoadTaskKind::StandardDeals => "Task failed".to_string(),
-                        LoadTaskKind::SearchDeals => "Search task failed".to_string(),
-                    };
-                    messages.push(Message::DealsLoadFailed(msg));
-                }
+    if let Some(result) = tasks.load_task.as_mut().and_then(Task::poll) {
+        tasks.load_task = None;
+        let load_kind = tasks
+            .load_task_kind
+            .take()
+            .unwrap_or(LoadTaskKind::StandardDeals);
+        let page_size = model.deals_page_size;
+        match result {
+            Ok(Ok(deals)) => {
+                tasks.record(|| Step::Deals {
+                    deals: deals.clone(),
+                });
+                let (is_more, page_size) = match load_kind {
+                    LoadTaskKind::StandardDeals => (deals.len() >= page_size, page_size),
+                    LoadTaskKind::SearchDeals => (false, deals.len()),
+                };
+
+                messages.push(Message::DealsLoaded {
+                    deals,
+                    is_more,
+                    page_size,
+                });
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(error = %e, kind = ?load_kind, "loading deals failed");
+                let msg = match load_kind {
+                    LoadTaskKind::StandardDeals => e.to_string(),
+                    LoadTaskKind::SearchDeals => format!("Search failed: {}", e),
+                };
+                tasks.record(|| Step::DealsFailed { error: Some(e) });
+                messages.push(Message::DealsLoadFailed(msg));
+            }
+            Err(TaskFailed) => {
+                tracing::error!(kind = ?load_kind, "deals task ended without a result");
+                tasks.record(|| Step::DealsFailed { error: None });
+                let msg = match load_kind {
+                    LoadTaskKind::StandardDeals => "Task failed".to_string(),
+                    LoadTaskKind::SearchDeals => "Search task failed".to_string(),
+                };
+                messages.push(Message::DealsLoadFailed(msg));
             }
         }
     }
 
     // Check load-more task
-    if let Some(task) = tasks.load_more_task.as_mut() {
-        if task.is_finished() {
-            let task = tasks.load_more_task.take().unwrap();
-            let page_size = model.deals_page_size;
-            match task.await {
-                Ok(Ok(deals)) => {
-                    let is_more = deals.len() >= page_size;
-                    messages.push(Message::MoreDealsLoaded {
-                        deals,
-                        is_more,
-                        page_size,
-                    });
-                }
-                Ok(Err(e)) => {
-                    messages.push(Message::DealsLoadFailed(e.to_string()));
-                }
-                Err(_) => {
-                    messages.push(Message::DealsLoadFailed("Task failed".to_string()));
-                }
+    if let Some(result) = tasks.load_more_task.as_mut().and_then(Task::poll) {
+        tasks.load_more_task = None;
+        let page_size = model.deals_page_size;
+        match result {
+            Ok(Ok(deals)) => {
+                tasks.record(|| Step::Deals {
+                    deals: deals.clone(),
+                });
+                let is_more = deals.len() >= page_size;
+                messages.push(Message::MoreDealsLoaded {
+                    deals,
+                    is_more,
+                    page_size,
+                });
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(error = %e, "loading more deals failed");
+                let msg = e.to_string();
+                tasks.record(|| Step::DealsFailed { error: Some(e) });
+                messages.push(Message::DealsLoadFailed(msg));
+            }
+            Err(TaskFailed) => {
+                tracing::error!("load more task ended without a result");
+                tasks.record(|| Step::DealsFailed { error: None });
+                messages.push(Message::DealsLoadFailed("Task failed".to_string()));
             }
         }
     }
 
     // Check price history task
-    if let Some(task) = tasks.price_history_task.as_mut() {
-        if task.is_finished() {
-            let task = tasks.price_history_task.take().unwrap();
-            if let Ok((game_id, result)) = task.await {
-                match result {
-                    Ok(history) => {
-                        messages.push(Message::PriceHistoryLoaded { game_id, history });
-                    }
-                    Err(_) => {
-                        messages.push(Message::PriceHistoryLoaded {
-                            game_id,
-                            history: vec![],
-                        });
-                    }
-                }
+    if let Some(result) = tasks.price_history_task.as_mut().and_then(Task::poll) {
+        tasks.price_history_task = None;
+        if let Ok((game_id, result)) = result {
+            // A failed load is shown as an empty history
+            let history = result.unwrap_or_default();
+            tasks.record(|| Step::PriceHistory {
+                game_id: game_id.clone(),
+                history: history.clone(),
+            });
+            messages.push(Message::PriceHistoryLoaded { game_id, history });
+        }
+    }
+
+    // Check game info task
+    if let Some(result) = tasks.game_info_task.as_mut().and_then(Task::poll) {
+        tasks.game_info_task = None;
+        if let Ok((game_id, result)) = result {
+            let info = result.ok();
+            tasks.record(|| Step::GameInfo {
+                game_id: game_id.clone(),
+                info: info.clone(),
+            });
+            messages.push(Message::GameInfoLoaded { game_id, info });
+        }
+    }
+
+    // Check game info prefetch task
+    if let Some(result) = tasks.game_info_batch_task.as_mut().and_then(Task::poll) {
+        tasks.game_info_batch_task = None;
+        match result {
+            Ok(infos) => {
+                tasks.record(|| Step::GameInfoBatch {
+                    infos: infos.clone(),
+                });
+                messages.push(Message::GameInfoBatchLoaded(infos));
+            }
+            // A failed batch still ends the loading of the tag filters
+            Err(TaskFailed) => {
+                tracing::error!("game info prefetch ended without a result");
+                messages.push(Message::GameInfoBatchLoaded(Vec::new()));
             }
         }
     }
 
+    // Check regional prices task
+    if let Some(result) = tasks.regional_prices_task.as_mut().and_then(Task::poll) {
+        tasks.regional_prices_task = None;
+        let (game_id, result) = match result {
+            Ok((game_id, result)) => (game_id, result.map_err(|e| e.to_string())),
+            Err(TaskFailed) => (
+                model.loading.regional_prices.clone().unwrap_or_default(),
+                Err("Task failed".to_string()),
+            ),
+        };
+        if let Err(e) = &result {
+            tracing::warn!(error = %e, %game_id, "loading regional prices failed");
+        }
+        tasks.record(|| Step::RegionalPrices {
+            game_id: game_id.clone(),
+            prices: result.as_ref().ok().cloned(),
+        });
+        messages.push(Message::RegionalPricesLoaded { game_id, result });
+    }
+
+    // Check exchange rates task
+    if let Some(result) = tasks.rates_task.as_mut().and_then(Task::poll) {
+        tasks.rates_task = None;
+        let result = match result {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(TaskFailed) => Err("Task failed".to_string()),
+        };
+        if let Err(e) = &result {
+            tracing::warn!(error = %e, "updating exchange rates failed");
+        }
+        messages.push(Message::RatesRefreshed(result));
+    }
+
+    // Check export game info task
+    if let Some(result) = tasks.export_info_task.as_mut().and_then(Task::poll) {
+        tasks.export_info_task = None;
+        // Games that couldn't be loaded are exported without their info
+        let infos = result.unwrap_or_default();
+        tasks.record(|| Step::GameInfoBatch {
+            infos: infos.clone(),
+        });
+        messages.push(Message::ExportInfoLoaded(infos));
+    }
+
+    // Check clipboard command task
+    if let Some(result) = tasks.copy_task.as_mut().and_then(Task::poll) {
+        tasks.copy_task = None;
+        let result = result.unwrap_or_else(|TaskFailed| Err("Task failed".to_string()));
+        if let Err(e) = &result {
+            tracing::warn!(error = %e, "clipboard command failed");
+        }
+        messages.push(Message::ClipboardCommandDone(result));
+    }
+
+    // Prefetch game info when sorting search results by release date, rank...
+    // for the columns showing it, or for filters on tags
+    let sort_needs_info = model.is_search_mode() && model.sort_state.criteria.needs_game_info();
+    if (sort_needs_info || model.columns_need_game_info() || model.query.needs_game_info())
+        && !model.loading.deals
+        && tasks.game_info_batch_task.is_none()
+    {
+        let missing: Vec<String> = model
+            .deals
+            .iter()
+            .map(|deal| deal.id.clone())
+            .filter(|id| model.lacks_column_game_info(id))
+            .filter(|id| tasks.prefetched_game_ids.insert(id.clone()))
+            .take(PREFETCH_BATCH_SIZE)
+            .collect();
+        if !missing.is_empty() {
+            model.loading.game_info_batch = true;
+            tasks.game_info_batch_task = Some(tasks.loader.game_info_batch(missing));
+        }
+    }
+
     // Check if we should load more deals (infinite scroll)
     if !model.is_search_mode()
         && model.should_load_more()
@@ -209,8 +650,7 @@ pub async fn check_tasks(model: &mut Model, tasks: &mut TaskManager) -> Vec<Mess
         && tasks.load_task.is_none()
     {
         model.pagination.loading_more = true;
-        tasks.load_more_task = Some(spawn_deals_load(
-            model.api_key.clone(),
+        tasks.load_more_task = Some(tasks.loader.deals(
             model.platform_filter,
             model.region.code().to_string(),
             model.pagination.offset,
@@ -223,29 +663,103 @@ pub async fn check_tasks(model: &mut Model, tasks: &mut TaskManager) -> Vec<Mess
     if tasks.price_history_task.is_none() && !model.loading.deals {
         if let Some(game_id) = model.needs_price_history_load() {
             model.loading.price_history = Some(game_id.clone());
-            let api_key = model.api_key.clone();
-            let region_code = model.region.code().to_string();
-            tasks.price_history_task = Some(tokio::spawn(async move {
-                let client = dealve_api::ItadClient::new(api_key);
-                let result = client.get_price_history(&game_id, &region_code).await;
-                (game_id, result)
-            }));
+            tasks.price_history_task = Some(
+                tasks
+                    .loader
+                    .price_history(game_id, model.region.code().to_string()),
+            );
+        }
+    }
+
+    // Load regional prices for an open comparison
+    if tasks.regional_prices_task.is_none() {
+        if let Some((game_id, regions)) = model.needs_regional_prices_load() {
+            model.loading.regional_prices = Some(game_id.clone());
+            tasks.regional_prices_task = Some(tasks.loader.regional_prices(game_id, regions));
+        }
+    }
+
+    // Load the game info a pending export waits for
+    if tasks.export_info_task.is_none() {
+        if let Some(game_ids) = model.needs_export_game_info() {
+            model.loading.export_info = true;
+            tasks.export_info_task = Some(tasks.loader.game_info_batch(game_ids));
+        }
+    }
+
+    // Check open link tasks
+    tasks.open_tasks.retain_mut(|task| match task.poll() {
+        None => true,
+        Some(result) => {
+            if let Ok((url, result)) = result {
+                if let Err(e) = &result {
+                    tracing::warn!(error = %e, %url, "opening a link failed");
+                }
+                messages.push(Message::LinkOpened { url, result });
+            }
+            false
+        }
+    });
+
+    // Open the links asked for
+    for url in std::mem::take(&mut model.pending_open) {
+        let task = tasks.opener.open(&url, model.open_command.as_deref());
+        tasks.open_tasks.push(task);
+    }
+
+    // Copy what the yank menu picked; a copy still running is replaced
+    if let Some(text) = model.pending_copy.take() {
+        if let Some(task) = tasks.copy_task.take() {
+            task.abort();
+        }
+        tasks.copy_task = tasks
+            .clipboard
+            .copy(&text, model.clipboard_command.as_deref());
+    }
+
+    // Run the rates command once asked to
+    if tasks.rates_task.is_none() && std::mem::take(&mut model.pending_rates_refresh) {
+        if let Some(command) = model.rates_command.clone() {
+            model.loading.rates = true;
+            tasks.rates_task = Some(refresh_rates(command));
         }
     }
 
     messages
 }
 
-/// Load game info for the currently selected deal (async, called from main loop)
-pub async fn load_game_info_if_needed(model: &mut Model) {
+/// Run the rates command on a blocking thread and save the rates it prints
+fn refresh_rates(command: String) -> RatesTask {
+    Task::spawn(async move {
+        let path = Config::rates_path()
+            .ok_or_else(|| DealveError::Rates("no config directory to save them to".to_string()))?;
+        tokio::task::spawn_blocking(move || ExchangeRates::refresh(&command, &path))
+            .await
+            .map_err(|e| DealveError::Rates(e.to_string()))?
+    })
+}
+
+/// Start loading game info for the selected deal once the selection has
+/// settled for the debounce delay
+pub fn load_game_info_if_due(model: &mut Model, tasks: &mut TaskManager) {
+    let delay = Duration::from_millis(model.game_info_delay_ms);
+    if !tasks.pending_game_info_load
+        || model.loading.deals
+        || tasks
+            .clock
+            .now()
+            .duration_since(tasks.last_selection_change)
+            < delay
+    {
+        return;
+    }
+    tasks.pending_game_info_load = false;
+
     if let Some(game_id) = model.needs_game_info_load() {
         model.loading.game_info = Some(game_id.clone());
-        let client = dealve_api::ItadClient::new(model.api_key.clone());
-        if let Ok(info) = client.get_game_info(&game_id).await {
-            model.game_info_cache.insert(game_id.clone(), info);
-        }
-        if model.loading.game_info.as_ref() == Some(&game_id) {
-            model.loading.game_info = None;
+        if let Some(task) = tasks.game_info_task.take() {
+            task.abort();
         }
+        tasks.game_info_task = Some(tasks.loader.game_info(game_id));
     }
 }
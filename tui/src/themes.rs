@@ -0,0 +1,362 @@
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::view::styles::{Theme, ThemePalette};
+
+/// How often the themes directory is checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A theme available in the options popup, built-in or loaded from a file
+#[derive(Debug, Clone)]
+pub struct ThemeEntry {
+    pub id: String,
+    pub name: String,
+    pub palette: ThemePalette,
+    /// File the theme was loaded from, `None` for built-ins
+    pub path: Option<PathBuf>,
+}
+
+/// Theme file layout. Colours not listed are taken from `base`.
+///
+/// ```toml
+/// name = "Midnight"
+/// base = "tokyo-night"
+///
+/// [colors]
+/// accent = "#7aa2f7"
+/// bg = "black"
+/// bg_highlight = "236"
+/// ```
+#[derive(Debug, Deserialize)]
+struct ThemeFile {
+    name: Option<String>,
+    base: Option<String>,
+    /// Sorted, so problems are always reported in the same order
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+}
+
+/// Built-in themes plus user themes from `~/.config/dealve/themes/*.toml`
+pub struct ThemeRegistry {
+    entries: Vec<ThemeEntry>,
+    /// Problems found in theme files, shown in the Theme tab
+    pub errors: Vec<String>,
+    /// Modification times of the theme files at the last load
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    last_check: Instant,
+    /// Directory the themes are loaded from and `poll_changes` watches,
+    /// `None` for the built-ins only
+    dir: Option<PathBuf>,
+}
+
+impl ThemeRegistry {
+    /// Get the user themes directory (~/.config/dealve/themes)
+    pub fn themes_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("dealve").join("themes"))
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(Self::themes_dir())
+    }
+
+    fn load_from(dir: Option<PathBuf>) -> Self {
+        let mut registry = Self::builtin();
+        registry.dir = dir;
+        registry.reload();
+        registry
+    }
+
+    /// Built-in themes only, without reading the themes directory
+    pub fn builtin() -> Self {
+        Self {
+            entries: builtin_entries(),
+            errors: Vec::new(),
+            files: Vec::new(),
+            last_check: Instant::now(),
+            dir: None,
+        }
+    }
+
+    pub fn entries(&self) -> &[ThemeEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ThemeEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Palette for a theme id, falling back to the default theme
+    pub fn palette(&self, id: &str) -> ThemePalette {
+        self.get(id)
+            .map(|entry| entry.palette)
+            .unwrap_or_else(|| Theme::default().palette())
+    }
+
+    /// Re-read the themes directory if a file was added, removed or modified.
+    /// Returns whether the themes changed.
+    pub fn poll_changes(&mut self) -> bool {
+        let Some(dir) = &self.dir else {
+            return false;
+        };
+        if self.last_check.elapsed() < RELOAD_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        if scan_theme_files(dir) == self.files {
+            return false;
+        }
+        self.reload();
+        true
+    }
+
+    fn reload(&mut self) {
+        self.entries = builtin_entries();
+        self.errors.clear();
+        self.files = self
+            .dir
+            .as_deref()
+            .map(scan_theme_files)
+            .unwrap_or_default();
+
+        for (path, _) in self.files.clone() {
+            match self.load_file(&path) {
+                // A file named after a built-in replaces it
+                Ok(entry) => match self.entries.iter_mut().find(|e| e.id == entry.id) {
+                    Some(existing) => *existing = entry,
+                    None => self.entries.push(entry),
+                },
+                Err(e) => self.errors.push(format!("{}: {}", file_name(&path), e)),
+            }
+        }
+    }
+
+    fn load_file(&mut self, path: &Path) -> Result<ThemeEntry, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let file: ThemeFile = toml::from_str(&content).map_err(|e| e.message().to_string())?;
+
+        let id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let base = match &file.base {
+            Some(base) => match self.get(base) {
+                Some(entry) => entry.palette,
+                None => {
+                    self.errors.push(format!(
+                        "{}: unknown base theme `{}`",
+                        file_name(path),
+                        base
+                    ));
+                    Theme::default().palette()
+                }
+            },
+            None => Theme::default().palette(),
+        };
+
+        let mut palette = base;
+        for (key, value) in &file.colors {
+            let Some(slot) = palette_slot(&mut palette, key) else {
+                self.errors
+                    .push(format!("{}: unknown colour `{}`", file_name(path), key));
+                continue;
+            };
+            // Invalid colours keep the base theme's value
+            match Color::from_str(value) {
+                Ok(color) => *slot = color,
+                Err(_) => self.errors.push(format!(
+                    "{}: invalid colour `{}` for `{}`",
+                    file_name(path),
+                    value,
+                    key
+                )),
+            }
+        }
+
+        Ok(ThemeEntry {
+            name: file.name.unwrap_or_else(|| id.clone()),
+            id,
+            palette,
+            path: Some(path.to_path_buf()),
+        })
+    }
+}
+
+fn builtin_entries() -> Vec<ThemeEntry> {
+    Theme::ALL
+        .iter()
+        .map(|theme| ThemeEntry {
+            id: theme.id().to_string(),
+            name: theme.name().to_string(),
+            palette: theme.palette(),
+            path: None,
+        })
+        .collect()
+}
+
+/// Theme files in the themes directory with their modification times, sorted by path
+fn scan_theme_files(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<(PathBuf, Option<SystemTime>)> = read_dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn palette_slot<'a>(palette: &'a mut ThemePalette, key: &str) -> Option<&'a mut Color> {
+    let slot = match key {
+        "primary" => &mut palette.primary,
+        "primary_light" => &mut palette.primary_light,
+        "accent" => &mut palette.accent,
+        "shortcut_key" => &mut palette.shortcut_key,
+        "green" => &mut palette.green,
+        "yellow" => &mut palette.yellow,
+        "text" => &mut palette.text,
+        "text_secondary" => &mut palette.text_secondary,
+        "text_dimmed" => &mut palette.text_dimmed,
+        "bg" => &mut palette.bg,
+        "bg_highlight" => &mut palette.bg_highlight,
+        "error" => &mut palette.error,
+        _ => return None,
+    };
+    Some(slot)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dealve-themes-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn theme_files_start_from_their_base() {
+        let dir = temp_dir("base");
+        fs::write(
+            dir.join("midnight.toml"),
+            "name = \"Midnight\"\nbase = \"dracula\"\n\n[colors]\naccent = \"#7aa2f7\"\nbg = \"236\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("plain.toml"), "[colors]\nbg = \"black\"\n").unwrap();
+        fs::write(dir.join("notes.txt"), "not a theme").unwrap();
+
+        let registry = ThemeRegistry::load_from(Some(dir.clone()));
+        assert!(registry.errors.is_empty(), "{:?}", registry.errors);
+        assert_eq!(registry.entries().len(), Theme::ALL.len() + 2);
+
+        let midnight = registry.get("midnight").unwrap();
+        let dracula = Theme::Dracula.palette();
+        assert_eq!(midnight.name, "Midnight");
+        assert_eq!(midnight.palette.accent, Color::Rgb(0x7a, 0xa2, 0xf7));
+        assert_eq!(midnight.palette.bg, Color::Indexed(236));
+        assert_eq!(midnight.palette.text, dracula.text);
+
+        // Without a base, the default theme
+        let plain = registry.get("plain").unwrap();
+        assert_eq!(plain.name, "plain");
+        assert_eq!(plain.palette.bg, Color::Black);
+        assert_eq!(plain.palette.text, Theme::default().palette().text);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn mistakes_are_reported_and_skipped() {
+        let dir = temp_dir("errors");
+        fs::write(
+            dir.join("typos.toml"),
+            "base = \"nope\"\n\n[colors]\naccnet = \"red\"\ntext = \"reddish\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("broken.toml"), "name = ").unwrap();
+
+        let registry = ThemeRegistry::load_from(Some(dir.clone()));
+        assert!(registry.get("broken").is_none());
+        let typos = registry.get("typos").unwrap();
+        assert_eq!(typos.palette, Theme::default().palette());
+
+        assert_eq!(registry.errors.len(), 4, "{:?}", registry.errors);
+        assert!(registry.errors[0].starts_with("broken.toml: "));
+        assert_eq!(
+            registry.errors[1..],
+            [
+                "typos.toml: unknown base theme `nope`",
+                "typos.toml: unknown colour `accnet`",
+                "typos.toml: invalid colour `reddish` for `text`",
+            ]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_file_named_after_a_built_in_replaces_it() {
+        let dir = temp_dir("override");
+        fs::write(dir.join("nord.toml"), "[colors]\naccent = \"red\"\n").unwrap();
+
+        let registry = ThemeRegistry::load_from(Some(dir.clone()));
+        assert_eq!(registry.entries().len(), Theme::ALL.len());
+        let nord = registry.get("nord").unwrap();
+        assert_eq!(nord.palette.accent, Color::Red);
+        assert_eq!(nord.path, Some(dir.join("nord.toml")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn changed_files_are_reloaded() {
+        let dir = temp_dir("reload");
+        let path = dir.join("mine.toml");
+        fs::write(&path, "[colors]\naccent = \"red\"\n").unwrap();
+        let mut registry = ThemeRegistry::load_from(Some(dir.clone()));
+        let poll = |registry: &mut ThemeRegistry| {
+            registry.last_check = Instant::now() - RELOAD_INTERVAL;
+            registry.poll_changes()
+        };
+        assert!(!poll(&mut registry));
+
+        fs::write(&path, "[colors]\naccent = \"blue\"\n").unwrap();
+        // Mark it modified later, in case the write landed in the same tick
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        // Not checked again before the interval
+        assert!(!registry.poll_changes());
+        assert!(poll(&mut registry));
+        assert_eq!(registry.get("mine").unwrap().palette.accent, Color::Blue);
+
+        fs::remove_file(&path).unwrap();
+        assert!(poll(&mut registry));
+        assert!(registry.get("mine").is_none());
+        assert!(!ThemeRegistry::builtin().poll_changes());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
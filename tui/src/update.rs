@@ -1,11 +1,20 @@
-use dealve_core::models::Platform;
+use dealve_core::models::{Deal, Platform};
+use dealve_core::query::DealQuery;
+use std::time::Duration;
 
+use crate::clipboard::YankItem;
+use crate::columns::{self, ColumnSetting, DealsColumn};
+use crate::config::Config;
+use crate::export::{expand_path, with_format_extension, Export};
 use crate::message::Message;
-use crate::model::{MenuItem, Model, OptionsTab, Popup, SortCriteria};
+use crate::model::{MenuItem, Model, OptionsTab, Pane, Popup, RegionComparison, StatusMessage};
 
 // Number of rows to scroll for PageUp/PageDown navigation
 const PAGE_SCROLL_SIZE: usize = 20;
 
+// Maximum delay between two clicks on the same row to open it
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
 /// Flags returned by update to signal side effects needed
 pub struct UpdateResult {
     pub msg: Option<Message>,
@@ -121,17 +130,82 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             UpdateResult::with_selection_changed()
         }
         Message::OpenSelectedDeal => {
-            if let Some(i) = model.ui.table_state.selected() {
-                let filtered = model.filtered_deals();
-                if let Some(deal) = filtered.get(i) {
-                    let _ = webbrowser::open(&deal.url);
-                }
+            let urls = model
+                .batch_deals()
+                .iter()
+                .map(|deal| deal.url.clone())
+                .collect();
+            model.open_links(urls);
+            UpdateResult::none()
+        }
+        Message::ScrollDeals(delta) => {
+            let filtered_count = model.filtered_deals().len();
+            if filtered_count > 0 {
+                let i = model
+                    .ui
+                    .table_state
+                    .selected()
+                    .unwrap_or(0)
+                    .saturating_add_signed(delta)
+                    .min(filtered_count - 1);
+                model.select(Some(i));
+            }
+            UpdateResult::with_selection_changed()
+        }
+        Message::DealClicked { index, at: now } => {
+            let is_double_click = matches!(
+                model.ui.last_click,
+                Some((last, at)) if last == index && now.duration_since(at) < DOUBLE_CLICK_INTERVAL
+            );
+            if is_double_click {
+                model.ui.last_click = None;
+                return UpdateResult::with_msg(Message::OpenSelectedDeal);
+            }
+            model.select(Some(index));
+            model.ui.last_click = Some((index, now));
+            UpdateResult::with_selection_changed()
+        }
+        Message::InspectChartPoint(index) => {
+            model.ui.chart_cursor = Some(index);
+            UpdateResult::none()
+        }
+
+        // Layout
+        Message::FocusNextPane => {
+            let panes = &model.ui.layout.panes;
+            if let Some(i) = panes.iter().position(|pane| *pane == model.ui.focus) {
+                model.ui.focus = panes[(i + 1) % panes.len()];
+            }
+            UpdateResult::none()
+        }
+        Message::ToggleMaximize => {
+            model.ui.maximized = !model.ui.maximized;
+            UpdateResult::none()
+        }
+        Message::ToggleDetails => {
+            model.ui.show_details = !model.ui.show_details;
+            if !model.ui.show_details {
+                model.ui.focus = Pane::Deals;
             }
             UpdateResult::none()
         }
+        Message::ToggleDebug => {
+            model.ui.show_debug = !model.ui.show_debug;
+            UpdateResult::none()
+        }
+        Message::Resize => {
+            // Chart columns and row positions moved with the layout
+            model.ui.chart_cursor = None;
+            model.ui.last_click = None;
+            UpdateResult::none()
+        }
 
         // Menu
         Message::ToggleMenu => {
+            // Esc drops a selection before it opens the menu
+            if !model.ui.show_menu && !model.selection.is_empty() {
+                return update(model, Message::ClearSelection);
+            }
             model.ui.show_menu = !model.ui.show_menu;
             if model.ui.show_menu {
                 model.ui.menu_selected = 0;
@@ -160,6 +234,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
                 }
                 MenuItem::Keybinds => {
                     model.ui.popup = Popup::Keybinds;
+                    model.ui.keybinds_scroll = 0;
                 }
                 MenuItem::Quit => {
                     return UpdateResult::with_msg(Message::Quit);
@@ -171,29 +246,36 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
         // Filtering
         Message::StartFilter => {
             model.filter.active = true;
-            model.filter.text = model.active_search_query.clone().unwrap_or_default();
+            model.filter.text = model.filter.applied.clone();
+            model.filter.error = None;
             UpdateResult::none()
         }
         Message::CancelFilter => {
             model.filter.active = false;
-            model.filter.text = model.active_search_query.clone().unwrap_or_default();
+            model.filter.text = model.filter.applied.clone();
+            model.filter.error = None;
             model.select(Some(0));
             UpdateResult::with_selection_changed()
         }
         Message::ConfirmFilter => {
-            model.filter.active = false;
-            let normalized = model.filter.text.trim().to_string();
-            let next_query = if normalized.is_empty() {
-                None
-            } else {
-                Some(normalized)
+            let query = match DealQuery::parse(&model.filter.text) {
+                Ok(query) => query,
+                Err(e) => {
+                    // Keep the input open so the error can be fixed in place
+                    model.filter.error = Some(e.to_string());
+                    return UpdateResult::none();
+                }
             };
+            model.filter.active = false;
+            model.filter.error = None;
+            model.filter.applied = model.filter.text.trim().to_string();
+            model.filter.text = model.filter.applied.clone();
+
+            // Free text falls through to the remote title search
+            let next_query = query.text.clone();
             let has_changed = model.active_search_query != next_query;
             model.active_search_query = next_query;
-            if model.is_search_mode() && !model.is_search_sort_supported() {
-                model.sort_state.criteria = SortCriteria::Price;
-            }
-            model.filter.text = model.active_search_query.clone().unwrap_or_default();
+            model.query = query;
             model.select(Some(0));
             if has_changed {
                 UpdateResult::with_reload()
@@ -203,11 +285,17 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
         }
         Message::FilterPush(c) => {
             model.filter.text.push(c);
+            model.filter.error = DealQuery::parse(&model.filter.text)
+                .err()
+                .map(|e| e.to_string());
             model.select(Some(0));
             UpdateResult::with_selection_changed()
         }
         Message::FilterPop => {
             model.filter.text.pop();
+            model.filter.error = DealQuery::parse(&model.filter.text)
+                .err()
+                .map(|e| e.to_string());
             model.select(Some(0));
             UpdateResult::with_selection_changed()
         }
@@ -215,10 +303,14 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             if !model.filter.text.is_empty()
                 || model.price_filter.is_active()
                 || model.active_search_query.is_some()
+                || !model.query.is_empty()
             {
                 let had_search_query = model.active_search_query.take().is_some();
                 model.filter.text.clear();
+                model.filter.applied.clear();
+                model.filter.error = None;
                 model.filter.active = false;
+                model.query = DealQuery::default();
                 model.price_filter.clear();
                 model.select(Some(0));
                 return if had_search_query {
@@ -230,17 +322,115 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             UpdateResult::none()
         }
 
+        Message::ToggleNewOnly => {
+            model.new_only = !model.new_only;
+            model.select(Some(0));
+            UpdateResult::with_selection_changed()
+        }
+
+        // Selection
+        Message::ToggleDealSelection => {
+            let Some(key) = model.selected_deal().map(Deal::key) else {
+                return UpdateResult::none();
+            };
+            if !model.selection.remove(&key) {
+                model.selection.insert(key.clone());
+            }
+            model.selection_anchor = Some(key);
+            UpdateResult::with_msg(Message::SelectNext)
+        }
+        Message::SelectDealRange => {
+            let filtered = model.filtered_deals();
+            let Some(cursor) = model
+                .ui
+                .table_state
+                .selected()
+                .filter(|&i| i < filtered.len())
+            else {
+                return UpdateResult::none();
+            };
+            // Without an anchor in the list, the range is the deal under the cursor
+            let anchor = model
+                .selection_anchor
+                .as_ref()
+                .and_then(|key| filtered.iter().position(|deal| deal.key() == *key))
+                .unwrap_or(cursor);
+            let keys: Vec<String> = filtered[anchor.min(cursor)..=anchor.max(cursor)]
+                .iter()
+                .map(|deal| deal.key())
+                .collect();
+            let cursor_key = filtered[cursor].key();
+            model.selection.extend(keys);
+            model.selection_anchor = Some(cursor_key);
+            UpdateResult::none()
+        }
+        Message::ClearSelection => {
+            model.selection.clear();
+            model.selection_anchor = None;
+            UpdateResult::none()
+        }
+
+        // Watchlist and ignored games
+        Message::ToggleWatchlist => {
+            let games = model.batch_games();
+            if games.is_empty() {
+                return UpdateResult::none();
+            }
+            let added = model.lists.toggle_watched(&games);
+            let status = if added {
+                format!("Added {} to the watchlist", games_label(&games))
+            } else {
+                format!("Removed {} from the watchlist", games_label(&games))
+            };
+            model.ui.status = Some(StatusMessage::new(status));
+            model.save_lists();
+            UpdateResult::none()
+        }
+        Message::ToggleIgnored => {
+            let games = model.batch_games();
+            if games.is_empty() {
+                return UpdateResult::none();
+            }
+            let ignored = model.lists.toggle_ignored(&games);
+            let status = if ignored {
+                format!("Ignored {}", games_label(&games))
+            } else {
+                format!("Stopped ignoring {}", games_label(&games))
+            };
+            model.ui.status = Some(StatusMessage::new(status));
+            model.save_lists();
+            model.deselect_ignored();
+
+            // The ignored rows are gone, keep the cursor in the list
+            let filtered_count = model.filtered_deals().len();
+            let cursor = model.ui.table_state.selected().unwrap_or(0);
+            model.select(Some(cursor.min(filtered_count.saturating_sub(1))));
+            UpdateResult::with_selection_changed()
+        }
+        Message::ToggleShowIgnored => {
+            model.show_ignored = !model.show_ignored;
+            let status = if model.show_ignored {
+                format!("Showing {} ignored games", model.lists.ignored_count())
+            } else {
+                "Hiding ignored games".to_string()
+            };
+            model.deselect_ignored();
+            model.ui.status = Some(StatusMessage::new(status));
+            model.select(Some(0));
+            UpdateResult::with_selection_changed()
+        }
+
         // Price filter
         Message::OpenPriceFilter => {
             model.price_filter.min_input = model
                 .price_filter
                 .active_min
-                .map(|v| format!("{:.0}", v))
+                .map(|amount| amount.to_string())
                 .unwrap_or_default();
             model.price_filter.max_input = model
                 .price_filter
                 .active_max
-                .map(|v| format!("{:.0}", v))
+                .map(|amount| amount.to_string())
                 .unwrap_or_default();
             model.price_filter.selected_field = 0;
             model.ui.popup = Popup::PriceFilter;
@@ -328,6 +518,313 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             }
             UpdateResult::none()
         }
+        Message::PlatformPopupClick(index) => {
+            model.ui.platform_popup_index = index;
+            UpdateResult::with_msg(Message::PlatformPopupSelect)
+        }
+
+        // Presets
+        Message::OpenPresetPopup => {
+            model.ui.preset_popup_index = model
+                .active_preset
+                .as_ref()
+                .and_then(|name| model.presets.iter().position(|p| &p.name == name))
+                .unwrap_or(0);
+            model.ui.preset_name_input = None;
+            model.ui.popup = Popup::Presets;
+            UpdateResult::none()
+        }
+        Message::PresetPopupNext => {
+            if !model.presets.is_empty() {
+                model.ui.preset_popup_index =
+                    (model.ui.preset_popup_index + 1) % model.presets.len();
+            }
+            UpdateResult::none()
+        }
+        Message::PresetPopupPrev => {
+            if !model.presets.is_empty() {
+                if model.ui.preset_popup_index == 0 {
+                    model.ui.preset_popup_index = model.presets.len() - 1;
+                } else {
+                    model.ui.preset_popup_index -= 1;
+                }
+            }
+            UpdateResult::none()
+        }
+        Message::PresetPopupSelect => {
+            UpdateResult::with_msg(Message::ApplyPreset(model.ui.preset_popup_index))
+        }
+        Message::ApplyPreset(index) => {
+            let Some(preset) = model.presets.get(index).cloned() else {
+                return UpdateResult::none();
+            };
+            model.pending_preset = Some(preset);
+            model.ui.popup = Popup::None;
+            model.select(Some(0));
+            UpdateResult::with_reload()
+        }
+        Message::PresetStartSave => {
+            model.ui.preset_name_input = Some(model.active_preset.clone().unwrap_or_default());
+            UpdateResult::none()
+        }
+        Message::PresetNamePush(c) => {
+            if let Some(input) = model.ui.preset_name_input.as_mut() {
+                if input.chars().count() < 30 {
+                    input.push(c);
+                }
+            }
+            UpdateResult::none()
+        }
+        Message::PresetNamePop => {
+            if let Some(input) = model.ui.preset_name_input.as_mut() {
+                input.pop();
+            }
+            UpdateResult::none()
+        }
+        Message::PresetConfirmSave => {
+            let name = model
+                .ui
+                .preset_name_input
+                .take()
+                .map(|name| name.trim().to_string())
+                .unwrap_or_default();
+            if !name.is_empty() {
+                model.save_preset(name.clone());
+                model.ui.preset_popup_index = model
+                    .presets
+                    .iter()
+                    .position(|p| p.name == name)
+                    .unwrap_or(0);
+            }
+            UpdateResult::none()
+        }
+        Message::PresetDelete => {
+            model.delete_preset(model.ui.preset_popup_index);
+            if model.ui.preset_popup_index >= model.presets.len() {
+                model.ui.preset_popup_index = model.presets.len().saturating_sub(1);
+            }
+            UpdateResult::none()
+        }
+
+        // Regional prices
+        Message::OpenRegions => {
+            let Some(deal) = model.selected_deal() else {
+                return UpdateResult::none();
+            };
+            if model.options.compare_regions.is_empty() {
+                model.ui.status = Some(StatusMessage::new(
+                    "No regions to compare: add some with [c] in the Options Region tab"
+                        .to_string(),
+                ));
+                return UpdateResult::none();
+            }
+            model.comparison = Some(RegionComparison::new(
+                deal.id.clone(),
+                deal.title.clone(),
+                model.options.compare_regions.clone(),
+                model
+                    .display_currency
+                    .unwrap_or(deal.price.amount.currency()),
+            ));
+            model.ui.popup = Popup::Regions;
+            UpdateResult::none()
+        }
+        Message::RegionsNext => {
+            if let Some(comparison) = model.comparison.as_mut() {
+                let count = comparison.regions.len();
+                comparison.selected = (comparison.selected + 1) % count.max(1);
+            }
+            UpdateResult::none()
+        }
+        Message::RegionsPrev => {
+            if let Some(comparison) = model.comparison.as_mut() {
+                let count = comparison.regions.len().max(1);
+                comparison.selected = (comparison.selected + count - 1) % count;
+            }
+            UpdateResult::none()
+        }
+        Message::RegionsOpenOffer => {
+            if let Some(comparison) = &model.comparison {
+                let rows = comparison.rows(&model.exchange_rates);
+                if let Some(url) = rows
+                    .get(comparison.selected)
+                    .and_then(|row| row.offer)
+                    .map(|offer| offer.url.clone())
+                {
+                    model.open_links(vec![url]);
+                }
+            }
+            UpdateResult::none()
+        }
+        Message::RegionsRefresh => {
+            if let Some(comparison) = model.comparison.as_mut() {
+                comparison.prices = None;
+            }
+            UpdateResult::none()
+        }
+
+        // Export
+        Message::OpenExport => {
+            model.export.cursor = 0;
+            model.export.path_input = None;
+            model.ui.popup = Popup::Export;
+            UpdateResult::none()
+        }
+        Message::ExportNext => {
+            model.export.cursor = (model.export.cursor + 1) % DealsColumn::EXPORT.len();
+            UpdateResult::none()
+        }
+        Message::ExportPrev => {
+            let count = DealsColumn::EXPORT.len();
+            model.export.cursor = (model.export.cursor + count - 1) % count;
+            UpdateResult::none()
+        }
+        Message::ExportToggleColumn => {
+            if let Some(&column) = DealsColumn::EXPORT.get(model.export.cursor) {
+                let columns = &mut model.export.columns;
+                match columns.iter().position(|&c| c == column) {
+                    Some(index) => {
+                        columns.remove(index);
+                    }
+                    None => columns.push(column),
+                }
+                model.save_export_columns();
+            }
+            UpdateResult::none()
+        }
+        Message::ExportNextFormat => {
+            model.export.format = model.export.format.next();
+            UpdateResult::none()
+        }
+        Message::ExportPrevFormat => {
+            model.export.format = model.export.format.prev();
+            UpdateResult::none()
+        }
+        Message::ExportStartPath => {
+            if model.export.columns.is_empty() {
+                model.ui.status = Some(StatusMessage::new(
+                    "Choose at least one column to export".to_string(),
+                ));
+                return UpdateResult::none();
+            }
+            let path = model.export.last_path.as_deref().unwrap_or("deals");
+            model.export.path_input = Some(with_format_extension(path, model.export.format));
+            UpdateResult::none()
+        }
+        Message::ExportPathPush(c) => {
+            if let Some(input) = model.export.path_input.as_mut() {
+                input.push(c);
+            }
+            UpdateResult::none()
+        }
+        Message::ExportPathPop => {
+            if let Some(input) = model.export.path_input.as_mut() {
+                input.pop();
+            }
+            UpdateResult::none()
+        }
+        Message::ExportConfirm => {
+            let Some(input) = model.export.path_input.take() else {
+                return UpdateResult::none();
+            };
+            let input = input.trim().to_string();
+            if input.is_empty() {
+                model.export.path_input = Some(input);
+                return UpdateResult::none();
+            }
+            let export = Export {
+                path: expand_path(&input),
+                format: model.export.format,
+                columns: model.export.columns.clone(),
+            };
+            model.export.last_path = Some(input);
+            model.ui.popup = Popup::None;
+
+            // Release dates and tags need the game info of every deal
+            let missing = export.missing_game_info(&model.export_deals(), &model.game_info_cache);
+            if missing.is_empty() {
+                model.write_export(&export);
+            } else {
+                model.ui.status = Some(StatusMessage::new(format!(
+                    "Loading game info of {} games to export...",
+                    missing.len()
+                )));
+                model.export.pending = Some(export);
+            }
+            UpdateResult::none()
+        }
+        Message::ExportInfoLoaded(infos) => {
+            model.loading.export_info = false;
+            for info in infos {
+                model.game_info_cache.insert(info.id.clone(), info);
+            }
+            if let Some(export) = model.export.pending.take() {
+                model.write_export(&export);
+            }
+            UpdateResult::none()
+        }
+
+        // Clipboard
+        Message::OpenYank => {
+            if !model.batch_deals().is_empty() {
+                model.ui.yank_popup_index = 0;
+                model.ui.popup = Popup::Yank;
+            }
+            UpdateResult::none()
+        }
+        Message::YankNext => {
+            model.ui.yank_popup_index = (model.ui.yank_popup_index + 1) % YankItem::ALL.len();
+            UpdateResult::none()
+        }
+        Message::YankPrev => {
+            let count = YankItem::ALL.len();
+            model.ui.yank_popup_index = (model.ui.yank_popup_index + count - 1) % count;
+            UpdateResult::none()
+        }
+        Message::YankSelect => {
+            let item = YankItem::ALL[model.ui.yank_popup_index];
+            let deals = model.batch_deals();
+            let texts: Vec<String> = deals
+                .iter()
+                .filter_map(|deal| item.text(deal, model.region.money_format()))
+                .collect();
+            let status = match (deals.len(), texts.as_slice()) {
+                (0, _) => return UpdateResult::none(),
+                (1, []) => "No ITAD page known for this deal".to_string(),
+                (_, []) => "No ITAD page known for these deals".to_string(),
+                (1, [text]) => format!("Copied {}", text),
+                (_, texts) => format!("Copied {} {}", texts.len(), item.plural()),
+            };
+            if !texts.is_empty() {
+                model.ui.popup = Popup::None;
+                model.pending_copy = Some(texts.join("\n"));
+            }
+            model.ui.status = Some(StatusMessage::new(status));
+            UpdateResult::none()
+        }
+        Message::LinkOpened { url, result } => {
+            if let Err(e) = result {
+                model.ui.status = Some(StatusMessage::new(format!(
+                    "Couldn't open the link ({}), copied it instead",
+                    e
+                )));
+                // Links of a batch that all failed are copied together
+                model.pending_copy = Some(match model.pending_copy.take() {
+                    Some(copied) => format!("{}\n{}", copied, url),
+                    None => url,
+                });
+            }
+            UpdateResult::none()
+        }
+        Message::ClipboardCommandDone(result) => {
+            if let Err(e) = result {
+                model.ui.status = Some(StatusMessage::new(format!(
+                    "Copied with OSC 52 only: {}",
+                    e
+                )));
+            }
+            UpdateResult::none()
+        }
 
         // Sort
         Message::ToggleSortDirection => {
@@ -340,11 +837,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             }
         }
         Message::NextSortCriteria => {
-            model.sort_state.criteria = if model.is_search_mode() {
-                model.sort_state.criteria.toggle_search()
-            } else {
-                model.sort_state.criteria.next()
-            };
+            model.sort_state.criteria = model.sort_state.criteria.next();
             model.select(Some(0));
             if model.is_search_mode() {
                 UpdateResult::with_selection_changed()
@@ -353,11 +846,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             }
         }
         Message::PrevSortCriteria => {
-            model.sort_state.criteria = if model.is_search_mode() {
-                model.sort_state.criteria.toggle_search()
-            } else {
-                model.sort_state.criteria.prev()
-            };
+            model.sort_state.criteria = model.sort_state.criteria.prev();
             model.select(Some(0));
             if model.is_search_mode() {
                 UpdateResult::with_selection_changed()
@@ -369,10 +858,24 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
         // Popups
         Message::ClosePopup => {
             model.ui.popup = Popup::None;
+            model.comparison = None;
+            model.ui.preset_name_input = None;
+            model.export.path_input = None;
             model.options.platform_list_index = 0;
             model.options.region_list_index = 0;
             model.options.advanced_list_index = 0;
             model.options.theme_list_index = 0;
+            model.options.columns_list_index = 0;
+            UpdateResult::none()
+        }
+        Message::KeybindsScrollDown => {
+            if model.ui.keybinds_scroll + 1 < model.keymap.help_entries().len() {
+                model.ui.keybinds_scroll += 1;
+            }
+            UpdateResult::none()
+        }
+        Message::KeybindsScrollUp => {
+            model.ui.keybinds_scroll = model.ui.keybinds_scroll.saturating_sub(1);
             UpdateResult::none()
         }
 
@@ -383,6 +886,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             model.options.region_list_index = 0;
             model.options.advanced_list_index = 0;
             model.options.theme_list_index = 0;
+            model.options.columns_list_index = 0;
             UpdateResult::none()
         }
         Message::OptionsPrevTab => {
@@ -395,6 +899,16 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             model.options.region_list_index = 0;
             model.options.advanced_list_index = 0;
             model.options.theme_list_index = 0;
+            model.options.columns_list_index = 0;
+            UpdateResult::none()
+        }
+        Message::OptionsSelectTab(tab) => {
+            model.options.current_tab = tab;
+            model.options.platform_list_index = 0;
+            model.options.region_list_index = 0;
+            model.options.advanced_list_index = 0;
+            model.options.theme_list_index = 0;
+            model.options.columns_list_index = 0;
             UpdateResult::none()
         }
         Message::OptionsNextItem => {
@@ -409,11 +923,15 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
                         (model.options.platform_list_index + 1) % total_items;
                 }
                 OptionsTab::Advanced => {
-                    model.options.advanced_list_index = (model.options.advanced_list_index + 1) % 3;
+                    model.options.advanced_list_index = (model.options.advanced_list_index + 1) % 5;
                 }
                 OptionsTab::Theme => {
-                    model.options.theme_list_index = (model.options.theme_list_index + 1)
-                        % crate::view::styles::Theme::ALL.len();
+                    model.options.theme_list_index =
+                        (model.options.theme_list_index + 1) % model.themes.entries().len();
+                }
+                OptionsTab::Columns => {
+                    model.options.columns_list_index =
+                        (model.options.columns_list_index + 1) % model.options.columns.len();
                 }
             }
             UpdateResult::none()
@@ -438,18 +956,25 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
                 }
                 OptionsTab::Advanced => {
                     if model.options.advanced_list_index == 0 {
-                        model.options.advanced_list_index = 2;
+                        model.options.advanced_list_index = 4;
                     } else {
                         model.options.advanced_list_index -= 1;
                     }
                 }
                 OptionsTab::Theme => {
                     if model.options.theme_list_index == 0 {
-                        model.options.theme_list_index = crate::view::styles::Theme::ALL.len() - 1;
+                        model.options.theme_list_index = model.themes.entries().len() - 1;
                     } else {
                         model.options.theme_list_index -= 1;
                     }
                 }
+                OptionsTab::Columns => {
+                    if model.options.columns_list_index == 0 {
+                        model.options.columns_list_index = model.options.columns.len() - 1;
+                    } else {
+                        model.options.columns_list_index -= 1;
+                    }
+                }
             }
             UpdateResult::none()
         }
@@ -466,7 +991,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
                             needs_reload = true;
                         }
                     }
-                    model.options.save_to_config();
+                    model.save_options();
                 }
                 OptionsTab::Platforms => {
                     if model.options.platform_list_index == 0 {
@@ -482,7 +1007,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
                             }
                         }
                     }
-                    model.options.save_to_config();
+                    model.save_options();
                 }
                 OptionsTab::Advanced => {
                     match model.options.advanced_list_index {
@@ -509,18 +1034,35 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
                                 };
                             model.game_info_delay_ms = model.options.game_info_delay_ms;
                         }
+                        3 => {
+                            model.options.accessibility = !model.options.accessibility;
+                            model.apply_accessibility();
+                        }
+                        4 => {
+                            model.options.animations = !model.options.animations;
+                            model.apply_accessibility();
+                        }
                         _ => {}
                     }
-                    model.options.save_to_config();
+                    model.save_options();
                 }
                 OptionsTab::Theme => {
-                    if let Some(&theme) =
-                        crate::view::styles::Theme::ALL.get(model.options.theme_list_index)
+                    if let Some(entry) = model.themes.entries().get(model.options.theme_list_index)
                     {
-                        model.options.theme = theme;
-                        crate::view::styles::set_active_theme(theme);
+                        model.options.theme = entry.id.clone();
+                        model.palette = entry.palette;
+                    }
+                    model.save_options();
+                }
+                OptionsTab::Columns => {
+                    let index = model.options.columns_list_index;
+                    if columns::toggle_column(&mut model.options.columns, index) {
+                        model.save_options();
+                    } else {
+                        model.ui.status = Some(StatusMessage::new(
+                            "The table needs at least one column".to_string(),
+                        ));
                     }
-                    model.options.save_to_config();
                 }
             }
             if needs_reload {
@@ -536,7 +1078,87 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             {
                 model.options.default_sort.direction =
                     model.options.default_sort.direction.toggle();
-                model.options.save_to_config();
+                model.save_options();
+            }
+            UpdateResult::none()
+        }
+
+        Message::OptionsToggleCompareRegion => {
+            if OptionsTab::ALL[model.options.current_tab] == OptionsTab::Region {
+                if let Some(&region) =
+                    dealve_core::models::Region::ALL.get(model.options.region_list_index)
+                {
+                    let regions = &mut model.options.compare_regions;
+                    match regions.iter().position(|&r| r == region) {
+                        Some(index) => {
+                            regions.remove(index);
+                        }
+                        None => regions.push(region),
+                    }
+                    model.save_options();
+                }
+            }
+            UpdateResult::none()
+        }
+        Message::OptionsCycleDisplayCurrency => {
+            if OptionsTab::ALL[model.options.current_tab] == OptionsTab::Region {
+                let currencies = model.exchange_rates.currencies();
+                let next = match model.options.display_currency {
+                    None => currencies.first().copied(),
+                    Some(current) => currencies
+                        .iter()
+                        .position(|&c| c == current)
+                        .and_then(|index| currencies.get(index + 1))
+                        .copied(),
+                };
+                model.options.display_currency = next;
+                model.display_currency = next;
+                model.save_options();
+            }
+            UpdateResult::none()
+        }
+        Message::OptionsRefreshRates => {
+            if OptionsTab::ALL[model.options.current_tab] == OptionsTab::Region
+                && !model.loading.rates
+            {
+                if model.rates_command.is_some() {
+                    model.pending_rates_refresh = true;
+                } else {
+                    model.ui.status = Some(StatusMessage::new(
+                        "No rates_command in config.toml to update exchange rates with".to_string(),
+                    ));
+                }
+            }
+            UpdateResult::none()
+        }
+
+        Message::OptionsMoveColumn(delta) => {
+            if OptionsTab::ALL[model.options.current_tab] == OptionsTab::Columns {
+                let index = model.options.columns_list_index;
+                let target = index.saturating_add_signed(delta);
+                if target != index && target < model.options.columns.len() {
+                    model.options.columns.swap(index, target);
+                    model.options.columns_list_index = target;
+                    model.save_options();
+                }
+            }
+            UpdateResult::none()
+        }
+        Message::OptionsResizeColumn(delta) => {
+            if OptionsTab::ALL[model.options.current_tab] == OptionsTab::Columns {
+                if let Some(setting) = model
+                    .options
+                    .columns
+                    .get_mut(model.options.columns_list_index)
+                {
+                    let width = setting
+                        .width()
+                        .saturating_add_signed(delta)
+                        .max(ColumnSetting::MIN_WIDTH);
+                    // Back at the default, the column follows later defaults
+                    setting.width = Some(width).filter(|&w| w != setting.column.default_width());
+                    model.save_options();
+                }
             }
             UpdateResult::none()
         }
@@ -550,6 +1172,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             if !is_more {
                 model.pagination.has_more = false;
             }
+            model.seen.record(&deals);
             model.deals = deals;
             model.pagination.offset = page_size;
             model.select(Some(0));
@@ -565,6 +1188,7 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             if !is_more {
                 model.pagination.has_more = false;
             }
+            model.seen.record(&deals);
             model.deals.extend(deals);
             model.pagination.offset += page_size;
             model.pagination.loading_more = false;
@@ -585,11 +1209,87 @@ pub fn update(model: &mut Model, msg: Message) -> UpdateResult {
             UpdateResult::none()
         }
 
+        Message::GameInfoLoaded { game_id, info } => {
+            if let Some(info) = info {
+                model.game_info_cache.insert(game_id.clone(), info);
+            }
+            if model.loading.game_info.as_ref() == Some(&game_id) {
+                model.loading.game_info = None;
+            }
+            UpdateResult::none()
+        }
+
+        Message::GameInfoBatchLoaded(infos) => {
+            model.loading.game_info_batch = false;
+            for info in infos {
+                model.game_info_cache.insert(info.id.clone(), info);
+            }
+            UpdateResult::none()
+        }
+
+        Message::RegionalPricesLoaded { game_id, result } => {
+            if let Some(comparison) = model.comparison.as_mut() {
+                if comparison.game_id == game_id {
+                    comparison.prices = Some(result);
+                }
+            }
+            if model.loading.regional_prices.as_ref() == Some(&game_id) {
+                model.loading.regional_prices = None;
+            }
+            UpdateResult::none()
+        }
+        Message::RatesRefreshed(result) => {
+            model.loading.rates = false;
+            let status = match result {
+                Ok(rates) => {
+                    let status = format!(
+                        "Exchange rates updated: {} currencies{}",
+                        rates.currencies().len(),
+                        rates
+                            .date()
+                            .map(|date| format!(" of {}", date))
+                            .unwrap_or_default()
+                    );
+                    model.exchange_rates = rates;
+                    model.rates_file = Config::rates_path();
+                    status
+                }
+                Err(e) => format!("Couldn't update exchange rates: {}", e),
+            };
+            model.ui.status = Some(StatusMessage::new(status));
+            UpdateResult::none()
+        }
+
         // System
         Message::RequestRefresh => UpdateResult::with_reload(),
 
+        // Keys
+        Message::KeySequence { pending, action } => {
+            model.ui.pending_keys = pending;
+            match action {
+                Some(msg) => UpdateResult::with_msg(*msg),
+                None => UpdateResult::none(),
+            }
+        }
+
         Message::Tick => {
-            if model.loading.deals || model.pagination.loading_more {
+            // Hot-reload edited theme files
+            if model.themes.poll_changes() {
+                let entries = model.themes.entries().len();
+                model.options.theme_list_index = model.options.theme_list_index.min(entries - 1);
+                model.apply_theme();
+            }
+            if let Some(status) = model.ui.status.as_mut() {
+                status.ticks_left = status.ticks_left.saturating_sub(1);
+                if status.ticks_left == 0 {
+                    model.ui.status = None;
+                }
+            }
+            if model.loading.deals
+                || model.pagination.loading_more
+                || model.loading.regional_prices.is_some()
+                || model.loading.rates
+            {
                 model.ui.spinner_frame = (model.ui.spinner_frame + 1) % 10;
             }
             UpdateResult::none()
@@ -619,3 +1319,150 @@ fn cycle_default_platform(model: &mut Model) {
         }
     }
 }
+
+/// The game's title when there is one, how many otherwise
+fn games_label(games: &[(String, String)]) -> String {
+    match games {
+        [(_, title)] => title.clone(),
+        _ => format!("{} games", games.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MemoryConfigStore;
+    use crate::fixtures;
+
+    fn model() -> Model {
+        let mut model = Model::with_store(Box::new(MemoryConfigStore::new(Config::default())));
+        model.deals = fixtures::deals();
+        model.game_info_cache = fixtures::game_info()
+            .into_iter()
+            .map(|info| (info.id.clone(), info))
+            .collect();
+        model.select(Some(0));
+        model
+    }
+
+    /// Update with the message and the ones it leads to
+    fn send(model: &mut Model, msg: Message) {
+        let mut next = Some(msg);
+        while let Some(msg) = next {
+            next = update(model, msg).msg;
+        }
+    }
+
+    fn titles(model: &Model, keys: &[String]) -> Vec<String> {
+        let mut titles: Vec<String> = model
+            .deals
+            .iter()
+            .filter(|deal| keys.contains(&deal.key()))
+            .map(|deal| deal.title.clone())
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    fn selected_titles(model: &Model) -> Vec<String> {
+        let keys: Vec<String> = model.selection.iter().cloned().collect();
+        titles(model, &keys)
+    }
+
+    #[test]
+    fn a_range_goes_from_the_anchor_to_the_cursor() {
+        let mut model = model();
+        send(&mut model, Message::ToggleDealSelection);
+        model.select(Some(2));
+        send(&mut model, Message::SelectDealRange);
+        let expected: Vec<String> = model.deals[..3].iter().map(Deal::key).collect();
+        assert_eq!(selected_titles(&model), titles(&model, &expected));
+        assert_eq!(model.selection_anchor, Some(model.deals[2].key()));
+    }
+
+    #[test]
+    fn a_range_from_a_filtered_out_anchor_starts_at_the_cursor() {
+        let mut model = model();
+        let anchor = model.deals[0].clone();
+        send(&mut model, Message::ToggleDealSelection);
+        model.query = DealQuery::parse("-hollow").unwrap();
+        assert_eq!(anchor.title, "Hollow Knight");
+        assert!(!model
+            .filtered_deals()
+            .iter()
+            .any(|deal| deal.key() == anchor.key()));
+
+        model.select(Some(2));
+        send(&mut model, Message::SelectDealRange);
+        let cursor = model.filtered_deals()[2].key();
+        assert_eq!(
+            selected_titles(&model),
+            titles(&model, &[anchor.key(), cursor.clone()])
+        );
+        assert_eq!(model.selection_anchor, Some(cursor));
+
+        // The cursor is the anchor of the next range
+        model.select(Some(4));
+        send(&mut model, Message::SelectDealRange);
+        assert_eq!(model.selection.len(), 4);
+    }
+
+    #[test]
+    fn ignored_games_leave_the_selection() {
+        let mut model = model();
+        send(&mut model, Message::ToggleDealSelection);
+        model.select(Some(3));
+        send(&mut model, Message::SelectDealRange);
+        let ignored: Vec<String> = model.deals[..4].iter().map(Deal::key).collect();
+        assert_eq!(model.selection, ignored.iter().cloned().collect());
+
+        send(&mut model, Message::ToggleIgnored);
+        assert!(model.selection.is_empty());
+        assert_eq!(model.selection_anchor, None);
+
+        // Shown again, they aren't selected any more but can be
+        send(&mut model, Message::ToggleShowIgnored);
+        assert!(ignored.iter().all(|key| !model.selection.contains(key)));
+        model.select(Some(0));
+        send(&mut model, Message::ToggleDealSelection);
+        assert!(model.selection.contains(&ignored[0]));
+
+        // Hiding them again deselects them
+        send(&mut model, Message::ToggleShowIgnored);
+        assert!(model.selection.is_empty());
+    }
+
+    #[test]
+    fn columns_are_moved_and_resized_in_the_options() {
+        let mut model = model();
+        model.options.current_tab = OptionsTab::ALL
+            .iter()
+            .position(|tab| *tab == OptionsTab::Columns)
+            .unwrap();
+        let shop = model
+            .options
+            .columns
+            .iter()
+            .position(|setting| setting.column.id() == "shop")
+            .unwrap();
+        model.options.columns_list_index = shop;
+
+        send(&mut model, Message::OptionsToggleItem);
+        send(&mut model, Message::OptionsMoveColumn(-1));
+        send(&mut model, Message::OptionsResizeColumn(2));
+        let config = model.config_store.load();
+        assert_eq!(config.columns, ["title", "price", "cut", "shop", "atl"]);
+        assert_eq!(config.column_widths.get("shop"), Some(&14));
+
+        // Back at the default width, the setting is dropped
+        send(&mut model, Message::OptionsResizeColumn(-2));
+        assert!(model.config_store.load().column_widths.is_empty());
+
+        // Columns can't leave the list or shrink below the minimum
+        model.options.columns_list_index = 0;
+        send(&mut model, Message::OptionsMoveColumn(-1));
+        send(&mut model, Message::OptionsResizeColumn(-100));
+        assert_eq!(model.options.columns[0].column.id(), "title");
+        assert_eq!(model.options.columns[0].width(), ColumnSetting::MIN_WIDTH);
+    }
+}
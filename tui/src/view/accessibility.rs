@@ -0,0 +1,146 @@
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+use super::styles::palette;
+
+/// How the UI adapts for screen readers, serial consoles and `NO_COLOR` users
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accessibility {
+    /// Strip all colours, using reverse video for highlights
+    pub no_color: bool,
+    /// Replace box drawing, arrows, spinners and other symbols with ASCII
+    pub ascii: bool,
+    /// Play tachyonfx transitions
+    pub animations: bool,
+}
+
+impl Accessibility {
+    /// Profile from the accessibility and animations settings, `no_color`
+    /// being whether `NO_COLOR` is set. Accessible mode turns everything on
+    /// and the animations off, `NO_COLOR` only the colours off.
+    pub fn new(accessible: bool, animations: bool, no_color: bool) -> Self {
+        Self {
+            no_color: accessible || no_color,
+            ascii: accessible,
+            animations: animations && !accessible,
+        }
+    }
+
+    /// Whether `NO_COLOR` asks for no colours (https://no-color.org: set and
+    /// not empty)
+    pub fn no_color_requested() -> bool {
+        std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+    }
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self::new(false, true, false)
+    }
+}
+
+/// Rewrite a rendered frame for a profile. Done on the whole buffer so every
+/// widget is covered without each one knowing about the profile.
+pub fn apply(buffer: &mut Buffer, profile: Accessibility) {
+    if !profile.no_color && !profile.ascii {
+        return;
+    }
+
+    let background = palette().bg;
+    for cell in buffer.content.iter_mut() {
+        if profile.no_color {
+            // Anything drawn on a non-background colour was a highlight
+            if cell.bg != background && cell.bg != Color::Reset {
+                cell.modifier.insert(Modifier::REVERSED);
+            }
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+        }
+        if profile.ascii {
+            if let Some(replacement) = ascii_symbol(cell.symbol()) {
+                cell.set_symbol(replacement);
+            }
+        }
+    }
+}
+
+/// ASCII stand-in for a non-ASCII glyph used by the UI
+fn ascii_symbol(symbol: &str) -> Option<&'static str> {
+    let replacement = match symbol {
+        // Box drawing
+        "─" | "═" | "━" => "-",
+        "│" | "║" | "┃" => "|",
+        "┌" | "┐" | "└" | "┘" | "├" | "┤" | "┬" | "┴" | "┼" => "+",
+        "╔" | "╗" | "╚" | "╝" | "╭" | "╮" | "╰" | "╯" => "+",
+        // Arrows and markers
+        "↑" => "^",
+        "↓" => "v",
+        "←" => "<",
+        "→" => ">",
+        "▼" => "v",
+        "▲" => "^",
+        "●" => "*",
+        "○" => "o",
+        "✓" => "+",
+        "✗" => "x",
+        "★" => "*",
+        "⏎" => "<",
+        "—" => "-",
+        "≥" => ">",
+        "…" => ".",
+        // Blocks, cursors and sparkline bars
+        "█" | "▇" => "#",
+        "▆" | "▅" => "|",
+        "▄" | "▃" => ":",
+        "▂" | "▁" => ".",
+        "▋" => "_",
+        "░" | "▒" | "▓" => "#",
+        // Braille spinner frames, in order
+        "⠋" | "⠼" | "⠇" => "|",
+        "⠙" | "⠴" | "⠏" => "/",
+        "⠹" | "⠦" => "-",
+        "⠸" | "⠧" => "\\",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn no_color_keeps_the_animations() {
+        let profile = Accessibility::new(false, true, true);
+        assert!(profile.no_color && !profile.ascii && profile.animations);
+
+        let profile = Accessibility::new(false, false, false);
+        assert!(!profile.no_color && !profile.ascii && !profile.animations);
+
+        let profile = Accessibility::new(true, true, false);
+        assert!(profile.no_color && profile.ascii && !profile.animations);
+    }
+
+    #[test]
+    fn apply_strips_colours_and_glyphs() {
+        let area = Rect::new(0, 0, 3, 1);
+        let highlight = Style::default().fg(Color::Red).bg(Color::Blue);
+        let mut buffer = Buffer::empty(area);
+        buffer.set_string(0, 0, "┌●a", highlight);
+
+        let mut untouched = buffer.clone();
+        apply(&mut untouched, Accessibility::default());
+        assert_eq!(untouched, buffer);
+
+        apply(&mut buffer, Accessibility::new(true, true, false));
+        let symbols: String = buffer.content.iter().map(|cell| cell.symbol()).collect();
+        assert_eq!(symbols, "+*a");
+        let cell = &buffer[(0, 0)];
+        assert_eq!((cell.fg, cell.bg), (Color::Reset, Color::Reset));
+        assert!(cell.modifier.contains(Modifier::REVERSED));
+    }
+}
@@ -9,8 +9,13 @@ use ratatui::{
     Frame,
 };
 
+use chrono::{DateTime, Utc};
+use dealve_core::money::Money;
+
 use super::styles::*;
-use crate::model::Model;
+use crate::columns::DealsColumn;
+use crate::model::{Model, Pane};
+use crate::seen::DealStatus;
 
 pub fn render_deals_list(frame: &mut Frame, model: &mut Model, area: Rect, dimmed: bool) {
     let text_color = if dimmed {
@@ -18,7 +23,7 @@ pub fn render_deals_list(frame: &mut Frame, model: &mut Model, area: Rect, dimme
     } else {
         text_primary()
     };
-    let border_color = if dimmed { text_dimmed() } else { accent() };
+    let border_color = pane_border(model.ui.focus == Pane::Deals, dimmed);
     let title_color = if dimmed {
         text_dimmed()
     } else {
@@ -86,26 +91,39 @@ pub fn render_deals_list(frame: &mut Frame, model: &mut Model, area: Rect, dimme
     } else {
         text_primary()
     };
-    let header = Row::new(vec![
-        Cell::from("Title").style(Style::default().fg(header_color)),
-        Cell::from("Price").style(Style::default().fg(header_color)),
-        Cell::from("Deal").style(Style::default().fg(header_color)),
-        Cell::from("").style(Style::default().fg(header_color)),
-    ]);
+    // Prices in the display currency go beside the shop's, when any differ
+    let columns = model.visible_columns();
+    let shows_price = columns
+        .iter()
+        .any(|setting| setting.column == DealsColumn::Price);
+    let converted_header = model.display_currency.filter(|_| {
+        shows_price
+            && filtered_deals
+                .iter()
+                .any(|deal| model.convert(deal.price.amount).is_some())
+    });
+    let mut header_cells = vec![Cell::from("").style(Style::default().fg(header_color))];
+    for setting in &columns {
+        header_cells
+            .push(Cell::from(setting.column.header()).style(Style::default().fg(header_color)));
+        if let (DealsColumn::Price, Some(currency)) = (setting.column, converted_header) {
+            header_cells.push(
+                Cell::from(format!("≈ {}", currency)).style(Style::default().fg(header_color)),
+            );
+        }
+    }
+    let header = Row::new(header_cells);
 
     // Build table rows
+    let no_color = model.accessibility.no_color;
+    let now = Utc::now();
     let rows: Vec<Row> = filtered_deals
         .iter()
         .map(|deal| {
-            let price_str = format!("{}{:.2}", deal.price.currency_symbol(), deal.price.amount);
-            let discount_str = format!("-{}%", deal.price.discount);
-
-            let is_atl = deal
-                .history_low
-                .map(|low| (low - deal.price.amount).abs() < 0.01)
-                .unwrap_or(false);
+            let is_atl = deal.is_historical_low();
 
-            let (item_title_color, price_color, discount_color) = if dimmed {
+            let ignored = model.lists.is_ignored(&deal.id);
+            let (item_title_color, price_color, discount_color) = if dimmed || ignored {
                 (text_dimmed(), text_dimmed(), text_dimmed())
             } else if is_atl {
                 (text_secondary(), primary(), primary())
@@ -116,20 +134,113 @@ pub fn render_deals_list(frame: &mut Frame, model: &mut Model, area: Rect, dimme
             } else {
                 (text_secondary(), text_secondary(), text_secondary())
             };
-
-            let atl_cell = if is_atl {
-                let atl_color = if dimmed { text_dimmed() } else { primary() };
-                Cell::from("ATL").style(Style::default().fg(atl_color).add_modifier(Modifier::BOLD))
+            let other_color = if dimmed || ignored {
+                text_dimmed()
             } else {
-                Cell::from("")
+                text_secondary()
+            };
+
+            // Without colour, statuses are spelled out with letters
+            let selected = model.selection.contains(&deal.key());
+            let (status_symbol, status_color) = match (model.seen.status(deal), no_color) {
+                _ if selected => ("✓", accent()),
+                (DealStatus::New, false) => ("●", accent()),
+                (DealStatus::New, true) => ("N", accent()),
+                (DealStatus::PriceDropped, false) => ("▼", green()),
+                (DealStatus::PriceDropped, true) => ("v", green()),
+                (DealStatus::Seen, _) => ("", text_secondary()),
             };
+            let status_color = if dimmed { text_dimmed() } else { status_color };
+            let status_cell = Cell::from(status_symbol).style(Style::default().fg(status_color));
 
-            Row::new(vec![
-                Cell::from(deal.title.clone()).style(Style::default().fg(item_title_color)),
-                Cell::from(price_str).style(Style::default().fg(price_color)),
-                Cell::from(discount_str).style(Style::default().fg(discount_color)),
-                atl_cell,
-            ])
+            let info = model.game_info_cache.get(&deal.id);
+            let money = |money: Option<Money>| {
+                Cell::from(money.map(|m| model.format_money(m)).unwrap_or_default())
+                    .style(Style::default().fg(other_color))
+            };
+            let text = |text: String| Cell::from(text).style(Style::default().fg(other_color));
+
+            let mut cells = vec![status_cell];
+            for setting in &columns {
+                let cell = match setting.column {
+                    DealsColumn::Title => {
+                        let title_color = if selected && !dimmed {
+                            accent()
+                        } else {
+                            item_title_color
+                        };
+                        let mut title_spans = Vec::new();
+                        if model.lists.is_watched(&deal.id) {
+                            let star_color = if dimmed { text_dimmed() } else { yellow() };
+                            title_spans.push(Span::styled("★ ", Style::default().fg(star_color)));
+                        }
+                        title_spans.push(Span::raw(deal.title.clone()));
+                        Cell::from(Line::from(title_spans)).style(Style::default().fg(title_color))
+                    }
+                    DealsColumn::Shop => text(deal.shop.name.clone()),
+                    DealsColumn::Price => Cell::from(model.format_money(deal.price.amount))
+                        .style(Style::default().fg(price_color)),
+                    DealsColumn::Regular => money(Some(deal.regular_price)),
+                    DealsColumn::Savings => {
+                        money(deal.regular_price.checked_sub(deal.price.amount))
+                    }
+                    DealsColumn::Cut => {
+                        // Without colour, big discounts stand out in bold instead
+                        let discount_style = if no_color && deal.price.discount >= 75 {
+                            Style::default()
+                                .fg(discount_color)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(discount_color)
+                        };
+                        Cell::from(format!("-{}%", deal.price.discount)).style(discount_style)
+                    }
+                    DealsColumn::Atl if is_atl => {
+                        let atl_color = if dimmed { text_dimmed() } else { primary() };
+                        Cell::from("ATL")
+                            .style(Style::default().fg(atl_color).add_modifier(Modifier::BOLD))
+                    }
+                    DealsColumn::Atl => Cell::from(""),
+                    DealsColumn::HistoryLow => money(deal.history_low),
+                    DealsColumn::StoreLow => money(deal.store_low),
+                    DealsColumn::Expiry => text(
+                        deal.expiry
+                            .as_deref()
+                            .map(|expiry| time_left(expiry, now))
+                            .unwrap_or_default(),
+                    ),
+                    DealsColumn::ReleaseDate => text(
+                        info.and_then(|info| info.release_date.clone())
+                            .unwrap_or_default(),
+                    ),
+                    DealsColumn::Tags => {
+                        text(info.map(|info| info.tags.join(", ")).unwrap_or_default())
+                    }
+                    DealsColumn::Reviews => text(
+                        info.and_then(|info| {
+                            info.reviews.iter().find_map(|review| {
+                                Some(format!("{} {}", review.score?, review.source))
+                            })
+                        })
+                        .unwrap_or_default(),
+                    ),
+                    DealsColumn::Bundled => text(
+                        info.and_then(|info| info.bundled)
+                            .map(|count| count.to_string())
+                            .unwrap_or_default(),
+                    ),
+                    DealsColumn::Url => text(deal.url.clone()),
+                };
+                cells.push(cell);
+                if setting.column == DealsColumn::Price && converted_header.is_some() {
+                    let converted = model
+                        .convert(deal.price.amount)
+                        .map(|converted| model.format_money(converted))
+                        .unwrap_or_default();
+                    cells.push(Cell::from(converted).style(Style::default().fg(other_color)));
+                }
+            }
+            Row::new(cells)
         })
         .collect();
 
@@ -163,12 +274,13 @@ pub fn render_deals_list(frame: &mut Frame, model: &mut Model, area: Rect, dimme
             .add_modifier(Modifier::BOLD),
     );
 
-    let widths = [
-        Constraint::Min(20),
-        Constraint::Length(10),
-        Constraint::Length(7),
-        Constraint::Length(4),
-    ];
+    let mut widths = vec![Constraint::Length(1)];
+    for setting in &columns {
+        widths.push(setting.constraint());
+        if setting.column == DealsColumn::Price && converted_header.is_some() {
+            widths.push(Constraint::Length(10));
+        }
+    }
 
     let table = Table::new(rows, widths)
         .header(header)
@@ -213,6 +325,20 @@ pub fn render_deals_list(frame: &mut Frame, model: &mut Model, area: Rect, dimme
     frame.render_stateful_widget(scrollbar, scrollbar_area, &mut scrollbar_state);
 }
 
+/// Time until a deal ends, e.g. `2d 5h`, `3h 20m` or `45m`
+fn time_left(expiry: &str, now: DateTime<Utc>) -> String {
+    let Ok(expiry) = DateTime::parse_from_rfc3339(expiry) else {
+        return String::new();
+    };
+    let minutes = (expiry.with_timezone(&Utc) - now).num_minutes();
+    match minutes {
+        ..=0 => "ended".to_string(),
+        1..=59 => format!("{}m", minutes),
+        60..=1439 => format!("{}h {}m", minutes / 60, minutes % 60),
+        _ => format!("{}d {}h", minutes / 1440, minutes % 1440 / 60),
+    }
+}
+
 /// Build status bar line with btop-style highlighted shortcut keys and separators
 fn build_status_line(model: &Model, dimmed: bool) -> Line<'static> {
     let text_color = if dimmed {
@@ -230,12 +356,36 @@ fn build_status_line(model: &Model, dimmed: bool) -> Line<'static> {
     } else {
         text_primary()
     };
-    let border_color = if dimmed { text_dimmed() } else { accent() };
+    let border_color = pane_border(model.ui.focus == Pane::Deals, dimmed);
 
     let mut spans: Vec<Span> = Vec::new();
 
     spans.push(Span::styled("┘", Style::default().fg(border_color)));
 
+    // A status message replaces the shortcuts until it expires
+    if let Some(status) = &model.ui.status {
+        let error_color = if dimmed { text_dimmed() } else { error_red() };
+        spans.push(Span::styled(
+            format!(" {} ", status.text),
+            Style::default().fg(error_color),
+        ));
+        spans.push(Span::styled("└", Style::default().fg(border_color)));
+        return Line::from(spans);
+    }
+
+    // Selection, counting deals the filters hide apart
+    if !model.selection.is_empty() {
+        let shown = model.selected_deals().len();
+        let hidden = model.selection.len() - shown;
+        let label = if hidden > 0 {
+            format!("{} selected, {} hidden", shown, hidden)
+        } else {
+            format!("{} selected", shown)
+        };
+        spans.push(Span::styled(label, Style::default().fg(value_color)));
+        spans.push(Span::styled("└┘", Style::default().fg(border_color)));
+    }
+
     // Filter
     if model.filter.active {
         spans.push(Span::styled("f ", Style::default().fg(sc_color)));
@@ -245,12 +395,26 @@ fn build_status_line(model: &Model, dimmed: bool) -> Line<'static> {
         ));
         spans.push(Span::styled("_", Style::default().fg(text_color)));
         spans.push(Span::styled(" ⏎", Style::default().fg(sc_color)));
-    } else if let Some(query) = &model.active_search_query {
+        if let Some(error) = &model.filter.error {
+            let error_color = if dimmed { text_dimmed() } else { error_red() };
+            spans.push(Span::styled(
+                format!(" {}", error),
+                Style::default().fg(error_color),
+            ));
+        }
+    } else if !model.filter.applied.is_empty() {
         spans.push(Span::styled("f", Style::default().fg(sc_color)));
         spans.push(Span::styled(
-            format!("[{}] ", query),
+            format!("[{}] ", model.filter.applied),
             Style::default().fg(value_color),
         ));
+        // Deals are left out until the game info of tag terms comes in
+        if model.loading.game_info_batch && model.query.needs_game_info() {
+            spans.push(Span::styled(
+                "loading tags… ",
+                Style::default().fg(text_color),
+            ));
+        }
         spans.push(Span::styled("c", Style::default().fg(sc_color)));
         spans.push(Span::styled("lear", Style::default().fg(text_color)));
     } else {
@@ -260,6 +424,24 @@ fn build_status_line(model: &Model, dimmed: bool) -> Line<'static> {
 
     spans.push(Span::styled("└┘", Style::default().fg(border_color)));
 
+    // New since last visit
+    spans.push(Span::styled("n", Style::default().fg(sc_color)));
+    spans.push(Span::styled("ew", Style::default().fg(text_color)));
+    let new_count = model.new_deals_count();
+    if model.new_only {
+        spans.push(Span::styled(
+            format!("[only {}]", new_count),
+            Style::default().fg(value_color),
+        ));
+    } else if new_count > 0 {
+        spans.push(Span::styled(
+            format!("[{}]", new_count),
+            Style::default().fg(value_color),
+        ));
+    }
+
+    spans.push(Span::styled("└┘", Style::default().fg(border_color)));
+
     // Platform
     spans.push(Span::styled("p", Style::default().fg(sc_color)));
     spans.push(Span::styled("latform", Style::default().fg(text_color)));
@@ -282,18 +464,23 @@ fn build_status_line(model: &Model, dimmed: bool) -> Line<'static> {
     spans.push(Span::styled("ort[", Style::default().fg(text_color)));
     spans.push(Span::styled("←", Style::default().fg(sc_color)));
     spans.push(Span::styled(
-        model.sort_state.criteria.name().to_string(),
-        Style::default().fg(value_color),
-    ));
-    spans.push(Span::styled(
-        model.sort_state.direction.arrow().to_string(),
+        model.sort_label(),
         Style::default().fg(value_color),
     ));
+    if model.is_search_mode() {
+        spans.push(Span::styled(" local", Style::default().fg(text_color)));
+    }
     spans.push(Span::styled("→", Style::default().fg(sc_color)));
     spans.push(Span::styled("]", Style::default().fg(text_color)));
 
     spans.push(Span::styled("└┘", Style::default().fg(border_color)));
 
+    // Presets
+    spans.push(Span::styled("v", Style::default().fg(sc_color)));
+    spans.push(Span::styled("iews", Style::default().fg(text_color)));
+
+    spans.push(Span::styled("└┘", Style::default().fg(border_color)));
+
     // Refresh
     spans.push(Span::styled("r", Style::default().fg(sc_color)));
     spans.push(Span::styled("efresh", Style::default().fg(text_color)));
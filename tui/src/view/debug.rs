@@ -0,0 +1,152 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::popups::centered_rect;
+use super::styles::*;
+use crate::model::{HitCount, Model};
+
+/// Recent API requests, background tasks and cache hit rates, over
+/// everything else
+pub fn render_debug_overlay(frame: &mut Frame, model: &Model) {
+    let popup_area = centered_rect(frame.area(), 76, 30);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            " Debug ",
+            Style::default().fg(primary_light()),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(primary()));
+    frame.render_widget(block, popup_area);
+
+    let inner = Rect::new(
+        popup_area.x + 1,
+        popup_area.y + 1,
+        popup_area.width.saturating_sub(2),
+        popup_area.height.saturating_sub(2),
+    );
+
+    let tasks_height = model.debug.tasks.len() as u16 + 1;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(tasks_height),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(2),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let mut tasks = vec![heading("Tasks")];
+    for (name, state) in &model.debug.tasks {
+        let color = if state == "idle" {
+            text_dimmed()
+        } else {
+            yellow()
+        };
+        tasks.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<15}", name),
+                Style::default().fg(text_secondary()),
+            ),
+            Span::styled(state.clone(), Style::default().fg(color)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(tasks), chunks[0]);
+
+    let stats = &model.cache_stats;
+    let cache = vec![
+        heading("Cache hits on selection"),
+        hit_rate_line("Game info", stats.game_info, model.game_info_cache.len()),
+        hit_rate_line(
+            "Price history",
+            stats.price_history,
+            model.price_history_cache.len(),
+        ),
+    ];
+    frame.render_widget(Paragraph::new(cache), chunks[2]);
+
+    // Newest first, as many as fit
+    let mut requests = vec![heading("Requests")];
+    if model.debug.requests.is_empty() {
+        requests.push(Line::from(Span::styled(
+            "  No requests yet",
+            Style::default().fg(text_dimmed()),
+        )));
+    }
+    for request in &model.debug.requests {
+        let (outcome, color) = match (request.status, &request.error) {
+            (Some(status), None) if status < 400 => (status.to_string(), green()),
+            (Some(status), _) => (status.to_string(), error_red()),
+            (None, Some(error)) => (error.clone(), error_red()),
+            (None, None) => ("—".to_string(), text_dimmed()),
+        };
+        requests.push(Line::from(vec![
+            Span::styled(
+                format!("  {} ", request.at.format("%H:%M:%S")),
+                Style::default().fg(text_dimmed()),
+            ),
+            Span::styled(
+                format!("{:<18}", request.endpoint),
+                Style::default().fg(text_primary()),
+            ),
+            Span::styled(
+                format!("{:>6} ms  ", request.latency_ms),
+                Style::default().fg(text_secondary()),
+            ),
+            Span::styled(outcome, Style::default().fg(color)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(requests), chunks[4]);
+
+    let log_dir = match &model.debug.log_dir {
+        Some(dir) => format!(" Logs: {}", dir.display()),
+        None => " Logs: no state directory, not written".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            log_dir,
+            Style::default().fg(text_secondary()),
+        ))),
+        chunks[5],
+    );
+}
+
+fn heading(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        format!(" {}", text),
+        Style::default()
+            .fg(primary_light())
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn hit_rate_line(name: &str, count: HitCount, cached: usize) -> Line<'static> {
+    let rate = match count.rate() {
+        Some(rate) => format!("{:>5.1}%", rate * 100.0),
+        None => "     —".to_string(),
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("  {:<15}", name),
+            Style::default().fg(text_secondary()),
+        ),
+        Span::styled(rate, Style::default().fg(text_primary())),
+        Span::styled(
+            format!(
+                "  {} hits, {} misses, {} cached",
+                count.hits, count.misses, cached
+            ),
+            Style::default().fg(text_dimmed()),
+        ),
+    ])
+}
@@ -6,10 +6,11 @@ use ratatui::{
     Frame,
 };
 
+use super::hyperlinks::Hyperlink;
 use super::styles::*;
-use crate::model::Model;
+use crate::model::{Model, Pane};
 
-pub fn render_game_details(frame: &mut Frame, model: &Model, area: Rect, dimmed: bool) {
+pub fn render_game_details(frame: &mut Frame, model: &mut Model, area: Rect, dimmed: bool) {
     let text_color = if dimmed {
         text_dimmed()
     } else {
@@ -20,7 +21,7 @@ pub fn render_game_details(frame: &mut Frame, model: &Model, area: Rect, dimmed:
     } else {
         primary_light()
     };
-    let border_color = if dimmed { text_dimmed() } else { accent() };
+    let border_color = pane_border(model.ui.focus == Pane::Details, dimmed);
     let title_color = if dimmed {
         text_dimmed()
     } else {
@@ -58,10 +59,7 @@ pub fn render_game_details(frame: &mut Frame, model: &Model, area: Rect, dimmed:
     let is_loading = model.loading.game_info.as_ref() == Some(&deal.id);
     let mut lines: Vec<Line> = Vec::new();
 
-    let is_atl = deal
-        .history_low
-        .map(|low| (low - deal.price.amount).abs() < 0.01)
-        .unwrap_or(false);
+    let is_atl = deal.is_historical_low();
 
     // ATL badge
     if is_atl {
@@ -121,9 +119,33 @@ pub fn render_game_details(frame: &mut Frame, model: &Model, area: Rect, dimmed:
         Span::styled(&deal.shop.name, Style::default().fg(text_color)),
     ]));
 
+    // Link, clickable in terminals with hyperlinks
+    let link_label = "Link: ";
+    let link_row = lines.len() as u16;
+    lines.push(Line::from(vec![
+        Span::styled(link_label, Style::default().fg(label_color)),
+        Span::styled(
+            &deal.url,
+            Style::default()
+                .fg(secondary_color)
+                .add_modifier(Modifier::UNDERLINED),
+        ),
+    ]));
+    let inner = block.inner(area);
+    let link_x = inner.x + link_label.len() as u16;
+    let link = (link_row < inner.height && link_x < inner.right()).then(|| Hyperlink {
+        area: Rect::new(
+            link_x,
+            inner.y + link_row,
+            (deal.url.len() as u16).min(inner.right() - link_x),
+            1,
+        ),
+        url: deal.url.clone(),
+    });
+
     // Price section
-    let regular_str = format!("{}{:.2}", deal.price.currency_symbol(), deal.regular_price);
-    let price_str = format!("{}{:.2}", deal.price.currency_symbol(), deal.price.amount);
+    let regular_str = model.format_money(deal.regular_price);
+    let price_str = model.format_money(deal.price.amount);
     let discount_str = format!("-{}%", deal.price.discount);
     let price_color = if is_atl { purple_color } else { green_color };
 
@@ -147,10 +169,28 @@ pub fn render_game_details(frame: &mut Frame, model: &Model, area: Rect, dimmed:
         ),
     ]));
 
+    // Same prices in the display currency
+    if let (Some(regular), Some(price)) = (
+        model.convert(deal.regular_price),
+        model.convert(deal.price.amount),
+    ) {
+        lines.push(Line::from(vec![
+            Span::styled("≈ ", Style::default().fg(secondary_color)),
+            Span::styled(
+                model.format_money(regular),
+                Style::default()
+                    .fg(secondary_color)
+                    .add_modifier(Modifier::CROSSED_OUT),
+            ),
+            Span::styled(" -> ", Style::default().fg(secondary_color)),
+            Span::styled(model.format_money(price), Style::default().fg(text_color)),
+        ]));
+    }
+
     // Savings
-    let savings = deal.regular_price - deal.price.amount;
-    if savings > 0.0 {
-        let savings_str = format!("{}{:.2}", deal.price.currency_symbol(), savings);
+    let savings = deal.regular_price.checked_sub(deal.price.amount);
+    if let Some(savings) = savings.filter(|savings| savings.minor() > 0) {
+        let savings_str = model.format_money(savings);
         lines.push(Line::from(vec![
             Span::styled("You save ", Style::default().fg(secondary_color)),
             Span::styled(
@@ -164,7 +204,7 @@ pub fn render_game_details(frame: &mut Frame, model: &Model, area: Rect, dimmed:
 
     // History low
     if let Some(low) = deal.history_low {
-        let low_str = format!("{}{:.2}", deal.price.currency_symbol(), low);
+        let low_str = model.format_money(low);
         let low_price_color = if is_atl { purple_color } else { text_color };
         lines.push(Line::from(vec![
             Span::styled("History low: ", Style::default().fg(label_color)),
@@ -197,4 +237,5 @@ pub fn render_game_details(frame: &mut Frame, model: &Model, area: Rect, dimmed:
 
     let paragraph = Paragraph::new(lines).block(block);
     frame.render_widget(paragraph, area);
+    model.ui.layout.links.extend(link);
 }
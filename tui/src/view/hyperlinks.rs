@@ -0,0 +1,38 @@
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use super::accessibility::Accessibility;
+
+/// A URL shown on screen, made clickable with an OSC 8 hyperlink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    pub area: Rect,
+    pub url: String,
+}
+
+/// Wrap the cells of each link in OSC 8 escape sequences. ratatui has no
+/// hyperlinks, so this is done on the rendered frame like `accessibility::apply`,
+/// which also keeps them out of the snapshots.
+pub fn apply(buffer: &mut Buffer, links: &[Hyperlink], profile: Accessibility) {
+    // A serial console or screen reader would print the sequences
+    if profile.ascii {
+        return;
+    }
+
+    for link in links {
+        let area = link.area.intersection(buffer.area);
+        for y in area.top()..area.bottom() {
+            // The buffer diff doesn't redraw the cell after one whose symbol
+            // is wider than a character, so each sequence carries two cells.
+            // Links are URLs, whose characters are all one cell wide.
+            for x in (area.left()..area.right()).step_by(2) {
+                let mut text = buffer[(x, y)].symbol().to_string();
+                if x + 1 < area.right() {
+                    text.push_str(buffer[(x + 1, y)].symbol());
+                    buffer[(x + 1, y)].set_skip(true);
+                }
+                buffer[(x, y)]
+                    .set_symbol(&format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", link.url, text));
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+use crate::model::{Pane, UiState};
+
+/// Narrower than this, the details move below the deals list
+const WIDE_MIN_WIDTH: u16 = 100;
+/// Narrow and shorter than this, only the deals list is shown
+const STACKED_MIN_HEIGHT: u16 = 30;
+/// Side panel shorter than this hides the price chart
+const CHART_MIN_HEIGHT: u16 = 24;
+/// Narrower than this, details and chart don't fit side by side
+const SIDE_BY_SIDE_MIN_WIDTH: u16 = 80;
+
+/// Arrangement of the main panes for a terminal size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Deals list left, details and chart right
+    Wide,
+    /// Deals list on top, details and chart below
+    Stacked,
+    /// Deals list only, details shown as an overlay on demand
+    ListOnly,
+}
+
+impl LayoutMode {
+    pub fn for_area(area: Rect) -> Self {
+        if area.width >= WIDE_MIN_WIDTH {
+            LayoutMode::Wide
+        } else if area.height >= STACKED_MIN_HEIGHT {
+            LayoutMode::Stacked
+        } else {
+            LayoutMode::ListOnly
+        }
+    }
+}
+
+/// Where each main pane goes; hidden panes have no area
+#[derive(Debug, Clone, Default)]
+pub struct MainLayout {
+    pub deals: Rect,
+    pub details: Option<Rect>,
+    pub chart: Option<Rect>,
+    /// Details and chart are drawn over the deals list
+    pub overlay: bool,
+    /// Panes this layout can show, in focus order, whether or not one is maximized
+    pub panes: Vec<Pane>,
+}
+
+impl MainLayout {
+    fn visible_panes(&self) -> Vec<Pane> {
+        let mut panes = vec![Pane::Deals];
+        if self.details.is_some() {
+            panes.push(Pane::Details);
+        }
+        if self.chart.is_some() {
+            panes.push(Pane::Chart);
+        }
+        panes
+    }
+}
+
+/// Compute the main layout for the terminal area and UI state
+pub fn main_layout(area: Rect, ui: &UiState) -> MainLayout {
+    let mut layout = match LayoutMode::for_area(area) {
+        LayoutMode::Wide => {
+            // Split horizontal: 55% left (deals), 45% right (details + chart)
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(area);
+            let (details, chart) = split_vertical(columns[1]);
+            MainLayout {
+                deals: columns[0],
+                details: Some(details),
+                chart,
+                ..MainLayout::default()
+            }
+        }
+        LayoutMode::Stacked => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(area);
+            let (details, chart) = split_horizontal(rows[1]);
+            MainLayout {
+                deals: rows[0],
+                details: Some(details),
+                chart,
+                ..MainLayout::default()
+            }
+        }
+        LayoutMode::ListOnly => {
+            let mut layout = MainLayout {
+                deals: area,
+                ..MainLayout::default()
+            };
+            if ui.show_details {
+                // Cover the bottom of the list, keeping the selected rows visible above
+                let height = (area.height * 6 / 10).max(area.height.min(10));
+                let overlay = Rect::new(area.x, area.bottom() - height, area.width, height);
+                let (details, chart) = split_horizontal(overlay);
+                layout.details = Some(details);
+                layout.chart = chart;
+                layout.overlay = true;
+            }
+            layout
+        }
+    };
+
+    layout.panes = layout.visible_panes();
+
+    // A maximized pane takes the whole area
+    if ui.maximized && layout.panes.contains(&ui.focus) {
+        layout.details = None;
+        layout.chart = None;
+        layout.overlay = false;
+        match ui.focus {
+            Pane::Deals => layout.deals = area,
+            Pane::Details => layout.details = Some(area),
+            Pane::Chart => layout.chart = Some(area),
+        }
+        if ui.focus != Pane::Deals {
+            layout.deals = Rect::default();
+        }
+    }
+
+    layout
+}
+
+/// Details above the chart, or details only when too short for the chart
+fn split_vertical(area: Rect) -> (Rect, Option<Rect>) {
+    if area.height < CHART_MIN_HEIGHT {
+        return (area, None);
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+    (rows[0], Some(rows[1]))
+}
+
+/// Details beside the chart, or details only when too narrow for both
+fn split_horizontal(area: Rect) -> (Rect, Option<Rect>) {
+    if area.width < SIDE_BY_SIDE_MIN_WIDTH {
+        return (area, None);
+    }
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    (columns[0], Some(columns[1]))
+}
@@ -1,20 +1,29 @@
+pub mod accessibility;
 pub mod deals_list;
+pub mod debug;
 pub mod game_details;
+pub mod hyperlinks;
+pub mod layout;
 pub mod popups;
 pub mod price_chart;
+pub mod regions;
 pub mod styles;
 
+#[cfg(test)]
+pub(crate) mod tests;
+
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
     style::Style,
-    widgets::Block,
+    widgets::{Block, Clear},
     Frame,
 };
 
-use crate::model::{Model, Popup};
+use crate::model::{Model, Pane, Popup};
 use styles::bg_dark;
 
 pub fn view(frame: &mut Frame, model: &mut Model) {
+    styles::set_frame_palette(model.palette);
+
     // Fill entire screen with theme background
     let bg_block = Block::default().style(Style::default().bg(bg_dark()));
     frame.render_widget(bg_block, frame.area());
@@ -29,33 +38,51 @@ pub fn view(frame: &mut Frame, model: &mut Model) {
     match model.ui.popup {
         Popup::None => {}
         Popup::Options => popups::render_options_popup(frame, model),
-        Popup::Keybinds => popups::render_keybinds_popup(frame),
+        Popup::Keybinds => popups::render_keybinds_popup(frame, model),
         Popup::Platform => popups::render_platform_popup(frame, model),
         Popup::PriceFilter => popups::render_price_filter_popup(frame, model),
+        Popup::Presets => popups::render_presets_popup(frame, model),
+        Popup::Regions => regions::render_regions_popup(frame, model),
+        Popup::Export => popups::render_export_popup(frame, model),
+        Popup::Yank => popups::render_yank_popup(frame, model),
     }
-}
 
-fn render_main(frame: &mut Frame, model: &mut Model, dimmed: bool) {
-    let area = frame.area();
+    if model.ui.show_debug {
+        debug::render_debug_overlay(frame, model);
+    }
 
-    // Split horizontal: 55% left (deals), 45% right (details + chart)
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
-        .split(area);
+    // Links under an overlay would leak into it
+    if model.ui.popup != Popup::None || model.ui.show_menu || model.ui.show_debug {
+        model.ui.layout.links.clear();
+    }
+}
 
-    let left_panel = main_chunks[0];
+fn render_main(frame: &mut Frame, model: &mut Model, dimmed: bool) {
+    let layout = layout::main_layout(frame.area(), &model.ui);
 
-    // Right panel: split vertical - details (40%), chart (60%)
-    let right_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-        .split(main_chunks[1]);
+    // Focus falls back to the list when its pane no longer fits
+    if !layout.panes.contains(&model.ui.focus) {
+        model.ui.focus = Pane::Deals;
+    }
 
-    let details_panel = right_chunks[0];
-    let chart_panel = right_chunks[1];
+    model.ui.layout.deals = layout.deals;
+    model.ui.layout.details = layout.details.unwrap_or_default();
+    model.ui.layout.chart = layout.chart.unwrap_or_default();
+    model.ui.layout.panes = layout.panes.clone();
+    model.ui.layout.links.clear();
 
-    deals_list::render_deals_list(frame, model, left_panel, dimmed);
-    game_details::render_game_details(frame, model, details_panel, dimmed);
-    price_chart::render_price_chart(frame, model, chart_panel, dimmed);
+    if !layout.deals.is_empty() {
+        deals_list::render_deals_list(frame, model, layout.deals, dimmed);
+    }
+    if layout.overlay {
+        for area in [layout.details, layout.chart].into_iter().flatten() {
+            frame.render_widget(Clear, area);
+        }
+    }
+    if let Some(area) = layout.details {
+        game_details::render_game_details(frame, model, area, dimmed);
+    }
+    if let Some(area) = layout.chart {
+        price_chart::render_price_chart(frame, model, area, dimmed);
+    }
 }
@@ -2,11 +2,14 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
 use super::styles::*;
+use crate::clipboard::YankItem;
+use crate::columns::DealsColumn;
+use crate::export::ExportFormat;
 use crate::model::{MenuItem, Model, OptionsTab};
 use dealve_core::models::{Platform, Region};
 
@@ -22,7 +25,7 @@ pub fn render_menu_overlay(frame: &mut Frame, model: &Model) {
     let start_y = area.height.saturating_sub(total_height) / 2;
 
     let logo_x = area.width.saturating_sub(logo_width) / 2;
-    let logo_area = Rect::new(logo_x, start_y, logo_width, logo_height);
+    let logo_area = Rect::new(logo_x, start_y, logo_width, logo_height).intersection(area);
 
     frame.render_widget(Clear, logo_area);
 
@@ -35,7 +38,7 @@ pub fn render_menu_overlay(frame: &mut Frame, model: &Model) {
 
     let menu_x = area.width.saturating_sub(menu_width) / 2;
     let menu_y = start_y + logo_height + 1;
-    let menu_area = Rect::new(menu_x, menu_y, menu_width, menu_height);
+    let menu_area = Rect::new(menu_x, menu_y, menu_width, menu_height).intersection(area);
 
     frame.render_widget(Clear, menu_area);
 
@@ -69,13 +72,11 @@ pub fn render_menu_overlay(frame: &mut Frame, model: &Model) {
     frame.render_widget(menu, menu_area);
 }
 
-pub fn render_options_popup(frame: &mut Frame, model: &Model) {
+pub fn render_options_popup(frame: &mut Frame, model: &mut Model) {
     let area = frame.area();
     let popup_width = 60u16;
     let popup_height = 26u16;
-    let popup_x = area.width.saturating_sub(popup_width) / 2;
-    let popup_y = area.height.saturating_sub(popup_height) / 2;
-    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+    let popup_area = centered_rect(area, popup_width, popup_height);
 
     frame.render_widget(Clear, popup_area);
 
@@ -122,6 +123,18 @@ pub fn render_options_popup(frame: &mut Frame, model: &Model) {
         })
         .collect();
 
+    // Remember where each tab label landed for mouse clicks
+    let mut tab_x = chunks[0].x;
+    model.ui.layout.options_tabs = tabs
+        .iter()
+        .map(|tab| {
+            let width = tab.width() as u16;
+            let rect = Rect::new(tab_x, chunks[0].y, width, 1);
+            tab_x += width;
+            rect
+        })
+        .collect();
+
     let tabs_line = Line::from(tabs);
     let tabs_para = Paragraph::new(tabs_line);
     frame.render_widget(tabs_para, chunks[0]);
@@ -132,6 +145,7 @@ pub fn render_options_popup(frame: &mut Frame, model: &Model) {
         OptionsTab::Platforms => render_platforms_tab(frame, model, content_area),
         OptionsTab::Advanced => render_advanced_tab(frame, model, content_area),
         OptionsTab::Theme => render_theme_tab(frame, model, content_area),
+        OptionsTab::Columns => render_columns_tab(frame, model, content_area),
     }
 }
 
@@ -145,10 +159,38 @@ fn render_region_tab(frame: &mut Frame, model: &Model, area: Rect) {
         ])
         .split(area);
 
-    let desc = Paragraph::new(Line::from(Span::styled(
-        "Select your region for local prices:",
-        Style::default().fg(text_secondary()),
-    )));
+    let rates = if model.loading.rates {
+        format!("updating rates {}", model.spinner_char())
+    } else if model.rates_file.is_none() {
+        "built-in rates".to_string()
+    } else {
+        let today = chrono::Local::now().date_naive();
+        match model.exchange_rates.date() {
+            Some(date) if model.exchange_rates.is_stale(today) => {
+                format!("rates of {}, press r to update", date)
+            }
+            Some(date) => format!("rates of {}", date),
+            None => "saved rates".to_string(),
+        }
+    };
+    let currency = match model.options.display_currency {
+        Some(currency) => currency.code().to_string(),
+        None => "none".to_string(),
+    };
+    let desc = Paragraph::new(vec![
+        Line::from(Span::styled(
+            "Select your region for local prices:",
+            Style::default().fg(text_secondary()),
+        )),
+        Line::from(vec![
+            Span::styled("Display currency: ", Style::default().fg(text_secondary())),
+            Span::styled(currency, Style::default().fg(primary_light())),
+            Span::styled(
+                format!(" ({})", rates),
+                Style::default().fg(text_secondary()),
+            ),
+        ]),
+    ]);
     frame.render_widget(desc, chunks[0]);
 
     let mut region_lines: Vec<Line> = Vec::new();
@@ -176,6 +218,11 @@ fn render_region_tab(frame: &mut Frame, model: &Model, area: Rect) {
 
         let is_selected = model.options.region_list_index == i;
         let is_current = model.options.region == *region;
+        let compared = if model.options.compare_regions.contains(region) {
+            " ✓"
+        } else {
+            ""
+        };
 
         let marker = if is_current { "●" } else { "○" };
         let line_style = if is_selected {
@@ -187,7 +234,13 @@ fn render_region_tab(frame: &mut Frame, model: &Model, area: Rect) {
         };
 
         region_lines.push(Line::from(Span::styled(
-            format!(" {} {} ({})", marker, region.name(), region.code()),
+            format!(
+                " {} {} ({}){}",
+                marker,
+                region.name(),
+                region.code(),
+                compared
+            ),
             line_style,
         )));
     }
@@ -213,10 +266,16 @@ fn render_region_tab(frame: &mut Frame, model: &Model, area: Rect) {
         .scroll((scroll_offset, 0));
     frame.render_widget(region_list, chunks[1]);
 
-    let help = Paragraph::new(Line::from(Span::styled(
-        "[Enter] Select  [Tab] Switch tab  [Esc] Close",
-        Style::default().fg(text_secondary()),
-    )));
+    let help = Paragraph::new(vec![
+        Line::from(Span::styled(
+            "[Enter] Select  [c] Compare  [Tab] Switch tab  [Esc] Close",
+            Style::default().fg(text_secondary()),
+        )),
+        Line::from(Span::styled(
+            "[d] Display currency  [r] Update exchange rates",
+            Style::default().fg(text_secondary()),
+        )),
+    ]);
     frame.render_widget(help, chunks[2]);
 }
 
@@ -345,6 +404,26 @@ fn render_advanced_tab(frame: &mut Frame, model: &Model, area: Rect) {
             format!("{}ms", model.options.game_info_delay_ms),
             "Debounce delay",
         ),
+        (
+            "Accessible",
+            if model.options.accessibility {
+                "On"
+            } else {
+                "Off"
+            }
+            .to_string(),
+            "ASCII, no colour",
+        ),
+        (
+            "Animations",
+            if model.options.animations {
+                "On"
+            } else {
+                "Off"
+            }
+            .to_string(),
+            "Transitions",
+        ),
     ];
 
     let mut setting_lines: Vec<Line> = Vec::new();
@@ -406,26 +485,113 @@ fn render_advanced_tab(frame: &mut Frame, model: &Model, area: Rect) {
     frame.render_widget(help, chunks[2]);
 }
 
-fn render_theme_tab(frame: &mut Frame, model: &Model, area: Rect) {
+fn render_columns_tab(frame: &mut Frame, model: &Model, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),
+            Constraint::Length(2),
             Constraint::Min(5),
             Constraint::Length(2),
         ])
         .split(area);
 
     let desc = Paragraph::new(Line::from(Span::styled(
-        "Select a color theme:",
+        "Columns of the deals table, in order, and their width:",
+        Style::default().fg(text_secondary()),
+    )));
+    frame.render_widget(desc, chunks[0]);
+
+    let mut column_lines: Vec<Line> = Vec::new();
+    for (i, setting) in model.options.columns.iter().enumerate() {
+        let is_selected = model.options.columns_list_index == i;
+        let checkbox = if setting.visible { "[x]" } else { "[ ]" };
+
+        let line_style = if is_selected {
+            Style::default().fg(text_primary()).bg(accent())
+        } else if setting.visible {
+            Style::default().fg(text_primary())
+        } else {
+            Style::default().fg(text_dimmed())
+        };
+        // The title takes whatever is left of the row
+        let width = if setting.column == DealsColumn::Title {
+            format!("≥{}", setting.width())
+        } else {
+            setting.width().to_string()
+        };
+        let width_color = if setting.width.is_some() {
+            primary_light()
+        } else {
+            text_dimmed()
+        };
+
+        column_lines.push(Line::from(vec![
+            Span::styled(
+                format!(" {} {:<20}", checkbox, setting.column.name()),
+                line_style,
+            ),
+            Span::styled(format!("{:>4}", width), Style::default().fg(width_color)),
+        ]));
+    }
+
+    // Calculate scroll offset
+    let visible_height = chunks[1].height.saturating_sub(2) as usize;
+    let scroll_offset = if model.options.columns_list_index >= visible_height {
+        (model.options.columns_list_index - visible_height + 1) as u16
+    } else {
+        0
+    };
+
+    let column_list = Paragraph::new(column_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(accent()))
+                .title(Span::styled(
+                    " Deals Table ",
+                    Style::default().fg(primary_light()),
+                )),
+        )
+        .scroll((scroll_offset, 0));
+    frame.render_widget(column_list, chunks[1]);
+
+    let help = Paragraph::new(Line::from(Span::styled(
+        "[Enter] Show/hide  [J/K] Move  [+/-] Width  [Esc] Close",
         Style::default().fg(text_secondary()),
     )));
+    frame.render_widget(help, chunks[2]);
+}
+
+fn render_theme_tab(frame: &mut Frame, model: &Model, area: Rect) {
+    // Theme file errors are listed under the themes, a few lines at most
+    let error_height = model.themes.errors.len().min(3) as u16;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(error_height),
+            Constraint::Length(2),
+        ])
+        .split(area);
+
+    let desc = Paragraph::new(vec![
+        Line::from(Span::styled(
+            "Select a color theme:",
+            Style::default().fg(text_secondary()),
+        )),
+        Line::from(Span::styled(
+            "Custom themes are read from ~/.config/dealve/themes/",
+            Style::default().fg(text_dimmed()),
+        )),
+    ]);
     frame.render_widget(desc, chunks[0]);
 
     let mut theme_lines: Vec<Line> = Vec::new();
-    for (i, theme) in Theme::ALL.iter().enumerate() {
+    for (i, theme) in model.themes.entries().iter().enumerate() {
         let is_selected = model.options.theme_list_index == i;
-        let is_current = model.options.theme == *theme;
+        let is_current = model.options.theme == theme.id;
 
         let marker = if is_current { "●" } else { "○" };
 
@@ -438,15 +604,19 @@ fn render_theme_tab(frame: &mut Frame, model: &Model, area: Rect) {
         };
 
         // Show color preview swatches including background colors
-        let p = theme.palette();
+        let p = theme.palette;
         let preview = Line::from(vec![
-            Span::styled(format!(" {} {:<20}", marker, theme.name()), line_style),
+            Span::styled(format!(" {} {:<20}", marker, theme.name), line_style),
             Span::styled("██", Style::default().fg(p.bg)),
             Span::styled("██", Style::default().fg(p.bg_highlight)),
             Span::styled("██", Style::default().fg(p.primary)),
             Span::styled("██", Style::default().fg(p.green)),
             Span::styled("██", Style::default().fg(p.yellow)),
             Span::styled("██", Style::default().fg(p.accent)),
+            Span::styled(
+                if theme.path.is_some() { " file" } else { "" },
+                Style::default().fg(text_dimmed()),
+            ),
         ]);
         theme_lines.push(preview);
     }
@@ -472,66 +642,119 @@ fn render_theme_tab(frame: &mut Frame, model: &Model, area: Rect) {
         .scroll((scroll_offset, 0));
     frame.render_widget(theme_list, chunks[1]);
 
+    let error_lines: Vec<Line> = model
+        .themes
+        .errors
+        .iter()
+        .map(|error| {
+            Line::from(Span::styled(
+                format!("! {}", error),
+                Style::default().fg(error_red()),
+            ))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(error_lines), chunks[2]);
+
     let help = Paragraph::new(Line::from(Span::styled(
         "[Enter] Select  [Tab] Switch tab  [Esc] Close",
         Style::default().fg(text_secondary()),
     )));
-    frame.render_widget(help, chunks[2]);
+    frame.render_widget(help, chunks[3]);
 }
 
-pub fn render_keybinds_popup(frame: &mut Frame) {
+pub fn render_keybinds_popup(frame: &mut Frame, model: &Model) {
     let area = frame.area();
-    let popup_width = 45u16;
-    let popup_height = 17u16;
-    let popup_x = area.width.saturating_sub(popup_width) / 2;
-    let popup_y = area.height.saturating_sub(popup_height) / 2;
-    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+    let popup_width = 52u16;
+    let popup_height = 24u16;
+    let popup_area = centered_rect(area, popup_width, popup_height);
 
     frame.render_widget(Clear, popup_area);
 
-    let content = vec![
-        "",
-        "  [Up/Down] or [j/k]  Navigate",
-        "  [PgUp/PgDown]       Page scroll",
-        "  [Home/End]          First/Last deal",
-        "  [Enter]             Open deal / Select",
-        "  [f]                 Filter by name",
-        "  [c]                 Clear filter",
-        "  [$]                 Price filter",
-        "  [p]                 Change platform",
-        "  [s]                 Toggle sort direction",
-        "  [Left/Right]        Change sort criteria",
-        "  [r]                 Refresh deals",
-        "  [Esc]               Menu / Close popup",
-        "  [q]                 Quit (from menu)",
-        "",
-        "  [Esc] Close",
-    ];
+    let block = Block::default()
+        .title(Span::styled(
+            " Keybinds ",
+            Style::default().fg(primary_light()),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(primary()));
+    frame.render_widget(block, popup_area);
 
-    let popup = Paragraph::new(content.join("\n"))
-        .style(Style::default().fg(text_primary()))
-        .block(
-            Block::default()
-                .title(Span::styled(
-                    " Keybinds ",
-                    Style::default().fg(primary_light()),
-                ))
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(primary())),
-        );
+    let inner = Rect::new(
+        popup_area.x + 1,
+        popup_area.y + 1,
+        popup_area.width.saturating_sub(2),
+        popup_area.height.saturating_sub(2),
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    // Generated from the active keymap so it always matches what keys do
+    let mut lines: Vec<Line> = Vec::new();
+    let mut current_context = None;
+    for (context, keys, description) in model.keymap.help_entries() {
+        if current_context != Some(context) {
+            if current_context.is_some() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                format!(" {}", context.name()),
+                Style::default()
+                    .fg(primary_light())
+                    .add_modifier(Modifier::BOLD),
+            )));
+            current_context = Some(context);
+        }
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<18}", format!("[{}]", keys)),
+                Style::default().fg(shortcut_key()),
+            ),
+            Span::styled(description, Style::default().fg(text_primary())),
+        ]));
+    }
+
+    if !model.keymap.warnings.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            " Keymap warnings",
+            Style::default()
+                .fg(error_red())
+                .add_modifier(Modifier::BOLD),
+        )));
+        for warning in &model.keymap.warnings {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", warning),
+                Style::default().fg(error_red()),
+            )));
+        }
+    }
+
+    let visible_height = chunks[0].height as usize;
+    let max_scroll = lines.len().saturating_sub(visible_height);
+    let scroll_offset = model.ui.keybinds_scroll.min(max_scroll) as u16;
+
+    let content = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll_offset, 0));
+    frame.render_widget(content, chunks[0]);
 
-    frame.render_widget(popup, popup_area);
+    let help = Paragraph::new(Line::from(Span::styled(
+        "[j/k] Scroll  [Esc] Close  keymap.toml to remap",
+        Style::default().fg(text_secondary()),
+    )));
+    frame.render_widget(help, chunks[1]);
 }
 
-pub fn render_platform_popup(frame: &mut Frame, model: &Model) {
+pub fn render_platform_popup(frame: &mut Frame, model: &mut Model) {
     let area = frame.area();
     let enabled_platforms = model.enabled_platforms();
 
     let popup_width = 35u16;
     let popup_height = (enabled_platforms.len() as u16 + 5).min(20);
-    let popup_x = area.width.saturating_sub(popup_width) / 2;
-    let popup_y = area.height.saturating_sub(popup_height) / 2;
-    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+    let popup_area = centered_rect(area, popup_width, popup_height);
 
     frame.render_widget(Clear, popup_area);
 
@@ -584,6 +807,9 @@ pub fn render_platform_popup(frame: &mut Frame, model: &Model) {
         0
     };
 
+    model.ui.layout.popup_list = chunks[0];
+    model.ui.layout.popup_scroll = scroll_offset as usize;
+
     let platform_list = Paragraph::new(platform_lines).scroll((scroll_offset, 0));
     frame.render_widget(platform_list, chunks[0]);
 
@@ -598,17 +824,17 @@ pub fn render_price_filter_popup(frame: &mut Frame, model: &Model) {
     let area = frame.area();
     let popup_width = 32u16;
     let popup_height = 10u16;
-    let popup_x = area.width.saturating_sub(popup_width) / 2;
-    let popup_y = area.height.saturating_sub(popup_height) / 2;
-    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+    let popup_area = centered_rect(area, popup_width, popup_height);
 
     frame.render_widget(Clear, popup_area);
 
+    // Bounds are read in the display currency when there is one
+    let title = match model.display_currency {
+        Some(currency) => format!(" Price Filter ({}) ", currency),
+        None => " Price Filter ".to_string(),
+    };
     let block = Block::default()
-        .title(Span::styled(
-            " Price Filter ",
-            Style::default().fg(primary_light()),
-        ))
+        .title(Span::styled(title, Style::default().fg(primary_light())))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(accent()));
     frame.render_widget(block, popup_area);
@@ -665,3 +891,302 @@ pub fn render_price_filter_popup(frame: &mut Frame, model: &Model) {
     let paragraph = Paragraph::new(content);
     frame.render_widget(paragraph, inner);
 }
+
+pub fn render_presets_popup(frame: &mut Frame, model: &Model) {
+    let area = frame.area();
+    let popup_width = 56u16;
+    let popup_height = (model.presets.len().max(1) as u16 + 6).min(20);
+    let popup_area = centered_rect(area, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            " Presets ",
+            Style::default().fg(primary_light()),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent()));
+    frame.render_widget(block, popup_area);
+
+    let inner = Rect::new(
+        popup_area.x + 1,
+        popup_area.y + 1,
+        popup_area.width - 2,
+        popup_area.height - 2,
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let mut preset_lines: Vec<Line> = Vec::new();
+    if model.presets.is_empty() {
+        preset_lines.push(Line::from(Span::styled(
+            " No presets yet, press [a] to save this view",
+            Style::default().fg(text_secondary()),
+        )));
+    }
+    for (i, preset) in model.presets.iter().enumerate() {
+        let is_selected = model.ui.preset_popup_index == i;
+        let is_current = model.active_preset.as_ref() == Some(&preset.name);
+
+        let marker = if is_current { "●" } else { "○" };
+        let number = if i < 9 {
+            format!("{}", i + 1)
+        } else {
+            " ".to_string()
+        };
+        let line_style = if is_selected {
+            Style::default().fg(text_primary()).bg(accent())
+        } else if is_current {
+            Style::default().fg(primary_light())
+        } else {
+            Style::default().fg(text_primary())
+        };
+        let summary_style = if is_selected {
+            Style::default().fg(text_secondary()).bg(accent())
+        } else {
+            Style::default().fg(text_secondary())
+        };
+
+        let sort = preset.get_sort();
+        let mut summary = format!(
+            " {} · {} · {}{}",
+            preset.region,
+            preset.get_platform().name(),
+            sort.criteria.name(),
+            sort.direction.arrow()
+        );
+        if !preset.query.is_empty() {
+            summary.push_str(&format!(" · {}", preset.query));
+        }
+
+        preset_lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", number), Style::default().fg(shortcut_key())),
+            Span::styled(format!("{} {}", marker, preset.name), line_style),
+            Span::styled(summary, summary_style),
+        ]));
+    }
+
+    // Calculate scroll offset
+    let visible_height = chunks[0].height as usize;
+    let scroll_offset = if model.ui.preset_popup_index >= visible_height {
+        (model.ui.preset_popup_index - visible_height + 1) as u16
+    } else {
+        0
+    };
+
+    let preset_list = Paragraph::new(preset_lines).scroll((scroll_offset, 0));
+    frame.render_widget(preset_list, chunks[0]);
+
+    if let Some(name) = &model.ui.preset_name_input {
+        let name_line = Line::from(vec![
+            Span::styled("Save as: ", Style::default().fg(primary_light())),
+            Span::styled(
+                format!("{}▋", name),
+                Style::default().fg(text_primary()).bg(bg_highlight()),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(name_line), chunks[1]);
+    }
+
+    let help_text = if model.ui.preset_name_input.is_some() {
+        "[Enter] Save  [Esc] Cancel"
+    } else {
+        "[Enter/1-9] Apply  [a] Save view  [d] Delete"
+    };
+    let help = Paragraph::new(Line::from(Span::styled(
+        help_text,
+        Style::default().fg(text_secondary()),
+    )));
+    frame.render_widget(help, chunks[2]);
+}
+
+pub fn render_export_popup(frame: &mut Frame, model: &Model) {
+    let area = frame.area();
+    let popup_width = 56u16;
+    let popup_height = DealsColumn::EXPORT.len() as u16 + 7;
+    let popup_area = centered_rect(area, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            " Export Deals ",
+            Style::default().fg(primary_light()),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent()));
+    frame.render_widget(block, popup_area);
+
+    let inner = Rect::new(
+        popup_area.x + 1,
+        popup_area.y + 1,
+        popup_area.width - 2,
+        popup_area.height - 2,
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    // Formats on one line, the chosen one highlighted
+    let label = if model.selected_deals().is_empty() {
+        format!(" {} deals as ", model.filtered_deals().len())
+    } else {
+        format!(" {} selected deals as ", model.selected_deals().len())
+    };
+    let mut format_spans = vec![Span::styled(label, Style::default().fg(text_secondary()))];
+    for format in ExportFormat::ALL {
+        let style = if *format == model.export.format {
+            Style::default().fg(text_primary()).bg(accent())
+        } else {
+            Style::default().fg(text_secondary())
+        };
+        format_spans.push(Span::styled(format!(" {} ", format.name()), style));
+    }
+    frame.render_widget(Paragraph::new(Line::from(format_spans)), chunks[0]);
+
+    // Columns with their position in the file
+    let column_lines: Vec<Line> = DealsColumn::EXPORT
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let is_selected = model.export.path_input.is_none() && model.export.cursor == i;
+            let position = model.export.columns.iter().position(|c| c == column);
+            let checkbox = match position {
+                Some(position) => format!("[{}]", position + 1),
+                None => "[ ]".to_string(),
+            };
+            let style = if is_selected {
+                Style::default().fg(text_primary()).bg(accent())
+            } else if position.is_some() {
+                Style::default().fg(text_primary())
+            } else {
+                Style::default().fg(text_secondary())
+            };
+            let prefix = if is_selected { " > " } else { "   " };
+            Line::from(vec![
+                Span::styled(prefix, Style::default().fg(primary_light())),
+                Span::styled(format!("{} {}", checkbox, column.export_header()), style),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(column_lines), chunks[1]);
+
+    if let Some(path) = &model.export.path_input {
+        let path_line = Line::from(vec![
+            Span::styled(" Save to: ", Style::default().fg(primary_light())),
+            Span::styled(
+                format!("{}▋", path),
+                Style::default().fg(text_primary()).bg(bg_highlight()),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(vec![Line::from(""), path_line]), chunks[2]);
+    }
+
+    let help_text = if model.export.path_input.is_some() {
+        "[Enter] Export  [Esc] Cancel"
+    } else {
+        "[Space] Column  [Tab] Format  [Enter] Export  [Esc] Close"
+    };
+    let help = Paragraph::new(Line::from(Span::styled(
+        help_text,
+        Style::default().fg(text_secondary()),
+    )));
+    frame.render_widget(help, chunks[3]);
+}
+
+pub fn render_yank_popup(frame: &mut Frame, model: &Model) {
+    // The first of the selected deals stands for the others
+    let deals = model.batch_deals();
+    let Some(deal) = deals.first() else {
+        return;
+    };
+    let area = frame.area();
+    let popup_width = 64u16;
+    let popup_height = YankItem::ALL.len() as u16 + 4;
+    let popup_area = centered_rect(area, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            if deals.len() > 1 {
+                format!(" Copy from {} deals ", deals.len())
+            } else {
+                " Copy ".to_string()
+            },
+            Style::default().fg(primary_light()),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent()));
+    frame.render_widget(block, popup_area);
+
+    let inner = Rect::new(
+        popup_area.x + 1,
+        popup_area.y + 1,
+        popup_area.width - 2,
+        popup_area.height - 2,
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    // Each item with the text it copies, cut off by the popup's edge
+    let item_lines: Vec<Line> = YankItem::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let is_selected = model.ui.yank_popup_index == i;
+            let text = item.text(deal, model.region.money_format());
+            let name_style = if is_selected {
+                Style::default().fg(text_primary()).bg(accent())
+            } else {
+                Style::default().fg(text_primary())
+            };
+            let prefix = if is_selected { " > " } else { "   " };
+            Line::from(vec![
+                Span::styled(prefix, Style::default().fg(primary_light())),
+                Span::styled(format!("{:<10}", item.name()), name_style),
+                Span::styled(
+                    format!("  {}", text.as_deref().unwrap_or("unknown")),
+                    Style::default().fg(text_secondary()),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(item_lines), chunks[0]);
+
+    let help = Paragraph::new(Line::from(Span::styled(
+        "[Enter/y] Copy  [Esc] Close",
+        Style::default().fg(text_secondary()),
+    )));
+    frame.render_widget(help, chunks[1]);
+}
+
+/// Centered popup area, shrunk to fit small terminals
+pub(super) fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
@@ -7,7 +7,8 @@ use ratatui::{
 };
 
 use super::styles::*;
-use crate::model::Model;
+use crate::model::{Model, Pane};
+use dealve_core::models::PriceHistoryPoint;
 
 pub fn render_price_chart(frame: &mut Frame, model: &Model, area: Rect, dimmed: bool) {
     let text_color = if dimmed {
@@ -15,7 +16,7 @@ pub fn render_price_chart(frame: &mut Frame, model: &Model, area: Rect, dimmed:
     } else {
         text_secondary()
     };
-    let border_color = if dimmed { text_dimmed() } else { accent() };
+    let border_color = pane_border(model.ui.focus == Pane::Chart, dimmed);
     let title_color = if dimmed {
         text_dimmed()
     } else {
@@ -37,20 +38,22 @@ pub fn render_price_chart(frame: &mut Frame, model: &Model, area: Rect, dimmed:
             return;
         }
 
-        // Convert prices to u64 (cents) for Sparkline
-        let data: Vec<u64> = points.iter().map(|p| (p.price * 100.0) as u64).collect();
-
-        let min_price = points.iter().map(|p| p.price).fold(f64::INFINITY, f64::min);
-        let max_price = points
+        // Minor units (cents) for the Sparkline
+        let data: Vec<u64> = points
             .iter()
-            .map(|p| p.price)
-            .fold(f64::NEG_INFINITY, f64::max);
-        let current_price = points.last().map(|p| p.price).unwrap_or(0.0);
+            .map(|p| p.price.minor().max(0) as u64)
+            .collect();
 
-        let currency = model
-            .selected_deal()
-            .map(|d| d.price.currency_symbol())
-            .unwrap_or("€");
+        // A history is in a single currency, the region's
+        let by_amount = |p: &&PriceHistoryPoint| p.price.minor();
+        let price = |point: Option<&PriceHistoryPoint>| {
+            point
+                .map(|p| model.format_money(p.price))
+                .unwrap_or_default()
+        };
+        let min_price = price(points.iter().min_by_key(by_amount));
+        let max_price = price(points.iter().max_by_key(by_amount));
+        let current_price = price(points.last());
 
         // Render block and get inner area
         let inner = block.inner(area);
@@ -70,22 +73,41 @@ pub fn render_price_chart(frame: &mut Frame, model: &Model, area: Rect, dimmed:
         } else {
             text_primary()
         };
-        let summary = Line::from(vec![
-            Span::styled(
-                format!("Low: {}{:.2}", currency, min_price),
-                Style::default().fg(low_color),
-            ),
-            Span::styled("  ", Style::default()),
-            Span::styled(
-                format!("High: {}{:.2}", currency, max_price),
-                Style::default().fg(high_color),
-            ),
-            Span::styled("  ", Style::default()),
-            Span::styled(
-                format!("Now: {}{:.2}", currency, current_price),
-                Style::default().fg(now_color),
-            ),
-        ]);
+        let inspected = model.ui.chart_cursor.and_then(|i| points.get(i));
+        let summary = if let Some(point) = inspected {
+            let date = chrono::DateTime::from_timestamp(point.timestamp, 0)
+                .map(|d| d.format("%b %d, %Y").to_string())
+                .unwrap_or_default();
+            Line::from(vec![
+                Span::styled(date, Style::default().fg(now_color)),
+                Span::styled("  ", Style::default()),
+                Span::styled(
+                    model.format_money(point.price),
+                    Style::default().fg(low_color),
+                ),
+                Span::styled(
+                    format!(" at {}", point.shop_name),
+                    Style::default().fg(text_color),
+                ),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled(
+                    format!("Low: {}", min_price),
+                    Style::default().fg(low_color),
+                ),
+                Span::styled("  ", Style::default()),
+                Span::styled(
+                    format!("High: {}", max_price),
+                    Style::default().fg(high_color),
+                ),
+                Span::styled("  ", Style::default()),
+                Span::styled(
+                    format!("Now: {}", current_price),
+                    Style::default().fg(now_color),
+                ),
+            ])
+        };
         frame.render_widget(Paragraph::new(summary), chunks[0]);
 
         // Sparkline
@@ -93,6 +115,16 @@ pub fn render_price_chart(frame: &mut Frame, model: &Model, area: Rect, dimmed:
             .data(&data)
             .style(Style::default().fg(chart_color));
         frame.render_widget(sparkline, chunks[1]);
+
+        // Highlight the column of the point picked with the mouse
+        if let Some(i) = model.ui.chart_cursor {
+            if i < data.len() && (i as u16) < chunks[1].width {
+                let column = Rect::new(chunks[1].x + i as u16, chunks[1].y, 1, chunks[1].height);
+                frame
+                    .buffer_mut()
+                    .set_style(column, Style::default().bg(bg_highlight()));
+            }
+        }
     } else if model.loading.price_history.is_some() {
         let spinner = model.spinner_char();
         render_empty(
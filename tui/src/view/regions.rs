@@ -0,0 +1,189 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use super::popups::centered_rect;
+use super::styles::*;
+use crate::model::{Model, RegionComparison};
+
+/// The selected game's cheapest offer in each compared region, converted to
+/// a single currency, with the cheapest region and the spread
+pub fn render_regions_popup(frame: &mut Frame, model: &Model) {
+    let Some(comparison) = &model.comparison else {
+        return;
+    };
+    let currency = comparison.currency;
+
+    let count = match &comparison.prices {
+        Some(Ok(prices)) => prices.len(),
+        _ => comparison.regions.len(),
+    };
+    let popup_height = count as u16 + 8;
+    let popup_area = centered_rect(frame.area(), 76, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            " Regional Prices ",
+            Style::default().fg(primary_light()),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent()));
+    frame.render_widget(block, popup_area);
+
+    let inner = Rect::new(
+        popup_area.x + 1,
+        popup_area.y + 1,
+        popup_area.width.saturating_sub(2),
+        popup_area.height.saturating_sub(2),
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let title = Paragraph::new(Line::from(Span::styled(
+        format!(" {}", comparison.title),
+        Style::default()
+            .fg(text_primary())
+            .add_modifier(Modifier::BOLD),
+    )));
+    frame.render_widget(title, chunks[0]);
+
+    let help = Paragraph::new(Line::from(Span::styled(
+        "[Enter] Open offer  [r] Reload  [Esc] Close",
+        Style::default().fg(text_secondary()),
+    )));
+    frame.render_widget(help, chunks[3]);
+
+    let rows = match &comparison.prices {
+        None => {
+            let loading = Paragraph::new(Line::from(Span::styled(
+                format!(
+                    " {} Loading prices in {} regions...",
+                    model.spinner_char(),
+                    comparison.regions.len()
+                ),
+                Style::default().fg(text_secondary()),
+            )));
+            frame.render_widget(loading, chunks[1]);
+            return;
+        }
+        Some(Err(error)) => {
+            let error = Paragraph::new(Line::from(Span::styled(
+                format!(" Error: {}", error),
+                Style::default().fg(error_red()),
+            )));
+            frame.render_widget(error, chunks[1]);
+            return;
+        }
+        Some(Ok(_)) => comparison.rows(&model.exchange_rates),
+    };
+    let spread = RegionComparison::spread(&rows);
+
+    let header_style = Style::default().fg(text_secondary());
+    let header = Row::new(vec![
+        Cell::from("Region").style(header_style),
+        Cell::from("Shop").style(header_style),
+        Cell::from(Line::from("Price").right_aligned()).style(header_style),
+        Cell::from(Line::from(format!("In {}", currency)).right_aligned()).style(header_style),
+        Cell::from(Line::from("vs best").right_aligned()).style(header_style),
+    ]);
+
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .map(|row| {
+            let region = Cell::from(row.region.name().to_string())
+                .style(Style::default().fg(text_primary()));
+            let Some(offer) = row.offer else {
+                return Row::new(vec![
+                    region,
+                    Cell::from("not sold").style(Style::default().fg(text_dimmed())),
+                ]);
+            };
+            let price = offer.price.amount.format(row.region.money_format());
+            let (converted, difference, color) = match (row.converted, &spread) {
+                (Some(converted), Some(spread)) => {
+                    let (difference, color) = if converted == spread.low {
+                        ("best".to_string(), green())
+                    } else {
+                        let percent = spread.percent_over_low(converted);
+                        (format!("+{:.0}%", percent), text_primary())
+                    };
+                    (model.format_money(converted), difference, color)
+                }
+                _ => ("no rate".to_string(), String::new(), text_dimmed()),
+            };
+            Row::new(vec![
+                region,
+                Cell::from(offer.shop.name.clone()).style(Style::default().fg(text_secondary())),
+                Cell::from(Line::from(price).right_aligned())
+                    .style(Style::default().fg(text_primary())),
+                Cell::from(Line::from(converted).right_aligned()).style(Style::default().fg(color)),
+                Cell::from(Line::from(difference).right_aligned())
+                    .style(Style::default().fg(color)),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Min(14),
+        Constraint::Length(16),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(7),
+    ];
+    let table = Table::new(table_rows, widths)
+        .header(header)
+        .row_highlight_style(Style::default().bg(bg_highlight()))
+        .highlight_symbol("> ");
+    let mut state = TableState::default().with_selected(Some(comparison.selected));
+    frame.render_stateful_widget(table, chunks[1], &mut state);
+
+    let summary = match spread {
+        Some(spread) => Line::from(vec![
+            Span::styled(" Cheapest: ", Style::default().fg(text_secondary())),
+            Span::styled(
+                format!(
+                    "{} ({})",
+                    spread.cheapest.name(),
+                    model.format_money(spread.low)
+                ),
+                Style::default().fg(green()).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  Spread: ", Style::default().fg(text_secondary())),
+            Span::styled(
+                format!(
+                    "{} (+{:.0}% in {})",
+                    model.format_money(spread.amount()),
+                    spread.percent_over_low(spread.high),
+                    spread.dearest.name()
+                ),
+                Style::default().fg(text_primary()),
+            ),
+        ]),
+        None if rows.iter().any(|row| row.offer.is_some()) => Line::from(Span::styled(
+            format!(
+                " No exchange rate to {}, update the rates in Options",
+                currency
+            ),
+            Style::default().fg(text_secondary()),
+        )),
+        None => Line::from(Span::styled(
+            " No shop sells this game in these regions",
+            Style::default().fg(text_secondary()),
+        )),
+    };
+    frame.render_widget(Paragraph::new(vec![Line::from(""), summary]), chunks[2]);
+}
@@ -75,10 +75,6 @@ impl Theme {
         }
     }
 
-    pub fn from_id(id: &str) -> Option<Theme> {
-        Theme::ALL.iter().find(|t| t.id() == id).copied()
-    }
-
     pub fn palette(&self) -> ThemePalette {
         match self {
             Theme::Default => ThemePalette {
@@ -211,23 +207,22 @@ impl Theme {
     }
 }
 
-// Active theme (global)
-use std::sync::atomic::{AtomicUsize, Ordering};
-
-static ACTIVE_THEME_INDEX: AtomicUsize = AtomicUsize::new(0);
+// Palette read by the colour accessors while drawing. `view` installs the
+// model's palette at the start of each frame; the default theme until then.
+use std::cell::Cell;
 
-pub fn set_active_theme(theme: Theme) {
-    let idx = Theme::ALL.iter().position(|t| *t == theme).unwrap_or(0);
-    ACTIVE_THEME_INDEX.store(idx, Ordering::Relaxed);
+thread_local! {
+    static FRAME_PALETTE: Cell<Option<ThemePalette>> = const { Cell::new(None) };
 }
 
-pub fn active_theme() -> Theme {
-    let idx = ACTIVE_THEME_INDEX.load(Ordering::Relaxed);
-    Theme::ALL[idx]
+pub fn set_frame_palette(palette: ThemePalette) {
+    FRAME_PALETTE.with(|active| active.set(Some(palette)));
 }
 
 pub fn palette() -> ThemePalette {
-    active_theme().palette()
+    FRAME_PALETTE
+        .with(|active| active.get())
+        .unwrap_or_else(|| Theme::default().palette())
 }
 
 // Convenience color accessors
@@ -277,6 +272,17 @@ pub const ASCII_LOGO: [&str; 6] = [
     "╚═════╝ ╚══════╝╚═╝  ╚═╝╚══════╝ ╚═══╝  ╚══════╝",
 ];
 
+/// Border colour of a main pane, brighter when it has focus
+pub fn pane_border(focused: bool, dimmed: bool) -> Color {
+    if dimmed {
+        text_dimmed()
+    } else if focused {
+        primary_light()
+    } else {
+        accent()
+    }
+}
+
 /// Build a title with btop-style brackets
 pub fn build_title(text: &str, border_color: Color, title_color: Color) -> Line<'static> {
     Line::from(vec![
@@ -292,18 +298,3 @@ pub fn vertical_padding(area_height: u16, text_lines: u16) -> String {
     let padding = inner_height.saturating_sub(text_lines) / 2;
     "\n".repeat(padding as usize)
 }
-
-pub trait CurrencySymbol {
-    fn currency_symbol(&self) -> &str;
-}
-
-impl CurrencySymbol for dealve_core::models::Price {
-    fn currency_symbol(&self) -> &str {
-        match self.currency.as_str() {
-            "USD" => "$",
-            "EUR" => "€",
-            "GBP" => "£",
-            _ => &self.currency,
-        }
-    }
-}
@@ -0,0 +1,297 @@
+//! Snapshot tests for the views.
+//!
+//! Each test builds a `Model` from the fixtures in `tui/fixtures/` (the real
+//! config dir is never read), renders it with a `TestBackend` and compares the
+//! buffer, symbols and styles, with `tui/snapshots/<name>.snap`.
+//!
+//! After an intended UI change, rewrite the snapshots and review the diff:
+//!
+//! ```sh
+//! UPDATE_SNAPSHOTS=1 cargo test -p dealve-tui
+//! git diff tui/snapshots
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::TimeZone;
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+use super::styles::Theme;
+use dealve_core::money::Currency;
+
+use crate::config::{Config, MemoryConfigStore, Preset};
+use crate::fixtures;
+use crate::logging::RequestRecord;
+use crate::model::{Model, OptionsTab, Popup, RegionComparison};
+
+/// Wide (list and side panel), stacked, and list only
+const SIZES: &[(u16, u16)] = &[(120, 40), (80, 40), (80, 24)];
+
+fn fixture_model(theme: Theme) -> Model {
+    let config = Config {
+        theme: theme.id().to_string(),
+        ..Config::default()
+    };
+    let mut model = Model::with_store(Box::new(MemoryConfigStore::new(config)));
+
+    model.deals = fixtures::deals();
+    model.game_info_cache = fixtures::game_info()
+        .into_iter()
+        .map(|info| (info.id.clone(), info))
+        .collect();
+    model.price_history_cache = fixtures::price_history();
+    model.presets = vec![Preset::capture(
+        "Cheap indies".to_string(),
+        model.region,
+        model.platform_filter,
+        model.sort_state,
+        &model.price_filter,
+        "cut>=75 price<10",
+    )];
+    model
+}
+
+fn render(model: &mut Model, width: u16, height: u16) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| super::view(frame, model)).unwrap();
+    terminal.backend().buffer().clone()
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{}.snap", name))
+}
+
+pub(crate) fn assert_snapshot(name: &str, buffer: &Buffer) {
+    let path = snapshot_path(name);
+    let actual = format!("{:?}\n", buffer);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}, run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    if actual != expected {
+        let line = actual
+            .lines()
+            .zip(expected.lines())
+            .position(|(a, e)| a != e)
+            .unwrap_or(0);
+        panic!(
+            "snapshot {} differs at line {}:\n  expected: {}\n    actual: {}\n\
+             run with UPDATE_SNAPSHOTS=1 to accept the change",
+            name,
+            line + 1,
+            expected.lines().nth(line).unwrap_or(""),
+            actual.lines().nth(line).unwrap_or(""),
+        );
+    }
+}
+
+/// Render a view at every size with the default theme
+fn assert_sizes(name: &str, setup: impl Fn(&mut Model)) {
+    for &(width, height) in SIZES {
+        let mut model = fixture_model(Theme::default());
+        setup(&mut model);
+        let buffer = render(&mut model, width, height);
+        assert_snapshot(&format!("{}_{}x{}", name, width, height), &buffer);
+    }
+}
+
+#[test]
+fn main_view() {
+    assert_sizes("main", |_| {});
+}
+
+#[test]
+fn main_view_filtering() {
+    assert_sizes("filter", |model| {
+        model.filter.active = true;
+        model.filter.text = "cut>=75 shop:steam".to_string();
+    });
+}
+
+#[test]
+fn details_overlay() {
+    assert_sizes("details", |model| model.ui.show_details = true);
+}
+
+#[test]
+fn deal_link_is_a_hyperlink() {
+    let url = "https://itad.link/018d937f-07d6-7327-8b0c-ab7bd8e8e2c1/";
+    let mut model = fixture_model(Theme::default());
+    let mut buffer = render(&mut model, 120, 40);
+    let links = model.ui.layout.links.clone();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].url, url);
+
+    // The link covers the URL as far as it's shown
+    let area = links[0].area;
+    let shown: String = (area.left()..area.right())
+        .map(|x| buffer[(x, area.y)].symbol())
+        .collect();
+    assert!(url.starts_with(&shown));
+    assert_eq!(shown.len(), area.width as usize);
+
+    super::hyperlinks::apply(&mut buffer, &links, model.accessibility);
+    assert_eq!(
+        buffer[(area.x, area.y)].symbol(),
+        format!("\x1b]8;;{}\x1b\\ht\x1b]8;;\x1b\\", url)
+    );
+    assert!(buffer[(area.x + 1, area.y)].skip);
+
+    // Nothing to click under a popup
+    model.ui.popup = Popup::Export;
+    render(&mut model, 120, 40);
+    assert!(model.ui.layout.links.is_empty());
+}
+
+#[test]
+fn menu() {
+    assert_sizes("menu", |model| model.ui.show_menu = true);
+}
+
+#[test]
+fn options_popup() {
+    for tab in 0..OptionsTab::ALL.len() {
+        assert_sizes(&format!("options_tab{}", tab), |model| {
+            model.ui.popup = Popup::Options;
+            model.options.current_tab = tab;
+        });
+    }
+}
+
+#[test]
+fn keybinds_popup() {
+    assert_sizes("keybinds", |model| model.ui.popup = Popup::Keybinds);
+}
+
+#[test]
+fn platform_popup() {
+    assert_sizes("platform", |model| model.ui.popup = Popup::Platform);
+}
+
+#[test]
+fn price_filter_popup() {
+    assert_sizes("price_filter", |model| model.ui.popup = Popup::PriceFilter);
+}
+
+#[test]
+fn presets_popup() {
+    assert_sizes("presets", |model| model.ui.popup = Popup::Presets);
+}
+
+#[test]
+fn regions_popup() {
+    assert_sizes("regions", |model| {
+        let deal = model.deals[0].clone();
+        let mut comparison = RegionComparison::new(
+            deal.id.clone(),
+            deal.title,
+            model.options.compare_regions.clone(),
+            deal.price.amount.currency(),
+        );
+        comparison.prices = Some(Ok(fixtures::regional_prices().remove(&deal.id).unwrap()));
+        comparison.selected = 1;
+        model.comparison = Some(comparison);
+        model.ui.popup = Popup::Regions;
+    });
+}
+
+#[test]
+fn export_popup() {
+    assert_sizes("export", |model| {
+        model.ui.popup = Popup::Export;
+        model.export.cursor = 7;
+        model.export.path_input = Some("~/deals.csv".to_string());
+    });
+}
+
+#[test]
+fn yank_popup() {
+    assert_sizes("yank", |model| {
+        model.ui.popup = Popup::Yank;
+        model.ui.yank_popup_index = 3;
+    });
+}
+
+#[test]
+fn configured_columns() {
+    // The expiry countdown is left out: it depends on the time of the run
+    let config = Config {
+        columns: ["shop", "title", "price", "store_low", "reviews"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect(),
+        column_widths: [("shop".to_string(), 6)].into(),
+        ..Config::default()
+    };
+    assert_sizes("columns", |model| {
+        model.options = crate::model::OptionsState::from_config(&config);
+    });
+}
+
+#[test]
+fn display_currency() {
+    assert_sizes("display_currency", |model| {
+        model.display_currency = Currency::new("USD");
+    });
+}
+
+#[test]
+fn themes() {
+    for &theme in Theme::ALL {
+        let mut model = fixture_model(theme);
+        let buffer = render(&mut model, 120, 40);
+        assert_snapshot(&format!("theme_{}", theme.id()), &buffer);
+    }
+}
+
+#[test]
+fn debug_overlay() {
+    assert_sizes("debug", |model| {
+        let at = chrono::Local
+            .with_ymd_and_hms(2026, 3, 14, 9, 30, 0)
+            .unwrap();
+        model.ui.show_debug = true;
+        model.debug.requests = vec![
+            RequestRecord {
+                at,
+                endpoint: "games/prices/v3".to_string(),
+                status: Some(200),
+                latency_ms: 184,
+                error: None,
+            },
+            RequestRecord {
+                at,
+                endpoint: "deals/v2".to_string(),
+                status: Some(429),
+                latency_ms: 96,
+                error: None,
+            },
+            RequestRecord {
+                at,
+                endpoint: "games/info/v2".to_string(),
+                status: None,
+                latency_ms: 10003,
+                error: Some("error sending request".to_string()),
+            },
+        ];
+        model.debug.tasks = vec![
+            ("Deals", "idle".to_string()),
+            ("Game info", "running".to_string()),
+        ];
+        model.debug.log_dir = Some(PathBuf::from("/home/user/.local/state/dealve/logs"));
+        model.cache_stats.game_info.hits = 3;
+        model.cache_stats.game_info.misses = 1;
+    });
+}